@@ -0,0 +1,69 @@
+varying vec2 v_pos;
+
+#ifdef VERTEX_SHADER
+uniform mat3 u_projection_matrix;
+uniform mat3 u_view_matrix;
+uniform mat3 u_model_matrix;
+
+attribute vec2 a_pos;
+
+void main() {
+    v_pos = a_pos;
+    vec3 pos = u_projection_matrix * u_view_matrix * u_model_matrix * vec3(a_pos, 1.0);
+    gl_Position = vec4(pos.xy, 0.0, pos.z);
+}
+#endif
+
+#ifdef FRAGMENT_SHADER
+// Must match `MAX_POLYGON_POINTS` in `src/render/util.rs`
+#define MAX_POINTS 32
+
+uniform vec4 u_color;
+// Distance from the edge at which the light is fully lit
+uniform float u_depth;
+// Inner radius relative to the outer one
+uniform float u_inner;
+// Half of the angle covered by the shape, PI for a full ring
+uniform float u_half_angle;
+// Convex polygon in counter-clockwise order, a ring sector is drawn when empty.
+// Unused points repeat the first one.
+uniform int u_point_count;
+uniform vec2 u_points[MAX_POINTS];
+
+const float HALF_PI = 1.5707963;
+
+// Signed distance to a sector of a ring with outer radius 1
+float ring_sdf(vec2 p) {
+    float r = length(p);
+    float dist = max(u_inner - r, r - 1.0);
+    float angle = abs(atan(p.y, p.x));
+    float dist_angle = r * sin(clamp(angle - u_half_angle, -HALF_PI, HALF_PI));
+    return max(dist, dist_angle);
+}
+
+float edge_sdf(vec2 p, vec2 a, vec2 b) {
+    vec2 edge = b - a;
+    float len = length(edge);
+    if (len == 0.0) {
+        return -1e9;
+    }
+    vec2 normal = vec2(edge.y, -edge.x) / len;
+    return dot(p - a, normal);
+}
+
+// Signed distance to a convex polygon, exact inside of it
+float polygon_sdf(vec2 p) {
+    float dist = edge_sdf(p, u_points[MAX_POINTS - 1], u_points[0]);
+    for (int i = 0; i < MAX_POINTS - 1; i++) {
+        dist = max(dist, edge_sdf(p, u_points[i], u_points[i + 1]));
+    }
+    return dist;
+}
+
+void main() {
+    float dist = u_point_count > 0 ? polygon_sdf(v_pos) : ring_sdf(v_pos);
+    float light = clamp(-dist / u_depth, 0.0, 1.0);
+    vec4 color = u_color * light;
+    gl_FragColor = vec4(color.rgb * color.a, color.a);
+}
+#endif
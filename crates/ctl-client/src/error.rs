@@ -1,4 +1,4 @@
-use ctl_core::prelude::serde_json;
+use ctl_core::{migration::GroupFormatError, prelude::serde_json};
 use reqwest::StatusCode;
 
 #[derive(thiserror::Error, Debug)]
@@ -11,6 +11,8 @@ pub enum ClientError {
     Bincode(#[from] bincode::Error),
     #[error("Unexpected error occurred")]
     Json(#[from] serde_json::Error),
+    #[error("Unsupported group format")]
+    GroupFormat(#[from] GroupFormatError),
     #[error("Unexpected error occurred")]
     Url(#[from] url::ParseError),
     #[error("Unexpected error occurred")]
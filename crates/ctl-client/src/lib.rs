@@ -7,12 +7,12 @@ pub use self::error::*;
 
 pub use ctl_core as core;
 use ctl_core::{
-    prelude::{log, serde_json, DeserializeOwned, Id, MusicInfo, MusicUpdate},
+    prelude::{log, serde_json, DeserializeOwned, Id, MusicFormat, MusicInfo, MusicUpdate},
     types::{GroupInfo, LevelInfo, LevelSet, NewArtist},
-    ScoreEntry, SubmitScore,
+    ScoreEntry, ScoresQuery, SubmitScore,
 };
 
-use core::types::GroupsQuery;
+use core::types::{GroupVersion, GroupsPage, GroupsQuery, QuotaOverride, UserProfile, UserQuota};
 use std::sync::atomic::AtomicBool;
 
 use reqwest::{Client, RequestBuilder, Response, StatusCode, Url};
@@ -78,9 +78,9 @@ impl Nertboard {
         Ok(())
     }
 
-    pub async fn fetch_scores(&self, level: Id) -> Result<Vec<ScoreEntry>> {
+    pub async fn fetch_scores(&self, level: Id, query: &ScoresQuery) -> Result<Vec<ScoreEntry>> {
         let url = self.url.join(&format!("level/{}/scores", level)).unwrap();
-        let req = self.client.get(url);
+        let req = self.client.get(url).query(query);
 
         let response = self.send(req).await?;
         let res = read_json(response).await?;
@@ -110,7 +110,7 @@ impl Nertboard {
 
     pub async fn upload_group(&self, group: &LevelSet) -> Result<GroupInfo> {
         let url = self.url.join("group/create").unwrap();
-        let body = bincode::serialize(group)?;
+        let body = group.encode()?;
         let req = self.client.post(url).body(body);
 
         let response = self.send(req).await?;
@@ -119,7 +119,7 @@ impl Nertboard {
         self.get_group_info(group_id).await
     }
 
-    pub async fn get_group_list(&self, query: &GroupsQuery) -> Result<Vec<GroupInfo>> {
+    pub async fn get_group_list(&self, query: &GroupsQuery) -> Result<GroupsPage> {
         let url = self.url.join("groups").unwrap();
         let req = self.client.get(url).query(&query);
         let response = self.send(req).await?;
@@ -131,6 +131,30 @@ impl Nertboard {
         self.get_json(&format!("group/{}", group)).await
     }
 
+    pub async fn get_user_profile(&self, user: Id) -> Result<UserProfile> {
+        self.get_json(&format!("user/{}", user)).await
+    }
+
+    /// Get the upload quota of the logged in user.
+    pub async fn get_quota(&self) -> Result<UserQuota> {
+        self.get_json("user/me/quota").await
+    }
+
+    pub async fn get_user_quota(&self, user: Id) -> Result<UserQuota> {
+        self.get_json(&format!("user/{}/quota", user)).await
+    }
+
+    /// Override the upload quota of the user, requires admin rights.
+    pub async fn set_user_quota(&self, user: Id, quota: &QuotaOverride) -> Result<()> {
+        let url = self.url.join(&format!("user/{}/quota", user)).unwrap();
+
+        let req = self.client.put(url).json(quota);
+
+        let response = self.send(req).await?;
+        get_body(response).await?;
+        Ok(())
+    }
+
     pub async fn get_music_list(&self) -> Result<Vec<MusicInfo>> {
         self.get_json("music").await
     }
@@ -139,15 +163,24 @@ impl Nertboard {
         self.get_json(&format!("music/{}", music)).await
     }
 
-    pub async fn download_music(&self, music: Id) -> Result<Bytes> {
+    /// Download the music file, in the original format if `format` is not specified.
+    pub async fn download_music(&self, music: Id, format: Option<MusicFormat>) -> Result<Bytes> {
         let url = self.url.join(&format!("music/{}/download", music)).unwrap();
-        let req = self.client.get(url);
+        let mut req = self.client.get(url);
+        if let Some(format) = format {
+            req = req.query(&[("format", format.extension())]);
+        }
 
         let response = self.send(req).await?;
         let response = error_for_status(response).await?;
         Ok(response.bytes().await?)
     }
 
+    /// Get the preview waveform of the music, empty if it has not been analyzed.
+    pub async fn get_music_waveform(&self, music: Id) -> Result<Vec<u8>> {
+        self.get_json(&format!("music/{}/waveform", music)).await
+    }
+
     pub async fn download_group(&self, group: Id) -> Result<Bytes> {
         let url = self.url.join(&format!("group/{}/download", group)).unwrap();
         let req = self.client.get(url);
@@ -157,6 +190,89 @@ impl Nertboard {
         Ok(response.bytes().await?)
     }
 
+    /// List the uploaded versions of the group, oldest first.
+    pub async fn get_group_versions(&self, group: Id) -> Result<Vec<GroupVersion>> {
+        self.get_json(&format!("group/{}/versions", group)).await
+    }
+
+    pub async fn download_group_version(&self, group: Id, version: Id) -> Result<Bytes> {
+        let url = self
+            .url
+            .join(&format!("group/{}/versions/{}/download", group, version))
+            .unwrap();
+        let req = self.client.get(url);
+
+        let response = self.send(req).await?;
+        let response = error_for_status(response).await?;
+        Ok(response.bytes().await?)
+    }
+
+    /// Make an older version of the group the current one.
+    pub async fn rollback_group(&self, group: Id, version: Id) -> Result<()> {
+        let url = self
+            .url
+            .join(&format!("group/{}/versions/{}/rollback", group, version))
+            .unwrap();
+        let req = self.client.post(url);
+
+        let response = self.send(req).await?;
+        get_body(response).await?;
+        Ok(())
+    }
+
+    /// Invite a user to co-author the whole group.
+    pub async fn group_author_add(&self, group: Id, user: Id) -> Result<()> {
+        let url = self.url.join(&format!("group/{}/authors", group)).unwrap();
+
+        let req = self.client.post(url).query(&[("id", user)]);
+
+        let response = self.send(req).await?;
+        get_body(response).await?;
+        Ok(())
+    }
+
+    pub async fn group_author_remove(&self, group: Id, user: Id) -> Result<()> {
+        let url = self.url.join(&format!("group/{}/authors", group)).unwrap();
+
+        let req = self.client.delete(url).query(&[("id", user)]);
+
+        let response = self.send(req).await?;
+        get_body(response).await?;
+        Ok(())
+    }
+
+    /// Make another user the owner of the group.
+    pub async fn group_transfer(&self, group: Id, user: Id) -> Result<()> {
+        let url = self.url.join(&format!("group/{}/owner", group)).unwrap();
+
+        let req = self.client.post(url).query(&[("id", user)]);
+
+        let response = self.send(req).await?;
+        get_body(response).await?;
+        Ok(())
+    }
+
+    /// Invite a user to co-author a single difficulty.
+    pub async fn level_author_add(&self, level: Id, user: Id) -> Result<()> {
+        let url = self.url.join(&format!("level/{}/authors", level)).unwrap();
+
+        let req = self.client.post(url).query(&[("id", user)]);
+
+        let response = self.send(req).await?;
+        get_body(response).await?;
+        Ok(())
+    }
+
+    pub async fn level_author_remove(&self, level: Id, user: Id) -> Result<()> {
+        let url = self.url.join(&format!("level/{}/authors", level)).unwrap();
+
+        let req = self.client.delete(url).query(&[("id", user)]);
+
+        let response = self.send(req).await?;
+        get_body(response).await?;
+        Ok(())
+    }
+
     pub async fn update_music(&self, music: Id, update: &MusicUpdate) -> Result<()> {
         let url = self.url.join(&format!("music/{}", music)).unwrap();
 
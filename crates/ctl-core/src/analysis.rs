@@ -0,0 +1,239 @@
+//! Analysis of decoded music, used to time the levels to it.
+
+use crate::prelude::*;
+
+/// Number of samples in a single frame of the onset envelope.
+const HOP_SIZE: usize = 512;
+/// Slowest tempo that can be detected.
+const MIN_BPM: f32 = 60.0;
+/// Fastest tempo that can be detected.
+const MAX_BPM: f32 = 200.0;
+/// Multiples of the true tempo correlate too, so the ones closer to this are preferred.
+const PREFERRED_BPM: f32 = 120.0;
+/// Time (in seconds) around an onset that it is compared against.
+const ONSET_WINDOW: f32 = 0.1;
+/// How many times an onset has to be stronger than its surroundings.
+const ONSET_THRESHOLD: f32 = 1.5;
+/// Shortest time (in seconds) between two onsets.
+const ONSET_MIN_GAP: f32 = 0.05;
+
+/// How sharply the loudness of the music rises over time.
+/// Peaks of the envelope are the moments where notes start.
+#[derive(Debug, Clone)]
+pub struct OnsetEnvelope {
+    /// Time (in seconds) between consecutive values.
+    pub step: f32,
+    pub values: Vec<f32>,
+}
+
+impl OnsetEnvelope {
+    pub fn new(samples: &[f32], sample_rate: u32) -> Self {
+        let energy: Vec<f32> = samples
+            .chunks(HOP_SIZE)
+            .map(|chunk| {
+                let power =
+                    chunk.iter().map(|sample| sample * sample).sum::<f32>() / chunk.len() as f32;
+                // Compress so that quiet parts still count
+                (1.0 + 100.0 * power).ln()
+            })
+            .collect();
+
+        // Only the rise in energy marks an onset
+        let values = std::iter::once(0.0)
+            .chain(
+                energy
+                    .windows(2)
+                    .map(|window| (window[1] - window[0]).max(0.0)),
+            )
+            .collect();
+
+        Self {
+            step: HOP_SIZE as f32 / sample_rate as f32,
+            values,
+        }
+    }
+
+    /// Estimate the tempo by finding the period at which the onsets repeat the most.
+    pub fn estimate_bpm(&self) -> Option<f32> {
+        let len = self.values.len();
+        let min_lag = ((60.0 / MAX_BPM / self.step).floor() as usize).max(1);
+        let max_lag = (60.0 / MIN_BPM / self.step).ceil() as usize;
+        if max_lag + 1 >= len {
+            return None;
+        }
+
+        let mean = self.values.iter().sum::<f32>() / len as f32;
+        let centered: Vec<f32> = self.values.iter().map(|value| value - mean).collect();
+        let correlation = |lag: usize| {
+            centered
+                .iter()
+                .zip(&centered[lag..])
+                .map(|(a, b)| a * b)
+                .sum::<f32>()
+                / (len - lag) as f32
+        };
+        let lag_bpm = |lag: f32| 60.0 / (lag * self.step);
+
+        let (best_lag, best_score) = (min_lag..=max_lag)
+            .map(|lag| {
+                let octaves = (lag_bpm(lag as f32) / PREFERRED_BPM).log2();
+                let weight = (-0.5 * octaves * octaves).exp();
+                (lag, correlation(lag) * weight)
+            })
+            .max_by(|(_, a), (_, b)| a.total_cmp(b))?;
+        if best_score <= 0.0 {
+            return None;
+        }
+
+        // Fit a parabola through the neighbours to get a fractional lag
+        let mut lag = best_lag as f32;
+        if best_lag > min_lag && best_lag < max_lag {
+            let before = correlation(best_lag - 1);
+            let peak = correlation(best_lag);
+            let after = correlation(best_lag + 1);
+            let curvature = before - 2.0 * peak + after;
+            if curvature < 0.0 {
+                lag += 0.5 * (before - after) / curvature;
+            }
+        }
+
+        Some(lag_bpm(lag))
+    }
+
+    /// Times (in seconds) of the peaks that stand out from their surroundings.
+    pub fn onsets(&self) -> Vec<f32> {
+        let window = ((ONSET_WINDOW / self.step).round() as usize).max(1);
+        let min_gap = ((ONSET_MIN_GAP / self.step).round() as usize).max(1);
+
+        let mut onsets = Vec::new();
+        let mut last: Option<usize> = None;
+        for (frame, &value) in self.values.iter().enumerate() {
+            let start = frame.saturating_sub(window);
+            let end = (frame + window + 1).min(self.values.len());
+            let around = &self.values[start..end];
+
+            let is_max = around.iter().all(|&other| other <= value);
+            let mean = around.iter().sum::<f32>() / around.len() as f32;
+            let stands_out = value > mean * ONSET_THRESHOLD && value > 0.0;
+            let far_enough = match last {
+                Some(last) => frame - last >= min_gap,
+                None => true,
+            };
+            if is_max && stands_out && far_enough {
+                onsets.push(frame as f32 * self.step);
+                last = Some(frame);
+            }
+        }
+        onsets
+    }
+
+    /// Estimate the time (in seconds) of the first beat given the tempo,
+    /// by finding the phase at which the beats line up with the onsets the most.
+    pub fn estimate_offset(&self, bpm: f32) -> f32 {
+        let period = 60.0 / bpm / self.step;
+        if !period.is_finite() || period <= 0.0 {
+            return 0.0;
+        }
+
+        let strength = |phase: usize| {
+            (0..)
+                .map(|i| (phase as f32 + i as f32 * period).round() as usize)
+                .take_while(|&frame| frame < self.values.len())
+                .map(|frame| self.values[frame])
+                .sum::<f32>()
+        };
+
+        let phase = (0..period.ceil() as usize)
+            .map(|phase| (phase, strength(phase)))
+            .max_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map_or(0, |(phase, _)| phase);
+        phase as f32 * self.step
+    }
+}
+
+/// Loudness and onsets of the music over time, shown under the editor timeline.
+#[derive(Debug, Clone)]
+pub struct Waveform {
+    /// Time (in seconds) between consecutive peaks.
+    pub step: f32,
+    /// Peak amplitude of every frame in `0..=1`.
+    pub peaks: Vec<f32>,
+    /// Times (in seconds) where notes start.
+    pub onsets: Vec<f32>,
+}
+
+impl Waveform {
+    pub fn new(samples: &[f32], sample_rate: u32) -> Self {
+        let peaks = samples
+            .chunks(HOP_SIZE)
+            .map(|chunk| {
+                chunk
+                    .iter()
+                    .fold(0.0_f32, |peak, sample| peak.max(sample.abs()))
+                    .min(1.0)
+            })
+            .collect();
+        let envelope = OnsetEnvelope::new(samples, sample_rate);
+        Self {
+            step: envelope.step,
+            peaks,
+            onsets: envelope.onsets(),
+        }
+    }
+
+    /// Peak amplitude over the time interval (in seconds).
+    pub fn peak(&self, from: f32, to: f32) -> f32 {
+        let frame = |time: f32| ((time / self.step).max(0.0) as usize).min(self.peaks.len());
+        let (start, end) = (frame(from), frame(to));
+        if start >= end {
+            return self.peaks.get(start).copied().unwrap_or(0.0);
+        }
+        self.peaks[start..end]
+            .iter()
+            .fold(0.0_f32, |peak, &value| peak.max(value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Short decaying clicks at the given tempo, starting after a lead-in.
+    fn click_track(bpm: f32, lead_in: f32, duration: f32, sample_rate: u32) -> Vec<f32> {
+        let click_length = (0.02 * sample_rate as f32) as usize;
+        let period = 60.0 / bpm;
+        let mut samples = vec![0.0; (duration * sample_rate as f32) as usize];
+        let mut time = lead_in;
+        while time < duration {
+            let start = (time * sample_rate as f32) as usize;
+            for (i, sample) in samples
+                .iter_mut()
+                .skip(start)
+                .take(click_length)
+                .enumerate()
+            {
+                let t = i as f32 / sample_rate as f32;
+                let decay = 1.0 - i as f32 / click_length as f32;
+                *sample = (t * 1000.0 * std::f32::consts::TAU).sin() * decay;
+            }
+            time += period;
+        }
+        samples
+    }
+
+    #[test]
+    fn click_track_tempo_and_offset() {
+        let sample_rate = 44100;
+        let samples = click_track(120.0, 0.25, 20.0, sample_rate);
+        let envelope = OnsetEnvelope::new(&samples, sample_rate);
+
+        let bpm = envelope.estimate_bpm().unwrap();
+        assert!((bpm - 120.0).abs() < 1.0, "estimated {bpm} BPM");
+
+        let offset = envelope.estimate_offset(bpm);
+        assert!(
+            (offset - 0.25).abs() < 2.0 * envelope.step,
+            "estimated offset {offset}"
+        );
+    }
+}
@@ -0,0 +1,134 @@
+//! Decoding of music files.
+
+use crate::types::MusicFormat;
+
+use symphonia::core::{
+    audio::SampleBuffer, codecs::DecoderOptions, errors::Error as SymphoniaError,
+    formats::FormatOptions, io::MediaSourceStream, meta::MetadataOptions, probe::Hint,
+};
+
+#[derive(thiserror::Error, Debug)]
+pub enum AudioError {
+    #[error("no audio track found")]
+    NoTrack,
+    #[error("audio contains no samples")]
+    Empty,
+    #[error("failed to decode audio: {0}")]
+    Decode(#[from] SymphoniaError),
+}
+
+/// Decoded audio with the samples split by channel.
+pub struct Audio {
+    pub sample_rate: u32,
+    pub channels: Vec<Vec<f32>>,
+}
+
+impl Audio {
+    pub fn decode(data: Vec<u8>, format: MusicFormat) -> Result<Self, AudioError> {
+        let source =
+            MediaSourceStream::new(Box::new(std::io::Cursor::new(data)), Default::default());
+        let mut hint = Hint::new();
+        hint.with_extension(format.extension());
+
+        let probed = symphonia::default::get_probe().format(
+            &hint,
+            source,
+            &FormatOptions::default(),
+            &MetadataOptions::default(),
+        )?;
+        let mut reader = probed.format;
+        let track = reader.default_track().ok_or(AudioError::NoTrack)?;
+        let track_id = track.id;
+        let mut sample_rate = track.codec_params.sample_rate;
+        let mut decoder = symphonia::default::get_codecs()
+            .make(&track.codec_params, &DecoderOptions::default())?;
+
+        let mut channels: Vec<Vec<f32>> = Vec::new();
+        loop {
+            let packet = match reader.next_packet() {
+                Ok(packet) => packet,
+                Err(SymphoniaError::IoError(err))
+                    if err.kind() == std::io::ErrorKind::UnexpectedEof =>
+                {
+                    break
+                }
+                Err(err) => return Err(err.into()),
+            };
+            if packet.track_id() != track_id {
+                continue;
+            }
+
+            let decoded = match decoder.decode(&packet) {
+                Ok(decoded) => decoded,
+                // Skip corrupted packets
+                Err(SymphoniaError::DecodeError(_)) => continue,
+                Err(err) => return Err(err.into()),
+            };
+
+            let spec = *decoded.spec();
+            let frames = decoded.frames();
+            if frames == 0 {
+                continue;
+            }
+            sample_rate = Some(spec.rate);
+
+            let mut buffer = SampleBuffer::<f32>::new(decoded.capacity() as u64, spec);
+            buffer.copy_planar_ref(decoded);
+            channels.resize_with(spec.channels.count(), Vec::new);
+            for (channel, samples) in channels.iter_mut().zip(buffer.samples().chunks(frames)) {
+                channel.extend_from_slice(samples);
+            }
+        }
+
+        let sample_rate = sample_rate.ok_or(AudioError::Empty)?;
+        if channels.is_empty() {
+            return Err(AudioError::Empty);
+        }
+        Ok(Self {
+            sample_rate,
+            channels,
+        })
+    }
+
+    /// Number of samples present in every channel,
+    /// channels of a corrupted file may end up with different lengths.
+    pub fn frames(&self) -> usize {
+        self.channels.iter().map(Vec::len).min().unwrap_or(0)
+    }
+
+    /// Mix all channels into one.
+    pub fn mono(&self) -> Vec<f32> {
+        let scale = 1.0 / self.channels.len() as f32;
+        (0..self.frames())
+            .map(|frame| {
+                self.channels
+                    .iter()
+                    .map(|channel| channel[frame])
+                    .sum::<f32>()
+                    * scale
+            })
+            .collect()
+    }
+
+    /// Duration in seconds.
+    pub fn duration(&self) -> f32 {
+        self.frames() as f32 / self.sample_rate as f32
+    }
+
+    /// Peak amplitude over `resolution` equal parts of the track, scaled to `0..=255`.
+    pub fn waveform(&self, resolution: usize) -> Vec<u8> {
+        let frames = self.frames();
+        (0..resolution)
+            .map(|i| {
+                let start = i * frames / resolution;
+                let end = (i + 1) * frames / resolution;
+                let peak = self
+                    .channels
+                    .iter()
+                    .flat_map(|channel| &channel[start..end])
+                    .fold(0.0_f32, |peak, sample| peak.max(sample.abs()));
+                (peak.clamp(0.0, 1.0) * 255.0).round() as u8
+            })
+            .collect()
+    }
+}
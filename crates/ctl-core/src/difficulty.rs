@@ -0,0 +1,246 @@
+//! Estimation of the level difficulty from its content.
+
+use crate::prelude::*;
+
+/// Time (in beats) between the samples of the player's path.
+const SAMPLE_STEP: f32 = 0.25;
+/// Maximum number of samples of the player's path,
+/// the step gets larger for very long levels.
+const MAX_SAMPLES: usize = 20_000;
+/// Shortest telegraph (in seconds) considered, so that instant lights do not dominate the rating.
+const MIN_REACTION_TIME: f32 = 0.1;
+
+/// Weights of the factors in the final rating.
+/// Chosen so that most levels fall roughly between 0 and 10.
+const WEIGHT_LIGHT_SPEED: f32 = 0.15;
+const WEIGHT_DANGER_DENSITY: f32 = 1.5;
+const WEIGHT_REACTION: f32 = 1.0;
+const WEIGHT_WAYPOINT_RATE: f32 = 0.5;
+const WEIGHT_CURSOR_TRAVEL: f32 = 0.3;
+
+/// Measurements that make a level hard to play.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct DifficultyFactors {
+    /// Average speed of the lights (in units per second).
+    pub light_speed: f32,
+    /// Danger lights per second.
+    pub danger_density: f32,
+    /// Average inverse of the telegraph time (in seconds),
+    /// i.e. how fast the player has to react to new lights.
+    pub reaction: f32,
+    /// Waypoints per second.
+    pub waypoint_rate: f32,
+    /// Distance (in units per second) the cursor has to travel to stay on a friendly light.
+    pub cursor_travel: f32,
+}
+
+impl DifficultyFactors {
+    pub fn measure(level: &Level, music: &MusicInfo) -> Self {
+        let first_beat = level
+            .events
+            .iter()
+            .map(|event| event.beat)
+            .min()
+            .unwrap_or(Time::ZERO);
+        // Lights after the end of the music are never seen
+        let last_beat = music
+            .last_beat()
+            .map_or(level.last_beat(), |end| level.last_beat().min(end));
+        let duration = (music.beat_to_time(last_beat) - music.beat_to_time(first_beat))
+            .as_f32()
+            .max(1.0);
+
+        let mut distance = 0.0;
+        let mut moving_time = 0.0;
+        let mut dangers = 0;
+        let mut reaction = 0.0;
+        let mut lights = 0;
+        let mut waypoints = 0;
+
+        for event in &level.events {
+            let Event::Light(light) = &event.event else {
+                continue;
+            };
+            lights += 1;
+            if light.light.danger {
+                dangers += 1;
+            }
+
+            let telegraph = music.beat_to_time(event.beat + light.telegraph.precede_time)
+                - music.beat_to_time(event.beat);
+            reaction += 1.0 / telegraph.as_f32().max(MIN_REACTION_TIME);
+
+            let start = event.beat + light.telegraph.precede_time;
+            let movement = &light.light.movement;
+            waypoints += movement.key_frames.len();
+            for ((_, from, from_time), (_, to, to_time)) in movement
+                .timed_positions()
+                .zip(movement.timed_positions().skip(1))
+            {
+                distance += (to.translation - from.translation).len().as_f32();
+                moving_time += (music.beat_to_time(start + to_time)
+                    - music.beat_to_time(start + from_time))
+                .as_f32();
+            }
+        }
+
+        Self {
+            light_speed: if moving_time > 0.0 {
+                distance / moving_time
+            } else {
+                0.0
+            },
+            danger_density: dangers as f32 / duration,
+            reaction: if lights > 0 {
+                reaction / lights as f32
+            } else {
+                0.0
+            },
+            waypoint_rate: waypoints as f32 / duration,
+            cursor_travel: cursor_travel(level, first_beat, last_beat) / duration,
+        }
+    }
+
+    /// Combine the factors into a single rating.
+    pub fn rating(&self) -> R32 {
+        r32(WEIGHT_LIGHT_SPEED * self.light_speed
+            + WEIGHT_DANGER_DENSITY * self.danger_density
+            + WEIGHT_REACTION * self.reaction
+            + WEIGHT_WAYPOINT_RATE * self.waypoint_rate
+            + WEIGHT_CURSOR_TRAVEL * self.cursor_travel)
+    }
+}
+
+/// Estimate the difficulty of the level, roughly between 0 and 10.
+pub fn rate_difficulty(level: &Level, music: &MusicInfo) -> R32 {
+    DifficultyFactors::measure(level, music).rating()
+}
+
+/// Total distance the cursor travels while following the closest friendly light.
+fn cursor_travel(level: &Level, from: Time, to: Time) -> f32 {
+    if to < from {
+        return 0.0;
+    }
+    let config = LevelConfig::default();
+    let step = r32(SAMPLE_STEP).max((to - from) / r32(MAX_SAMPLES as f32));
+    let samples = ((to - from) / step).as_f32().floor() as usize;
+
+    let mut cursor: Option<vec2<Coord>> = None;
+    let mut travel = 0.0;
+
+    for sample in 0..=samples {
+        let beat = from + step * r32(sample as f32);
+        let state = LevelState::render(level, &config, beat, None);
+        let target = state
+            .lights
+            .iter()
+            .filter(|light| !light.danger)
+            .map(|light| light.collider.position)
+            .min_by_key(|&position| cursor.map_or(Coord::ZERO, |cursor| (position - cursor).len()));
+        if let Some(target) = target {
+            if let Some(cursor) = cursor {
+                travel += (target - cursor).len().as_f32();
+            }
+            cursor = Some(target);
+        }
+    }
+
+    travel
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn light(beat: f32, danger: bool, movement: Movement) -> TimedEvent {
+        TimedEvent {
+            beat: r32(beat),
+            event: Event::Light(LightEvent {
+                light: LightSerde {
+                    danger,
+                    shape: Shape::Circle { radius: r32(1.0) },
+                    movement,
+                },
+                telegraph: Telegraph::default(),
+            }),
+        }
+    }
+
+    fn moving_light(beat: f32, danger: bool, distance: f32) -> TimedEvent {
+        let mut movement = Movement::default();
+        movement.key_frames.push_back(MoveFrame::new(
+            r32(4.0),
+            Transform {
+                translation: vec2(distance, 0.0).as_r32(),
+                ..Transform::identity()
+            },
+        ));
+        light(beat, danger, movement)
+    }
+
+    #[test]
+    fn empty_level() {
+        let factors = DifficultyFactors::measure(&Level { events: vec![] }, &MusicInfo::default());
+        assert_eq!(factors, DifficultyFactors::default());
+        assert_eq!(factors.rating(), R32::ZERO);
+    }
+
+    #[test]
+    fn faster_lights_are_harder() {
+        let music = MusicInfo::default();
+        let slow = Level {
+            events: vec![moving_light(0.0, false, 1.0)],
+        };
+        let fast = Level {
+            events: vec![moving_light(0.0, false, 8.0)],
+        };
+        let slow = DifficultyFactors::measure(&slow, &music);
+        let fast = DifficultyFactors::measure(&fast, &music);
+        assert!(fast.light_speed > slow.light_speed);
+        assert!(fast.cursor_travel > slow.cursor_travel);
+        assert!(fast.rating() > slow.rating());
+    }
+
+    #[test]
+    fn dangers_are_harder() {
+        let music = MusicInfo::default();
+        let calm = Level {
+            events: vec![moving_light(0.0, false, 0.0)],
+        };
+        let dangerous = Level {
+            events: vec![
+                moving_light(0.0, false, 0.0),
+                moving_light(1.0, true, 0.0),
+                moving_light(2.0, true, 0.0),
+            ],
+        };
+        assert!(rate_difficulty(&dangerous, &music) > rate_difficulty(&calm, &music));
+    }
+
+    #[test]
+    fn faster_music_is_harder() {
+        let level = Level {
+            events: vec![moving_light(0.0, false, 4.0), moving_light(0.0, true, 0.0)],
+        };
+        let slow = MusicInfo {
+            bpm: r32(60.0),
+            ..default()
+        };
+        let fast = MusicInfo {
+            bpm: r32(180.0),
+            ..default()
+        };
+        assert!(rate_difficulty(&level, &fast) > rate_difficulty(&level, &slow));
+    }
+
+    #[test]
+    fn long_level_is_sampled() {
+        let level = Level {
+            events: vec![moving_light(0.0, false, 4.0), moving_light(1e7, false, 4.0)],
+        };
+        // Would take 4e7 samples with the default step
+        let start = std::time::Instant::now();
+        rate_difficulty(&level, &MusicInfo::default());
+        assert!(start.elapsed() < std::time::Duration::from_secs(10));
+    }
+}
@@ -1,5 +1,11 @@
+pub mod analysis;
+pub mod audio;
 pub mod auth;
+pub mod difficulty;
+pub mod lint;
+pub mod migration;
 pub mod model;
+pub mod simulation;
 pub mod types;
 pub mod util;
 
@@ -21,15 +27,101 @@ pub mod prelude {
 
 use crate::prelude::*;
 
+/// Category of a score on the leaderboard.
+/// Every category keeps its own best score for each player.
+#[derive(Debug, Default, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(default)]
+pub struct ScoreCategory {
+    pub nofail: bool,
+    pub sudden: bool,
+    pub hidden: bool,
+    pub health: HealthPreset,
+}
+
+impl ScoreCategory {
+    pub fn new(mods: &LevelModifiers, health: &HealthConfig) -> Self {
+        Self {
+            nofail: mods.nofail,
+            sudden: mods.sudden,
+            hidden: mods.hidden,
+            health: health.preset(),
+        }
+    }
+
+    /// The config that runs in this category are played with,
+    /// `None` for custom health that cannot be reproduced.
+    pub fn level_config(&self) -> Option<LevelConfig> {
+        let health = match self.health {
+            HealthPreset::Normal => HealthConfig::preset_normal(),
+            HealthPreset::Custom => return None,
+        };
+        Some(LevelConfig {
+            health,
+            modifiers: LevelModifiers {
+                clean_auto: false,
+                nofail: self.nofail,
+                sudden: self.sudden,
+                hidden: self.hidden,
+            },
+            ..Default::default()
+        })
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub struct ScoreEntry {
     pub user: UserInfo,
     pub score: i32,
     pub extra_info: Option<String>,
+    /// Whether the score has been verified by simulating a replay.
+    #[serde(default)]
+    pub verified: bool,
+    #[serde(default)]
+    pub category: ScoreCategory,
+    /// Position on the leaderboard of the category, starting from 1.
+    #[serde(default)]
+    pub rank: u32,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+/// Filters for the level leaderboard.
+/// Unset fields match any category.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ScoresQuery {
+    pub nofail: Option<bool>,
+    pub sudden: Option<bool>,
+    pub hidden: Option<bool>,
+    pub health: Option<HealthPreset>,
+    /// Maximum number of scores to return.
+    pub limit: Option<u32>,
+    /// Return the scores around this user's position instead of the top ones.
+    pub around: Option<Id>,
+}
+
+impl ScoresQuery {
+    /// Query the scores in exactly that category.
+    pub fn category(category: ScoreCategory) -> Self {
+        Self {
+            nofail: Some(category.nofail),
+            sudden: Some(category.sudden),
+            hidden: Some(category.hidden),
+            health: Some(category.health),
+            ..Default::default()
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SubmitScore {
     pub score: i32,
     pub extra_info: Option<String>,
+    #[serde(default)]
+    pub category: ScoreCategory,
+    #[serde(default)]
+    pub accuracy: f32,
+    #[serde(default)]
+    pub precision: f32,
+    /// Replay of the run used to verify the score.
+    #[serde(default)]
+    pub replay: Option<Replay>,
 }
@@ -0,0 +1,368 @@
+//! Checks for common mistakes in levels.
+
+use crate::prelude::*;
+
+/// Half size of the area that is always visible to the player.
+const VISIBLE_AREA: vec2<f32> = vec2(8.0, 5.0);
+/// Time (in beats) between the checks of the level state.
+const SAMPLE_STEP: f32 = 0.25;
+/// Maximum number of checks of the level state,
+/// the step gets larger for very long levels.
+const MAX_SAMPLES: usize = 20_000;
+/// Shortest time (in seconds) without a friendly light to be reported.
+const MIN_DARKNESS: f32 = 1.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    Warning,
+    /// The level cannot be uploaded.
+    Error,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub kind: DiagnosticKind,
+    /// Beat at which the problem occurs.
+    pub beat: Time,
+    /// Index of the event that causes the problem.
+    pub event: Option<usize>,
+    pub waypoint: Option<WaypointId>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum DiagnosticKind {
+    /// The light appears outside of the visible area.
+    OffScreen,
+    /// The waypoint is reached instantly.
+    ZeroLerpTime,
+    /// The only friendly light is covered by a dangerous one.
+    CoveredLight { danger: usize },
+    /// The event is the same as another one at the same beat.
+    Duplicate { original: usize },
+    /// There is no friendly light for a while.
+    NoLight {
+        /// Duration in beats.
+        duration: Time,
+        /// Whether the player is guaranteed to lose all health.
+        fatal: bool,
+    },
+}
+
+impl Display for DiagnosticKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::OffScreen => write!(f, "Light appears off-screen"),
+            Self::ZeroLerpTime => write!(f, "Waypoint is reached instantly"),
+            Self::CoveredLight { .. } => write!(f, "The only light is covered by danger"),
+            Self::Duplicate { .. } => write!(f, "Duplicate event"),
+            Self::NoLight { duration, .. } => {
+                write!(f, "No light for {:.2} beats", duration.as_f32())
+            }
+        }
+    }
+}
+
+impl Diagnostic {
+    fn warning(kind: DiagnosticKind, beat: Time, event: Option<usize>) -> Self {
+        Self {
+            severity: Severity::Warning,
+            kind,
+            beat,
+            event,
+            waypoint: None,
+        }
+    }
+
+    fn error(kind: DiagnosticKind, beat: Time, event: Option<usize>) -> Self {
+        Self {
+            severity: Severity::Error,
+            ..Self::warning(kind, beat, event)
+        }
+    }
+}
+
+/// Check the level for problems, sorted by the beat at which they occur.
+pub fn lint_level(level: &Level, config: &LevelConfig, music: &MusicInfo) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+    lint_events(level, &mut diagnostics);
+    lint_states(level, config, music, &mut diagnostics);
+    diagnostics.sort_by_key(|diagnostic| diagnostic.beat);
+    diagnostics
+}
+
+/// Whether any of the diagnostics prevents the level from being uploaded.
+pub fn has_errors(diagnostics: &[Diagnostic]) -> bool {
+    diagnostics
+        .iter()
+        .any(|diagnostic| diagnostic.severity == Severity::Error)
+}
+
+/// Checks of the individual events.
+fn lint_events(level: &Level, diagnostics: &mut Vec<Diagnostic>) {
+    let visible = Aabb2::ZERO.extend_symmetric(VISIBLE_AREA).map(r32);
+
+    lint_duplicates(level, diagnostics);
+
+    for (i, event) in level.events.iter().enumerate() {
+        let Event::Light(light) = &event.event else {
+            continue;
+        };
+        let start = event.beat + light.telegraph.precede_time;
+        let movement = &light.light.movement;
+
+        let collider =
+            Collider::new(vec2::ZERO, light.light.shape.clone()).transformed(movement.initial);
+        if !collider.compute_aabb().intersects(&visible) {
+            diagnostics.push(Diagnostic::warning(
+                DiagnosticKind::OffScreen,
+                start,
+                Some(i),
+            ));
+        }
+
+        for (waypoint, (frame, (_, _, time))) in movement
+            .key_frames
+            .iter()
+            .zip(movement.timed_positions().skip(1))
+            .enumerate()
+        {
+            if frame.lerp_time <= Time::ZERO {
+                diagnostics.push(Diagnostic {
+                    waypoint: Some(WaypointId::Frame(waypoint)),
+                    ..Diagnostic::warning(DiagnosticKind::ZeroLerpTime, start + time, Some(i))
+                });
+            }
+        }
+    }
+}
+
+/// Report events equal to an earlier one.
+/// Equal events share the beat, so only the events on the same beat are compared.
+fn lint_duplicates(level: &Level, diagnostics: &mut Vec<Diagnostic>) {
+    let mut order: Vec<usize> = (0..level.events.len()).collect();
+    order.sort_by_key(|&i| (level.events[i].beat, i));
+
+    for same_beat in order.chunk_by(|&a, &b| level.events[a].beat == level.events[b].beat) {
+        for (j, &i) in same_beat.iter().enumerate() {
+            let event = &level.events[i];
+            if let Some(&original) = same_beat[..j]
+                .iter()
+                .find(|&&other| level.events[other] == *event)
+            {
+                diagnostics.push(Diagnostic::warning(
+                    DiagnosticKind::Duplicate { original },
+                    event.beat,
+                    Some(i),
+                ));
+            }
+        }
+    }
+}
+
+/// Checks of the level state over time.
+fn lint_states(
+    level: &Level,
+    config: &LevelConfig,
+    music: &MusicInfo,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    // Lights after the end of the music are never seen
+    let last_beat = music
+        .last_beat()
+        .map_or(level.last_beat(), |end| level.last_beat().min(end));
+    if last_beat < Time::ZERO {
+        return;
+    }
+    let step = r32(SAMPLE_STEP).max(last_beat / r32(MAX_SAMPLES as f32));
+    let samples = (last_beat / step).as_f32().floor() as usize;
+    // Time (in seconds) in darkness after which the player is out of health
+    let fatal_darkness = (config.health.dark_decrease_rate > Time::ZERO)
+        .then(|| config.health.max / config.health.dark_decrease_rate);
+
+    let mut covered: Vec<usize> = Vec::new();
+    // Start of the current stretch without friendly lights
+    let mut dark_since: Option<Time> = None;
+    let mut any_light = false;
+
+    for sample in 0..=samples {
+        let beat = step * r32(sample as f32);
+        let state = LevelState::render(level, config, beat, None);
+        let (danger, friendly): (Vec<&Light>, Vec<&Light>) =
+            state.lights.iter().partition(|light| light.danger);
+
+        if friendly.is_empty() {
+            if any_light && dark_since.is_none() {
+                dark_since = Some(beat);
+            }
+        } else {
+            any_light = true;
+            if let Some(since) = dark_since.take() {
+                push_darkness(since, beat, music, fatal_darkness, diagnostics);
+            }
+        }
+
+        if let [light] = friendly.as_slice() {
+            if let Some(event) = light.event_id {
+                let aabb = light.collider.compute_aabb();
+                let points = [
+                    aabb.center(),
+                    aabb.bottom_left(),
+                    aabb.bottom_right(),
+                    aabb.top_left(),
+                    aabb.top_right(),
+                ];
+                let cover = danger
+                    .iter()
+                    .find(|danger| points.iter().all(|&point| danger.collider.contains(point)));
+                match cover.and_then(|danger| danger.event_id) {
+                    Some(danger) if !covered.contains(&event) => {
+                        covered.push(event);
+                        diagnostics.push(Diagnostic::warning(
+                            DiagnosticKind::CoveredLight { danger },
+                            beat,
+                            Some(event),
+                        ));
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+}
+
+fn push_darkness(
+    from: Time,
+    to: Time,
+    music: &MusicInfo,
+    fatal_darkness: Option<Time>,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    let seconds = music.beat_to_time(to) - music.beat_to_time(from);
+    if seconds.as_f32() < MIN_DARKNESS {
+        return;
+    }
+
+    let fatal = fatal_darkness.map_or(false, |fatal| seconds >= fatal);
+    let kind = DiagnosticKind::NoLight {
+        duration: to - from,
+        fatal,
+    };
+    diagnostics.push(if fatal {
+        Diagnostic::error(kind, from, None)
+    } else {
+        Diagnostic::warning(kind, from, None)
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn light(beat: f32, movement: Movement) -> TimedEvent {
+        TimedEvent {
+            beat: r32(beat),
+            event: Event::Light(LightEvent {
+                light: LightSerde {
+                    danger: false,
+                    shape: Shape::Circle { radius: r32(1.0) },
+                    movement,
+                },
+                telegraph: Telegraph::default(),
+            }),
+        }
+    }
+
+    fn still_light(beat: f32) -> TimedEvent {
+        let mut movement = Movement::default();
+        movement.key_frames.push_back(MoveFrame::scale(4.0, 1.0));
+        light(beat, movement)
+    }
+
+    fn lint(level: &Level, music: &MusicInfo) -> Vec<Diagnostic> {
+        lint_level(level, &LevelConfig::default(), music)
+    }
+
+    #[test]
+    fn duplicate_events() {
+        let level = Level {
+            events: vec![still_light(1.0), still_light(2.0), still_light(1.0)],
+        };
+        let duplicates: Vec<_> = lint(&level, &MusicInfo::default())
+            .into_iter()
+            .filter(|diagnostic| matches!(diagnostic.kind, DiagnosticKind::Duplicate { .. }))
+            .collect();
+        assert_eq!(duplicates.len(), 1);
+        assert_eq!(
+            duplicates[0].kind,
+            DiagnosticKind::Duplicate { original: 0 }
+        );
+        assert_eq!(duplicates[0].event, Some(2));
+    }
+
+    #[test]
+    fn zero_lerp_time_is_warning() {
+        let mut movement = Movement::default();
+        movement.key_frames.push_back(MoveFrame::scale(0.0, 2.0));
+        movement.key_frames.push_back(MoveFrame::scale(4.0, 1.0));
+        let level = Level {
+            events: vec![light(1.0, movement)],
+        };
+        let diagnostic = lint(&level, &MusicInfo::default())
+            .into_iter()
+            .find(|diagnostic| diagnostic.kind == DiagnosticKind::ZeroLerpTime)
+            .expect("zero lerp time should be reported");
+        assert_eq!(diagnostic.severity, Severity::Warning);
+        assert_eq!(diagnostic.waypoint, Some(WaypointId::Frame(0)));
+    }
+
+    #[test]
+    fn off_screen_light() {
+        let mut movement = Movement::default();
+        movement.initial.translation = vec2(100.0, 0.0).as_r32();
+        movement.key_frames.push_back(MoveFrame::scale(4.0, 1.0));
+        let level = Level {
+            events: vec![light(1.0, movement)],
+        };
+        assert!(lint(&level, &MusicInfo::default())
+            .iter()
+            .any(|diagnostic| diagnostic.kind == DiagnosticKind::OffScreen));
+    }
+
+    #[test]
+    fn darkness_between_lights() {
+        let level = Level {
+            events: vec![still_light(0.0), still_light(100.0)],
+        };
+        let diagnostics = lint(&level, &MusicInfo::default());
+        assert!(diagnostics
+            .iter()
+            .any(|diagnostic| matches!(diagnostic.kind, DiagnosticKind::NoLight { .. })));
+    }
+
+    #[test]
+    fn states_end_with_music() {
+        let level = Level {
+            events: vec![still_light(0.0), still_light(100.0)],
+        };
+        let music = MusicInfo {
+            duration: r32(20.0),
+            ..default()
+        };
+        // The second light is after the end of the music, so the darkness never ends
+        assert!(!lint(&level, &music)
+            .iter()
+            .any(|diagnostic| matches!(diagnostic.kind, DiagnosticKind::NoLight { .. })));
+    }
+
+    #[test]
+    fn long_level_is_sampled() {
+        let level = Level {
+            events: vec![still_light(0.0), still_light(1e7)],
+        };
+        // Would take 4e7 samples with the default step
+        let start = std::time::Instant::now();
+        lint(&level, &MusicInfo::default());
+        assert!(start.elapsed() < std::time::Duration::from_secs(10));
+    }
+}
@@ -0,0 +1,467 @@
+//! Versioning of the group file format.
+//!
+//! Group files start with a [`GroupHeader`] followed by the bincode-encoded [`LevelSet`].
+//! Bincode is positional, so every change to the layout of the level data bumps [`GROUP_VERSION`]
+//! and keeps a copy of the previous layout here together with the migration to the next version.
+
+use crate::{model, prelude::*, types};
+
+/// Marks the start of a versioned group file.
+pub const GROUP_MAGIC: [u8; 4] = *b"CTLG";
+/// Current version of the group format.
+pub const GROUP_VERSION: u32 = 3;
+
+#[derive(thiserror::Error, Debug)]
+pub enum GroupFormatError {
+    #[error("unsupported group version {0}, latest is {GROUP_VERSION}")]
+    Version(u32),
+    #[error("invalid group data: {0}")]
+    Bincode(#[from] bincode::Error),
+}
+
+/// Written in front of the group data.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct GroupHeader {
+    pub magic: [u8; 4],
+    pub version: u32,
+}
+
+impl GroupHeader {
+    pub fn current() -> Self {
+        Self {
+            magic: GROUP_MAGIC,
+            version: GROUP_VERSION,
+        }
+    }
+}
+
+/// Encode the group in the current version, prefixed by the header.
+pub fn encode_group<L: Serialize>(group: &LevelSet<L>) -> bincode::Result<Vec<u8>> {
+    let mut bytes = bincode::serialize(&GroupHeader::current())?;
+    bincode::serialize_into(&mut bytes, group)?;
+    Ok(bytes)
+}
+
+/// Returns the version of the encoded group.
+/// Files without a header were saved before it was introduced, which is version `0`.
+pub fn group_version(bytes: &[u8]) -> Result<u32, GroupFormatError> {
+    if !bytes.starts_with(&GROUP_MAGIC) {
+        return Ok(0);
+    }
+    let header: GroupHeader = bincode::deserialize(bytes)?;
+    Ok(header.version)
+}
+
+/// Decode a group of any known version, migrating it to the current one.
+pub fn decode_group<L: DeserializeOwned + From<LevelFull>>(
+    bytes: &[u8],
+) -> Result<LevelSet<L>, GroupFormatError> {
+    if !bytes.starts_with(&GROUP_MAGIC) {
+        return migrate(0, bytes);
+    }
+
+    let mut data = bytes;
+    let header: GroupHeader = bincode::deserialize_from(&mut data)?;
+    migrate(header.version, data)
+}
+
+/// Decode the group data of the given version and migrate it to the current one.
+fn migrate<L: DeserializeOwned + From<LevelFull>>(
+    version: u32,
+    data: &[u8],
+) -> Result<LevelSet<L>, GroupFormatError> {
+    let group: types::LevelSet<types::LevelFull> = match version {
+        0 => bincode::deserialize::<common::LevelSet<v0::MoveFrame>>(data)?.into(),
+        1 => bincode::deserialize::<common::LevelSet<v1::MoveFrame>>(data)?.into(),
+        2 => bincode::deserialize::<v2::LevelSet>(data)?.into(),
+        GROUP_VERSION => return Ok(bincode::deserialize(data)?),
+        _ => return Err(GroupFormatError::Version(version)),
+    };
+    Ok(LevelSet {
+        id: group.id,
+        music: group.music,
+        owner: group.owner,
+        levels: group.levels.into_iter().map(L::from).collect(),
+    })
+}
+
+/// Layout shared by the older versions, generic over the keyframe type.
+mod common {
+    use super::*;
+
+    #[derive(Deserialize)]
+    pub struct LevelSet<F> {
+        pub id: Id,
+        pub music: Id,
+        pub owner: UserInfo,
+        pub levels: Vec<LevelFull<F>>,
+    }
+
+    #[derive(Deserialize)]
+    pub struct LevelFull<F> {
+        pub meta: LevelInfo,
+        pub data: Level<F>,
+    }
+
+    /// Level metadata before the difficulty rating.
+    #[derive(Deserialize)]
+    pub struct LevelInfo {
+        pub id: Id,
+        pub name: Name,
+        pub authors: Vec<UserInfo>,
+        pub hash: String,
+    }
+
+    impl From<LevelInfo> for types::LevelInfo {
+        fn from(value: LevelInfo) -> Self {
+            Self {
+                id: value.id,
+                name: value.name,
+                authors: value.authors,
+                hash: value.hash,
+                difficulty: None,
+            }
+        }
+    }
+
+    #[derive(Deserialize)]
+    pub struct Level<F> {
+        pub events: Vec<TimedEvent<F>>,
+    }
+
+    #[derive(Deserialize)]
+    pub struct TimedEvent<F> {
+        pub beat: Time,
+        pub event: Event<F>,
+    }
+
+    #[derive(Deserialize)]
+    pub enum Event<F> {
+        Light(LightEvent<F>),
+        PaletteSwap,
+    }
+
+    #[derive(Deserialize)]
+    pub struct LightEvent<F> {
+        pub light: LightSerde<F>,
+        pub telegraph: Telegraph,
+    }
+
+    #[derive(Deserialize)]
+    pub struct LightSerde<F> {
+        pub danger: bool,
+        pub shape: Shape,
+        pub movement: Movement<F>,
+    }
+
+    #[derive(Deserialize)]
+    pub struct Movement<F> {
+        pub fade_in: Time,
+        pub fade_out: Time,
+        pub initial: Transform,
+        pub key_frames: VecDeque<F>,
+    }
+
+    /// Transform with a uniform scale.
+    #[derive(Deserialize, Clone, Copy)]
+    pub struct Transform {
+        pub translation: vec2<Coord>,
+        pub rotation: Angle<Coord>,
+        pub scale: Coord,
+    }
+
+    impl From<Transform> for model::Transform {
+        fn from(value: Transform) -> Self {
+            Self {
+                translation: value.translation,
+                rotation: value.rotation,
+                scale: vec2::splat(value.scale),
+            }
+        }
+    }
+
+    impl<F: Into<model::MoveFrame>> From<LevelSet<F>> for types::LevelSet<types::LevelFull> {
+        fn from(value: LevelSet<F>) -> Self {
+            Self {
+                id: value.id,
+                music: value.music,
+                owner: value.owner,
+                levels: value
+                    .levels
+                    .into_iter()
+                    .map(|level| types::LevelFull {
+                        meta: level.meta.into(),
+                        data: level.data.into(),
+                    })
+                    .collect(),
+            }
+        }
+    }
+
+    impl<F: Into<model::MoveFrame>> From<Level<F>> for model::Level {
+        fn from(value: Level<F>) -> Self {
+            Self {
+                events: value
+                    .events
+                    .into_iter()
+                    .map(|event| model::TimedEvent {
+                        beat: event.beat,
+                        event: match event.event {
+                            Event::Light(event) => model::Event::Light(model::LightEvent {
+                                light: model::LightSerde {
+                                    danger: event.light.danger,
+                                    shape: event.light.shape,
+                                    movement: event.light.movement.into(),
+                                },
+                                telegraph: event.telegraph,
+                            }),
+                            Event::PaletteSwap => model::Event::PaletteSwap,
+                        },
+                    })
+                    .collect(),
+            }
+        }
+    }
+
+    impl<F: Into<model::MoveFrame>> From<Movement<F>> for model::Movement {
+        fn from(value: Movement<F>) -> Self {
+            Self {
+                fade_in: value.fade_in,
+                fade_out: value.fade_out,
+                initial: value.initial.into(),
+                key_frames: value.key_frames.into_iter().map(Into::into).collect(),
+            }
+        }
+    }
+}
+
+/// Version 0, before movement keyframes got easing and curves.
+mod v0 {
+    use super::*;
+
+    #[derive(Deserialize)]
+    pub struct MoveFrame {
+        pub lerp_time: Time,
+        pub transform: common::Transform,
+    }
+
+    impl From<MoveFrame> for v1::MoveFrame {
+        fn from(value: MoveFrame) -> Self {
+            Self {
+                lerp_time: value.lerp_time,
+                transform: value.transform,
+                easing: Easing::default(),
+                curve: Vec::new(),
+            }
+        }
+    }
+
+    impl From<MoveFrame> for model::MoveFrame {
+        fn from(value: MoveFrame) -> Self {
+            v1::MoveFrame::from(value).into()
+        }
+    }
+}
+
+/// Version 1, before transforms got a non-uniform scale.
+mod v1 {
+    use super::*;
+
+    #[derive(Deserialize)]
+    pub struct MoveFrame {
+        pub lerp_time: Time,
+        pub transform: common::Transform,
+        pub easing: Easing,
+        pub curve: Vec<vec2<Coord>>,
+    }
+
+    impl From<MoveFrame> for model::MoveFrame {
+        fn from(value: MoveFrame) -> Self {
+            Self {
+                lerp_time: value.lerp_time,
+                transform: value.transform.into(),
+                easing: value.easing,
+                curve: value.curve,
+            }
+        }
+    }
+}
+
+/// Version 2, before levels got a difficulty rating.
+mod v2 {
+    use super::*;
+
+    #[derive(Deserialize)]
+    pub struct LevelSet {
+        pub id: Id,
+        pub music: Id,
+        pub owner: UserInfo,
+        pub levels: Vec<LevelFull>,
+    }
+
+    #[derive(Deserialize)]
+    pub struct LevelFull {
+        pub meta: common::LevelInfo,
+        pub data: model::Level,
+    }
+
+    impl From<LevelSet> for types::LevelSet<types::LevelFull> {
+        fn from(value: LevelSet) -> Self {
+            Self {
+                id: value.id,
+                music: value.music,
+                owner: value.owner,
+                levels: value
+                    .levels
+                    .into_iter()
+                    .map(|level| types::LevelFull {
+                        meta: level.meta.into(),
+                        data: level.data,
+                    })
+                    .collect(),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn put<T: Serialize + ?Sized>(bytes: &mut Vec<u8>, value: &T) {
+        bincode::serialize_into(bytes, value).unwrap();
+    }
+
+    fn put_transform(bytes: &mut Vec<u8>, x: f32, scale: f32) {
+        put(bytes, &vec2(x, 0.0).as_r32());
+        put(bytes, &Angle::<Coord>::ZERO);
+        put(bytes, &r32(scale));
+    }
+
+    /// A group written field by field in the layout used before the header was introduced.
+    fn v0_bytes() -> Vec<u8> {
+        let mut bytes = Vec::new();
+        // LevelSet
+        put(&mut bytes, &1u32);
+        put(&mut bytes, &2u32);
+        put(&mut bytes, &(3u32, "owner"));
+        put(&mut bytes, &1u64);
+        // LevelInfo
+        put(&mut bytes, &4u32);
+        put(&mut bytes, "hard");
+        put(&mut bytes, &vec![(3u32, "owner")]);
+        put(&mut bytes, "abc");
+        // Level
+        put(&mut bytes, &2u64);
+        // Event::Light
+        put(&mut bytes, &r32(4.0));
+        put(&mut bytes, &0u32);
+        put(&mut bytes, &true);
+        // Shape::Circle
+        put(&mut bytes, &0u32);
+        put(&mut bytes, &r32(0.5));
+        // Movement
+        put(&mut bytes, &r32(1.0));
+        put(&mut bytes, &r32(1.0));
+        put_transform(&mut bytes, 0.0, 1.0);
+        put(&mut bytes, &1u64);
+        put(&mut bytes, &r32(2.0));
+        put_transform(&mut bytes, 3.0, 2.0);
+        // Telegraph
+        put(&mut bytes, &r32(1.0));
+        put(&mut bytes, &r32(2.0));
+        // Event::PaletteSwap
+        put(&mut bytes, &r32(8.0));
+        put(&mut bytes, &1u32);
+        bytes
+    }
+
+    fn v0_group() -> types::LevelSet<types::LevelFull> {
+        let owner = UserInfo {
+            id: 3,
+            name: "owner".into(),
+        };
+        let frame = model::MoveFrame::new(
+            r32(2.0),
+            model::Transform {
+                translation: vec2(3.0, 0.0).as_r32(),
+                ..model::Transform::scale(2.0)
+            },
+        );
+        let light = model::LightEvent {
+            light: model::LightSerde {
+                danger: true,
+                shape: Shape::Circle { radius: r32(0.5) },
+                movement: model::Movement {
+                    fade_in: r32(1.0),
+                    fade_out: r32(1.0),
+                    initial: model::Transform::identity(),
+                    key_frames: [frame].into(),
+                },
+            },
+            telegraph: Telegraph {
+                precede_time: r32(1.0),
+                speed: r32(2.0),
+            },
+        };
+        types::LevelSet {
+            id: 1,
+            music: 2,
+            owner: owner.clone(),
+            levels: vec![types::LevelFull {
+                meta: types::LevelInfo {
+                    id: 4,
+                    name: "hard".into(),
+                    authors: vec![owner],
+                    hash: "abc".into(),
+                    difficulty: None,
+                },
+                data: model::Level {
+                    events: vec![
+                        model::TimedEvent {
+                            beat: r32(4.0),
+                            event: model::Event::Light(light),
+                        },
+                        model::TimedEvent {
+                            beat: r32(8.0),
+                            event: model::Event::PaletteSwap,
+                        },
+                    ],
+                },
+            }],
+        }
+    }
+
+    #[test]
+    fn headerless_is_v0() {
+        let bytes = v0_bytes();
+        assert_eq!(group_version(&bytes).unwrap(), 0);
+        let group: types::LevelSet<types::LevelFull> = decode_group(&bytes).unwrap();
+        assert_eq!(group, v0_group());
+    }
+
+    #[test]
+    fn v0_round_trip() {
+        let group: types::LevelSet<types::LevelFull> = decode_group(&v0_bytes()).unwrap();
+        let bytes = encode_group(&group).unwrap();
+        assert_eq!(group_version(&bytes).unwrap(), GROUP_VERSION);
+
+        let decoded: types::LevelSet<types::LevelFull> = decode_group(&bytes).unwrap();
+        assert_eq!(decoded, v0_group());
+        assert_eq!(encode_group(&decoded).unwrap(), bytes);
+    }
+
+    #[test]
+    fn unknown_version() {
+        let mut bytes = bincode::serialize(&GroupHeader {
+            magic: GROUP_MAGIC,
+            version: GROUP_VERSION + 1,
+        })
+        .unwrap();
+        bytes.extend(bincode::serialize(&v0_group()).unwrap());
+        assert!(matches!(
+            decode_group::<types::LevelFull>(&bytes),
+            Err(GroupFormatError::Version(version)) if version == GROUP_VERSION + 1
+        ));
+    }
+}
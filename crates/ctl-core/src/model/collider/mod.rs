@@ -74,8 +74,45 @@ impl Collider {
         })
     }
 
+    /// Returns the range of the other collider's projection
+    /// onto the normal of this collider's x axis, relative to this collider's position.
+    fn project_normal(&self, other: &Self) -> (Coord, Coord) {
+        if let Shape::Line { width } = other.shape {
+            let relative = other.rotation - self.rotation;
+            if relative.sin().abs() > r32(1e-3) {
+                // Not parallel lines always intersect
+                let inf = r32(1e9);
+                return (-inf, inf);
+            }
+            let normal = self.rotation.unit_vec().rotate_90();
+            let center = vec2::dot(other.position - self.position, normal);
+            let half = width / r32(2.0);
+            return (center - half, center + half);
+        }
+
+        let local = Collider {
+            position: (other.position - self.position).rotate(-self.rotation),
+            rotation: other.rotation - self.rotation,
+            shape: other.shape.clone(),
+        };
+        let aabb = local.compute_aabb();
+        (aabb.min.y, aabb.max.y)
+    }
+
     /// Check whether two colliders are intersecting.
     pub fn check(&self, other: &Self) -> bool {
+        match (&self.shape, &other.shape) {
+            (&Shape::Line { width }, _) => {
+                let half = width / r32(2.0);
+                let (min, max) = self.project_normal(other);
+                max >= -half && min <= half
+            }
+            (_, Shape::Line { .. }) => other.check(self),
+            _ => self.check_parry(other),
+        }
+    }
+
+    fn check_parry(&self, other: &Self) -> bool {
         let delta = (other.position - self.position).as_f32();
 
         let self_angle = self.rotation.as_radians().as_f32();
@@ -93,6 +130,39 @@ impl Collider {
 
     /// Return the collision info if the two colliders are intersecting.
     pub fn collide(&self, other: &Self) -> Option<Collision> {
+        match (&self.shape, &other.shape) {
+            (&Shape::Line { width }, _) => {
+                let half = width / r32(2.0);
+                let (min, max) = self.project_normal(other);
+                if max < -half || min > half {
+                    return None;
+                }
+
+                let normal = self.rotation.unit_vec().rotate_90();
+                let delta = other.position - self.position;
+                let (normal, penetration) = if vec2::dot(delta, normal) >= Coord::ZERO {
+                    (normal, half - min)
+                } else {
+                    (-normal, max + half)
+                };
+                // Closest point on the edge of the strip
+                let point = delta - normal * (vec2::dot(delta, normal) - half);
+                Some(Collision {
+                    point,
+                    normal,
+                    penetration,
+                })
+            }
+            (_, Shape::Line { .. }) => other.collide(self).map(|collision| Collision {
+                point: collision.point + other.position - self.position,
+                normal: -collision.normal,
+                penetration: collision.penetration,
+            }),
+            _ => self.collide_parry(other),
+        }
+    }
+
+    fn collide_parry(&self, other: &Self) -> Option<Collision> {
         let delta = (other.position - self.position).as_f32();
 
         let self_angle = self.rotation.as_radians().as_f32();
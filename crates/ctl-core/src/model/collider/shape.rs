@@ -1,10 +1,48 @@
 use super::*;
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+/// Number of segments used to approximate round shapes in colliders.
+const ROUND_RESOLUTION: usize = 32;
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub enum Shape {
-    Circle { radius: Coord },
-    Line { width: Coord },
-    Rectangle { width: Coord, height: Coord },
+    Circle {
+        radius: Coord,
+    },
+    /// An infinite strip along the x axis.
+    Line {
+        width: Coord,
+    },
+    Rectangle {
+        width: Coord,
+        height: Coord,
+    },
+    /// A circle with a hole in the middle.
+    Ring {
+        /// Outer radius.
+        radius: Coord,
+        /// Distance between the outer and the inner radius.
+        width: Coord,
+    },
+    /// A part of a ring, symmetric around the x axis.
+    /// When `width` is equal to `radius`, it is a pie slice.
+    Arc {
+        /// Outer radius.
+        radius: Coord,
+        /// Distance between the outer and the inner radius.
+        width: Coord,
+        /// Angle covered by the arc.
+        angle: Angle<Coord>,
+    },
+    /// A polygon with vertices relative to its center.
+    /// Collides and is drawn as the convex hull of the vertices, see [`Shape::convex_hull`].
+    Polygon {
+        points: Vec<vec2<Coord>>,
+    },
+    /// A circle stretched along the axes, produced by scaling a circle non-uniformly.
+    Ellipse {
+        /// Radius along each axis.
+        radius: vec2<Coord>,
+    },
 }
 
 impl Shape {
@@ -23,40 +61,279 @@ impl Shape {
         }
     }
 
-    pub fn to_parry(self) -> Box<dyn parry2d::shape::Shape> {
+    pub fn ring(radius: Coord, width: Coord) -> Self {
+        Self::Ring { radius, width }
+    }
+
+    pub fn arc(radius: Coord, width: Coord, angle: Angle<Coord>) -> Self {
+        Self::Arc {
+            radius,
+            width,
+            angle,
+        }
+    }
+
+    pub fn ellipse(radius: vec2<Coord>) -> Self {
+        Self::Ellipse { radius }
+    }
+
+    /// An equilateral triangle inscribed in a circle of the given radius, pointing along the x axis.
+    pub fn triangle(radius: Coord) -> Self {
+        Self::regular_polygon(3, radius)
+    }
+
+    pub fn regular_polygon(sides: usize, radius: Coord) -> Self {
+        let points = (0..sides)
+            .map(|i| {
+                let angle = Angle::from_radians(r32(i as f32 / sides as f32 * f32::PI * 2.0));
+                angle.unit_vec() * radius
+            })
+            .collect();
+        Self::Polygon { points }
+    }
+
+    /// Vertices of the convex hull of the points in counter-clockwise order.
+    /// Fewer than 3 vertices are returned when the points do not enclose any area.
+    pub fn convex_hull(points: &[vec2<Coord>]) -> Vec<vec2<Coord>> {
+        let mut points = points.to_vec();
+        points.sort_by_key(|point| (point.x, point.y));
+        points.dedup();
+        if points.len() < 3 {
+            return points;
+        }
+
+        // Andrew's monotone chain: the lower half, then the upper half
+        let cross = |o: vec2<Coord>, a: vec2<Coord>, b: vec2<Coord>| {
+            (a.x - o.x) * (b.y - o.y) - (a.y - o.y) * (b.x - o.x)
+        };
+        let mut hull: Vec<vec2<Coord>> = Vec::with_capacity(points.len() + 1);
+        for _ in 0..2 {
+            let start = hull.len();
+            for &point in &points {
+                while hull.len() >= start + 2
+                    && cross(hull[hull.len() - 2], hull[hull.len() - 1], point) <= Coord::ZERO
+                {
+                    hull.pop();
+                }
+                hull.push(point);
+            }
+            // The last point starts the other half
+            hull.pop();
+            points.reverse();
+        }
+        hull
+    }
+
+    /// Approximate radius of the shape, used to scale visuals with it.
+    pub fn radius(&self) -> Coord {
+        match self {
+            Shape::Circle { radius } => *radius,
+            Shape::Line { width } => *width / r32(2.0),
+            Shape::Rectangle { width, height } => (*width).max(*height) / r32(2.0),
+            Shape::Ring { radius, .. } => *radius,
+            Shape::Arc { radius, .. } => *radius,
+            Shape::Polygon { points } => points
+                .iter()
+                .map(|point| point.len())
+                .max()
+                .unwrap_or(Coord::ZERO),
+            Shape::Ellipse { radius } => radius.x.max(radius.y),
+        }
+    }
+
+    /// Convert to a parry shape.
+    /// [`Shape::Line`] is infinite, so it is only approximated here,
+    /// collisions with it are computed analytically in [`Collider`].
+    pub fn to_parry(&self) -> Box<dyn parry2d::shape::Shape> {
         match self {
             Shape::Circle { radius } => Box::new(parry2d::shape::Ball::new(radius.as_f32())),
-            Shape::Line { width } => Shape::Rectangle {
-                width: r32(1e3), // TODO: unhack
-                height: width,
-            }
-            .to_parry(),
+            Shape::Line { width } => Box::new(parry2d::shape::Cuboid::new(
+                parry2d::na::Vector2::new(1e6, width.as_f32() / 2.0),
+            )),
             Shape::Rectangle { width, height } => {
-                let aabb = Aabb2::ZERO.extend_symmetric(vec2(width, height).as_f32() / 2.0);
-                let points = aabb.corners().map(|p| {
-                    let vec2(x, y) = p;
-                    parry2d::math::Point::new(x, y)
-                });
-                match parry2d::shape::ConvexPolygon::from_convex_hull(&points) {
-                    Some(shape) => Box::new(shape),
-                    None => Box::new(parry2d::shape::Ball::new(0.0)),
-                }
+                let aabb = Aabb2::ZERO.extend_symmetric(vec2(*width, *height).as_f32() / 2.0);
+                convex_polygon(aabb.corners())
+            }
+            Shape::Ring { radius, width } => {
+                annulus_sector(*radius, *width, Angle::from_radians(r32(f32::PI)))
+            }
+            Shape::Arc {
+                radius,
+                width,
+                angle,
+            } => annulus_sector(
+                *radius,
+                *width,
+                Angle::from_radians(angle.as_radians() / r32(2.0)),
+            ),
+            Shape::Polygon { points } => convex_polygon(points.iter().map(|p| p.as_f32())),
+            Shape::Ellipse { radius } => {
+                let radius = radius.as_f32();
+                convex_polygon((0..ROUND_RESOLUTION).map(|i| {
+                    let angle = i as f32 / ROUND_RESOLUTION as f32 * f32::PI * 2.0;
+                    Angle::from_radians(angle).unit_vec() * radius
+                }))
             }
         }
     }
 
-    pub fn scaled(self, scale: Coord) -> Self {
+    /// Scale the shape along each axis.
+    /// Circles become ellipses when the axes differ.
+    /// Rings and arcs can only be scaled uniformly, so they use the average of the axes.
+    pub fn scaled(&self, scale: vec2<Coord>) -> Self {
+        let uniform = (scale.x + scale.y) / r32(2.0);
         match self {
-            Shape::Circle { radius } => Shape::Circle {
-                radius: radius * scale,
+            Shape::Circle { radius } if scale.x == scale.y => Shape::Circle {
+                radius: *radius * scale.x,
+            },
+            Shape::Circle { radius } => Shape::Ellipse {
+                radius: vec2::splat(*radius) * scale,
             },
             Shape::Line { width } => Shape::Line {
-                width: width * scale,
+                width: *width * scale.y,
             },
             Shape::Rectangle { width, height } => Shape::Rectangle {
-                width: width * scale,
-                height: height * scale,
+                width: *width * scale.x,
+                height: *height * scale.y,
+            },
+            Shape::Ring { radius, width } => Shape::Ring {
+                radius: *radius * uniform,
+                width: *width * uniform,
+            },
+            Shape::Arc {
+                radius,
+                width,
+                angle,
+            } => Shape::Arc {
+                radius: *radius * uniform,
+                width: *width * uniform,
+                angle: *angle,
+            },
+            Shape::Polygon { points } => Shape::Polygon {
+                points: points.iter().map(|&point| point * scale).collect(),
+            },
+            Shape::Ellipse { radius } => Shape::Ellipse {
+                radius: *radius * scale,
             },
         }
     }
 }
+
+fn convex_polygon(points: impl IntoIterator<Item = vec2<f32>>) -> Box<dyn parry2d::shape::Shape> {
+    let points: Vec<_> = points
+        .into_iter()
+        .map(|vec2(x, y)| parry2d::math::Point::new(x, y))
+        .collect();
+    match parry2d::shape::ConvexPolygon::from_convex_hull(&points) {
+        Some(shape) => Box::new(shape),
+        None => Box::new(parry2d::shape::Ball::new(0.0)),
+    }
+}
+
+/// A compound of convex segments covering a part of a ring between `-half_angle` and `half_angle`.
+fn annulus_sector(
+    radius: Coord,
+    width: Coord,
+    half_angle: Angle<Coord>,
+) -> Box<dyn parry2d::shape::Shape> {
+    let outer = radius.as_f32().max(0.0);
+    let inner = (radius - width).as_f32().clamp(0.0, outer);
+    let half_angle = half_angle.as_radians().as_f32().clamp(0.0, f32::PI);
+
+    let segments = ((ROUND_RESOLUTION as f32 * half_angle / f32::PI).ceil() as usize).max(1);
+    let step = half_angle * 2.0 / segments as f32;
+    let parts: Vec<_> = (0..segments)
+        .filter_map(|i| {
+            let from = Angle::from_radians(-half_angle + step * i as f32).unit_vec();
+            let to = Angle::from_radians(-half_angle + step * (i + 1) as f32).unit_vec();
+            let points: Vec<_> = [from * inner, from * outer, to * outer, to * inner]
+                .into_iter()
+                .map(|vec2(x, y)| parry2d::math::Point::new(x, y))
+                .collect();
+            let shape = parry2d::shape::ConvexPolygon::from_convex_hull(&points)?;
+            Some((
+                parry2d::math::Isometry::identity(),
+                parry2d::shape::SharedShape::new(shape),
+            ))
+        })
+        .collect();
+
+    if parts.is_empty() {
+        return Box::new(parry2d::shape::Ball::new(0.0));
+    }
+    Box::new(parry2d::shape::Compound::new(parts))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn points(points: &[(f32, f32)]) -> Vec<vec2<Coord>> {
+        points.iter().map(|&(x, y)| vec2(x, y).as_r32()).collect()
+    }
+
+    #[test]
+    fn hull_of_concave_polygon() {
+        // An arrow pointing along the x axis, with a notch at the back
+        let arrow = points(&[(1.0, 0.0), (-1.0, 1.0), (0.0, 0.0), (-1.0, -1.0)]);
+        assert_eq!(
+            Shape::convex_hull(&arrow),
+            points(&[(-1.0, -1.0), (1.0, 0.0), (-1.0, 1.0)])
+        );
+    }
+
+    #[test]
+    fn hull_of_self_intersecting_polygon() {
+        // A bow tie with the vertices of a square
+        let bow_tie = points(&[(-1.0, -1.0), (1.0, 1.0), (1.0, -1.0), (-1.0, 1.0)]);
+        assert_eq!(
+            Shape::convex_hull(&bow_tie),
+            points(&[(-1.0, -1.0), (1.0, -1.0), (1.0, 1.0), (-1.0, 1.0)])
+        );
+    }
+
+    #[test]
+    fn hull_of_degenerate_polygon() {
+        let line = points(&[(0.0, 0.0), (2.0, 0.0), (1.0, 0.0), (2.0, 0.0)]);
+        assert_eq!(Shape::convex_hull(&line), points(&[(0.0, 0.0), (2.0, 0.0)]));
+    }
+
+    #[test]
+    fn concave_polygon_collides_as_hull() {
+        let arrow = Shape::Polygon {
+            points: points(&[(1.0, 0.0), (-1.0, 1.0), (0.0, 0.0), (-1.0, -1.0)]),
+        };
+        let collider = Collider::new(vec2::ZERO, arrow);
+        // Inside the notch
+        assert!(collider.contains(vec2(-0.5, 0.0).as_r32()));
+        assert!(!collider.contains(vec2(-1.5, 0.0).as_r32()));
+    }
+
+    #[test]
+    fn circles_scale_along_each_axis() {
+        let circle = Shape::circle(r32(1.0));
+        assert_eq!(
+            circle.scaled(vec2::splat(r32(2.0))),
+            Shape::circle(r32(2.0))
+        );
+
+        let ellipse = Collider::new(vec2::ZERO, circle.scaled(vec2(1.0, 3.0).as_r32()));
+        // Only inside the ellipse
+        assert!(ellipse.contains(vec2(0.0, 2.5).as_r32()));
+        // Only inside a circle with the average radius
+        assert!(!ellipse.contains(vec2(1.5, 0.0).as_r32()));
+    }
+
+    #[test]
+    fn rings_scale_uniformly() {
+        let scale = vec2(1.0, 3.0).as_r32();
+        assert_eq!(
+            Shape::ring(r32(1.0), r32(0.5)).scaled(scale),
+            Shape::ring(r32(2.0), r32(1.0))
+        );
+        assert_eq!(
+            Shape::rectangle(vec2(1.0, 1.0).as_r32()).scaled(scale),
+            Shape::rectangle(scale)
+        );
+    }
+}
@@ -27,6 +27,16 @@ pub struct HealthConfig {
     pub restore_rate: Time,
 }
 
+/// Named health configurations, used to separate leaderboards.
+#[derive(Debug, Default, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum HealthPreset {
+    #[default]
+    Normal,
+    /// Any configuration that does not match a preset.
+    Custom,
+}
+
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
 #[serde(default)]
 pub struct WaypointsConfig {
@@ -153,6 +163,15 @@ impl HealthConfig {
     //     }
     // }
 
+    /// Returns the preset matching this configuration.
+    pub fn preset(&self) -> HealthPreset {
+        if *self == Self::preset_normal() {
+            HealthPreset::Normal
+        } else {
+            HealthPreset::Custom
+        }
+    }
+
     pub fn preset_normal() -> Self {
         Self {
             max: r32(1.0),
@@ -1,8 +1,27 @@
 mod collider;
+mod config;
 mod level;
 mod light;
 mod movement;
+mod pattern;
+mod player;
+mod replay;
+mod score;
+mod state;
 
-pub use self::{collider::*, level::*, movement::*, light::*};
+pub use self::{
+    collider::*, config::*, level::*, light::*, movement::*, pattern::*, player::*, replay::*,
+    score::*, state::*,
+};
 
 use crate::prelude::*;
+
+pub const COYOTE_TIME: f32 = 0.1;
+pub const BUFFER_TIME: f32 = 0.1;
+
+pub type Lifetime = Bounded<Time>;
+
+#[derive(Debug, Clone)]
+pub enum GameEvent {
+    Rhythm { perfect: bool },
+}
@@ -15,6 +15,33 @@ pub struct MoveFrame {
     /// How long (in beats) should the interpolation from the last frame to that frame last.
     pub lerp_time: Time,
     pub transform: Transform,
+    /// Easing applied to the interpolation from the last frame.
+    #[serde(default)]
+    pub easing: Easing,
+    /// Control points of a Bezier curve along which the translation moves from the last frame.
+    /// Empty for a straight line.
+    #[serde(default)]
+    pub curve: Vec<vec2<Coord>>,
+}
+
+#[derive(
+    Debug, Default, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, enum_iterator::Sequence,
+)]
+pub enum Easing {
+    Linear,
+    #[default]
+    Smoothstep,
+    QuadIn,
+    QuadOut,
+    QuadInOut,
+    CubicIn,
+    CubicOut,
+    CubicInOut,
+    /// Overshoots the target and comes back.
+    BackOut,
+    BounceOut,
+    /// Stays in place and jumps to the target at the end.
+    Step,
 }
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
@@ -45,15 +72,133 @@ impl WaypointId {
 pub struct Transform {
     pub translation: vec2<Coord>,
     pub rotation: Angle<Coord>,
-    pub scale: Coord,
+    /// Scale along each of the axes (before rotation).
+    /// A single number is accepted for uniform scale in human-readable formats.
+    #[serde(deserialize_with = "deserialize_scale")]
+    pub scale: vec2<Coord>,
+}
+
+fn deserialize_scale<'de, D: serde::Deserializer<'de>>(
+    deserializer: D,
+) -> Result<vec2<Coord>, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Scale {
+        Uniform(Coord),
+        Vector(vec2<Coord>),
+    }
+
+    if deserializer.is_human_readable() {
+        Ok(match Scale::deserialize(deserializer)? {
+            Scale::Uniform(scale) => vec2::splat(scale),
+            Scale::Vector(scale) => scale,
+        })
+    } else {
+        // Untagged enums are not supported by binary formats
+        vec2::deserialize(deserializer)
+    }
 }
 
 impl MoveFrame {
-    pub fn scale(lerp_time: impl Float, scale: impl Float) -> Self {
+    pub fn new(lerp_time: Time, transform: Transform) -> Self {
         Self {
-            lerp_time: lerp_time.as_r32(),
-            transform: Transform::scale(scale),
+            lerp_time,
+            transform,
+            easing: Easing::default(),
+            curve: Vec::new(),
+        }
+    }
+
+    pub fn scale(lerp_time: impl Float, scale: impl Float) -> Self {
+        Self::new(lerp_time.as_r32(), Transform::scale(scale))
+    }
+
+    /// Interpolate from the given transform to this frame, `t` is in range `0..=1`.
+    pub fn interpolate(&self, from: &Transform, t: Time) -> Transform {
+        let t = self.easing.apply(t);
+        let mut transform = from.lerp(&self.transform, t);
+        if !self.curve.is_empty() {
+            let points: Vec<_> = std::iter::once(from.translation)
+                .chain(self.curve.iter().copied())
+                .chain(std::iter::once(self.transform.translation))
+                .collect();
+            transform.translation = crate::util::bezier(&points, t);
         }
+        transform
+    }
+}
+
+impl Easing {
+    pub fn name(self) -> &'static str {
+        match self {
+            Easing::Linear => "Linear",
+            Easing::Smoothstep => "Smooth",
+            Easing::QuadIn => "Quad in",
+            Easing::QuadOut => "Quad out",
+            Easing::QuadInOut => "Quad in-out",
+            Easing::CubicIn => "Cubic in",
+            Easing::CubicOut => "Cubic out",
+            Easing::CubicInOut => "Cubic in-out",
+            Easing::BackOut => "Back out",
+            Easing::BounceOut => "Bounce out",
+            Easing::Step => "Step",
+        }
+    }
+
+    /// Apply the easing to the interpolation parameter `t` in range `0..=1`.
+    pub fn apply(self, t: Time) -> Time {
+        let t = t.as_f32().clamp(0.0, 1.0);
+        let value = match self {
+            Easing::Linear => t,
+            Easing::Smoothstep => crate::util::smoothstep(t),
+            Easing::QuadIn => t * t,
+            Easing::QuadOut => 1.0 - (1.0 - t).powi(2),
+            Easing::QuadInOut => {
+                if t < 0.5 {
+                    2.0 * t * t
+                } else {
+                    1.0 - (-2.0 * t + 2.0).powi(2) / 2.0
+                }
+            }
+            Easing::CubicIn => t * t * t,
+            Easing::CubicOut => 1.0 - (1.0 - t).powi(3),
+            Easing::CubicInOut => {
+                if t < 0.5 {
+                    4.0 * t * t * t
+                } else {
+                    1.0 - (-2.0 * t + 2.0).powi(3) / 2.0
+                }
+            }
+            Easing::BackOut => {
+                let c1 = 1.70158;
+                let c3 = c1 + 1.0;
+                1.0 + c3 * (t - 1.0).powi(3) + c1 * (t - 1.0).powi(2)
+            }
+            Easing::BounceOut => {
+                let n1 = 7.5625;
+                let d1 = 2.75;
+                if t < 1.0 / d1 {
+                    n1 * t * t
+                } else if t < 2.0 / d1 {
+                    let t = t - 1.5 / d1;
+                    n1 * t * t + 0.75
+                } else if t < 2.5 / d1 {
+                    let t = t - 2.25 / d1;
+                    n1 * t * t + 0.9375
+                } else {
+                    let t = t - 2.625 / d1;
+                    n1 * t * t + 0.984375
+                }
+            }
+            Easing::Step => {
+                if t < 1.0 {
+                    0.0
+                } else {
+                    1.0
+                }
+            }
+        };
+        r32(value)
     }
 }
 
@@ -62,13 +207,13 @@ impl Transform {
         Self {
             translation: vec2::ZERO,
             rotation: Angle::ZERO,
-            scale: Coord::ONE,
+            scale: vec2::splat(Coord::ONE),
         }
     }
 
     pub fn scale(scale: impl Float) -> Self {
         Self {
-            scale: scale.as_r32(),
+            scale: vec2::splat(scale.as_r32()),
             ..Self::identity()
         }
     }
@@ -77,7 +222,8 @@ impl Transform {
         Self {
             translation: self.translation + (target.translation - self.translation) * t,
             rotation: self.rotation + self.rotation.angle_to(target.rotation) * t,
-            scale: self.scale + (target.scale - self.scale) * t,
+            // Overshooting easings could otherwise flip the shape inside out
+            scale: (self.scale + (target.scale - self.scale) * t).map(|x| x.max(Coord::ZERO)),
         }
     }
 }
@@ -127,6 +273,14 @@ impl Movement {
         }
     }
 
+    /// Get the keyframe moving into the waypoint, `None` for the initial one.
+    pub fn get_move_frame_mut(&mut self, id: WaypointId) -> Option<&mut MoveFrame> {
+        match id {
+            WaypointId::Initial => None,
+            WaypointId::Frame(i) => self.key_frames.get_mut(i),
+        }
+    }
+
     pub fn get_time(&self, id: WaypointId) -> Option<Time> {
         let i = match id {
             WaypointId::Initial => 0,
@@ -146,13 +300,15 @@ impl Movement {
     pub fn get(&self, mut time: Time) -> Transform {
         let mut from = self.initial;
 
-        let lerp = |from: Transform, to, time, duration| {
-            let t = if duration > Time::ZERO {
+        let progress = |time: Time, duration: Time| {
+            if duration > Time::ZERO {
                 time / duration
             } else {
                 Time::ONE
-            };
-            let t = smoothstep(t);
+            }
+        };
+        let lerp = |from: Transform, to, time, duration| {
+            let t = crate::util::smoothstep(progress(time, duration));
             from.lerp(&to, t)
         };
 
@@ -160,7 +316,7 @@ impl Movement {
         if time <= self.fade_in {
             return lerp(
                 Transform {
-                    scale: Coord::ZERO,
+                    scale: vec2::ZERO,
                     ..from
                 },
                 from,
@@ -172,7 +328,7 @@ impl Movement {
 
         for frame in self.frames_iter() {
             if time <= frame.lerp_time {
-                return lerp(from, frame.transform, time, frame.lerp_time);
+                return frame.interpolate(&from, progress(time, frame.lerp_time));
             }
             time -= frame.lerp_time;
             from = frame.transform;
@@ -180,7 +336,7 @@ impl Movement {
 
         // Fade out
         let target = Transform {
-            scale: Coord::ZERO,
+            scale: vec2::ZERO,
             ..from
         };
         if time <= self.fade_out {
@@ -212,6 +368,22 @@ impl Movement {
     }
 }
 
-fn smoothstep<T: Float>(t: T) -> T {
-    T::from_f32(3.0) * t * t - T::from_f32(2.0) * t * t * t
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn overshoot_keeps_scale_positive() {
+        let frame = MoveFrame {
+            easing: Easing::BackOut,
+            ..MoveFrame::scale(1.0, 0.0)
+        };
+        let from = Transform::identity();
+        for i in 0..=20 {
+            let transform = frame.interpolate(&from, r32(i as f32 / 20.0));
+            assert!(transform.scale.x >= Coord::ZERO && transform.scale.y >= Coord::ZERO);
+        }
+        // Passes through zero instead of going past it
+        assert_eq!(frame.interpolate(&from, r32(0.5)).scale, vec2::ZERO);
+    }
 }
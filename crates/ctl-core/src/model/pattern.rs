@@ -0,0 +1,35 @@
+use super::*;
+
+/// A named set of events that can be placed into any level.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Pattern {
+    pub name: String,
+    /// Events with beats relative to the start of the pattern
+    /// and positions relative to its center.
+    pub events: Vec<TimedEvent>,
+}
+
+impl Pattern {
+    /// Encode into a human readable file that can be shared.
+    pub fn encode(&self) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(self)
+    }
+
+    pub fn decode(data: &str) -> serde_json::Result<Self> {
+        serde_json::from_str(data)
+    }
+
+    /// Name of the file the pattern is stored in.
+    pub fn file_name(&self) -> String {
+        Self::name_to_file(&self.name)
+    }
+
+    /// Name of the file a pattern with the given name is stored in.
+    pub fn name_to_file(name: &str) -> String {
+        let name: String = name
+            .chars()
+            .map(|c| if c.is_alphanumeric() { c } else { '_' })
+            .collect();
+        format!("{}.json", name)
+    }
+}
@@ -117,7 +117,30 @@ impl Player {
                 let dot = dir.x * delta_pos.x + dir.y * delta_pos.y;
                 (dot.abs(), width / r32(2.0))
             }
-            Shape::Rectangle { .. } => todo!(),
+            Shape::Ring { radius, width } => {
+                // Distance to the middle of the ring
+                let middle = radius - width / r32(2.0);
+                ((delta_pos.len() - middle).abs(), width / r32(2.0))
+            }
+            Shape::Arc {
+                radius,
+                width,
+                angle,
+            } => {
+                let local = delta_pos.rotate(-light.rotation);
+                let half_angle = angle.as_radians() / r32(2.0);
+                if local.arg().as_radians().abs() > half_angle {
+                    return;
+                }
+                let middle = radius - width / r32(2.0);
+                ((delta_pos.len() - middle).abs(), width / r32(2.0))
+            }
+            Shape::Rectangle { .. } | Shape::Polygon { .. } | Shape::Ellipse { .. } => {
+                if !light.contains(self.collider.position) {
+                    return;
+                }
+                (delta_pos.len(), light.shape.radius())
+            }
         };
 
         if raw_distance > max_distance {
@@ -138,13 +161,77 @@ impl Player {
 
             let radius = match self.collider.shape {
                 Shape::Circle { radius } => radius,
-                Shape::Line { .. } => unimplemented!(),
-                Shape::Rectangle { .. } => unimplemented!(),
+                _ => unimplemented!(),
             };
             self.is_perfect = raw_distance < radius;
         }
     }
 
+    /// Returns the event and waypoint ids of the closest friendly light.
+    pub fn closest_waypoint(&self, level_state: &LevelState) -> Option<(usize, WaypointId)> {
+        let id = self.closest_light?;
+        level_state
+            .lights
+            .iter()
+            .find(|light| light.event_id == Some(id))
+            .map(|light| (id, light.closest_waypoint.1))
+    }
+
+    /// Update the distances to all lights in the level state.
+    /// Returns `true` if the player has missed a rhythm.
+    pub fn update_lights(
+        &mut self,
+        level_state: &LevelState,
+        last_rhythm: (usize, WaypointId),
+    ) -> bool {
+        let get_light = |id: Option<usize>, pass: bool| {
+            id.and_then(|id| {
+                level_state
+                    .lights
+                    .iter()
+                    .find(|light| light.event_id == Some(id))
+                    .filter(|light| {
+                        // Can only miss after the waypoint, not before, hence no buffer time
+                        // (allows for unpunished early exit)
+                        //
+                        // A miss occurs when the player was inside a light that was leaving its waypoint
+                        // and has missed the coyote time
+                        //
+                        // `pass` used to extend the coyote time for `last_light`,
+                        // because otherwise we cannot detect a miss
+                        // as both will get set to `None` at the same frame
+                        // (allows for unpunished late entrance)
+                        let time = light.closest_waypoint.0.as_f32();
+                        time < 0.0 && (time > -COYOTE_TIME || pass && time > -COYOTE_TIME * 2.0)
+                    })
+                    .map(|light| (id, light.closest_waypoint.1))
+            })
+        };
+        let last_light = get_light(self.closest_light, true);
+
+        self.reset_distance();
+        for light in &level_state.lights {
+            self.update_light_distance(light, last_rhythm);
+        }
+
+        // Light has changed and no perfect rhythm
+        let light = get_light(self.closest_light, false);
+        last_light.is_some() && last_light != light && last_light != Some(last_rhythm)
+    }
+
+    /// Update health depending on the lights the player is in.
+    pub fn update_health(&mut self, config: &HealthConfig, delta_time: Time) {
+        if let Some(distance) = self.danger_distance {
+            let multiplier = (r32(1.0) - distance + r32(0.5)).min(r32(1.0));
+            self.health
+                .change(-config.danger_decrease_rate * multiplier * delta_time);
+        } else if self.light_distance.is_some() {
+            self.health.change(config.restore_rate * delta_time);
+        } else {
+            self.health.change(-config.dark_decrease_rate * delta_time);
+        }
+    }
+
     pub fn update_light_distance(&mut self, light: &Light, last_rhythm: (usize, WaypointId)) {
         let (time, waypoint) = light.closest_waypoint;
         let at_waypoint = time.as_f32() > -COYOTE_TIME
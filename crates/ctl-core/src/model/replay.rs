@@ -0,0 +1,67 @@
+use super::*;
+
+/// Current version of the replay format.
+/// Bumped whenever the layout of [`Replay`] changes.
+pub const REPLAY_VERSION: u32 = 1;
+
+#[derive(thiserror::Error, Debug)]
+pub enum ReplayError {
+    #[error("unsupported replay version {0}, expected {REPLAY_VERSION}")]
+    Version(u32),
+    #[error("invalid replay data: {0}")]
+    Bincode(#[from] bincode::Error),
+}
+
+/// A recording of the player's input during a single run of a level.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Replay {
+    /// Version of the replay format, always the first field of the serialized data.
+    pub version: u32,
+    /// Id of the played level, `0` for local levels.
+    pub level: Id,
+    /// Hash of the level data that was played.
+    pub level_hash: String,
+    pub config: LevelConfig,
+    /// Time (in seconds) from which the level was started.
+    pub start_time: Time,
+    /// Fixed timestep (in seconds) between recorded frames.
+    pub delta_time: Time,
+    /// Target position of the cursor for every frame of the run.
+    pub frames: Vec<vec2<Coord>>,
+    /// The final score of the run.
+    pub score: Score,
+}
+
+impl Replay {
+    pub fn new(level: Id, level_hash: String, config: LevelConfig, start_time: Time) -> Self {
+        Self {
+            version: REPLAY_VERSION,
+            level,
+            level_hash,
+            score: Score::new(config.modifiers.multiplier()),
+            config,
+            start_time,
+            delta_time: Time::ZERO,
+            frames: Vec::new(),
+        }
+    }
+
+    /// Record a single frame of player input.
+    pub fn record(&mut self, player_target: vec2<Coord>, delta_time: Time) {
+        self.delta_time = delta_time;
+        self.frames.push(player_target);
+    }
+
+    pub fn encode(&self) -> Result<Vec<u8>, ReplayError> {
+        Ok(bincode::serialize(self)?)
+    }
+
+    /// Decode the replay checking that its version is supported.
+    pub fn decode(bytes: &[u8]) -> Result<Self, ReplayError> {
+        let version: u32 = bincode::deserialize(bytes)?;
+        if version != REPLAY_VERSION {
+            return Err(ReplayError::Version(version));
+        }
+        Ok(bincode::deserialize(bytes)?)
+    }
+}
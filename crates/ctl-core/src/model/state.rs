@@ -124,7 +124,7 @@ pub fn render_light(
                             let t = (relative_time - sustain_time) / fade_time;
                             sustain_scale * crate::util::smoothstep(Time::ONE - t)
                         };
-                        transform.scale *= scale;
+                        transform.scale = transform.scale * scale;
                         let mut tele = base_tele.clone();
                         tele.light.collider = base_light.collider.transformed(transform);
                         tele
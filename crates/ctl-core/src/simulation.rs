@@ -0,0 +1,483 @@
+use crate::prelude::*;
+
+/// The default timestep (in seconds) used by the simulation.
+/// Matches the fixed update rate of the game.
+pub const FIXED_DELTA_TIME: f32 = 1.0 / 60.0;
+
+/// Accepted range of the timestep (in seconds) of a replay.
+const REPLAY_DELTA_TIME_MIN: f32 = 1.0 / 480.0;
+const REPLAY_DELTA_TIME_MAX: f32 = 1.0 / 10.0;
+/// Time (in seconds) that a replay may last past the end of the level.
+const REPLAY_TIME_MARGIN: f32 = 5.0;
+
+/// Last collected rhythm before the player has collected any.
+pub const NO_RHYTHM: (usize, WaypointId) = (999, WaypointId::Frame(999)); // Should be never the first one
+
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub enum ReplaySimulationError {
+    #[error("replay timestep {0} is out of range")]
+    DeltaTime(Time),
+    #[error("replay has {frames} frames, but the level is over after {max}")]
+    TooLong { frames: usize, max: usize },
+}
+
+/// Scripted player input used to drive the simulation.
+pub trait CursorTrack {
+    /// Returns the target cursor position for the next frame of the simulation.
+    fn position(&mut self, simulation: &Simulation) -> vec2<Coord>;
+}
+
+impl<F: FnMut(&Simulation) -> vec2<Coord>> CursorTrack for F {
+    fn position(&mut self, simulation: &Simulation) -> vec2<Coord> {
+        self(simulation)
+    }
+}
+
+/// Cursor that follows the closest friendly light, used to auto-verify levels.
+#[derive(Debug, Clone, Default)]
+pub struct AutoCursor {
+    position: vec2<Coord>,
+}
+
+impl CursorTrack for AutoCursor {
+    fn position(&mut self, simulation: &Simulation) -> vec2<Coord> {
+        let target = simulation
+            .level_state
+            .lights
+            .iter()
+            .filter(|light| !light.danger)
+            .min_by_key(|light| (light.collider.position - self.position).len())
+            .map(|light| light.collider.position);
+        if let Some(target) = target {
+            self.position = target;
+        }
+        self.position
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SimulationState {
+    Playing,
+    Lost,
+    Finished,
+}
+
+/// A headless deterministic simulation of the gameplay.
+#[derive(Debug)]
+pub struct Simulation {
+    pub level: Level,
+    pub config: LevelConfig,
+    /// Timing of the music.
+    pub music: MusicInfo,
+
+    pub player: Player,
+    pub level_state: LevelState,
+    pub score: Score,
+    pub state: SimulationState,
+    /// Last collected rhythm (event_id, waypoint_id).
+    pub last_rhythm: (usize, WaypointId),
+
+    /// The number of frames simulated so far.
+    pub frame: usize,
+    /// Current time with beats as measure.
+    pub beat_time: Time,
+}
+
+/// The outcome of a finished simulation.
+#[derive(Debug, Clone)]
+pub struct SimulationResult {
+    pub score: Score,
+    /// Whether the level has been played to the end without losing.
+    pub completed: bool,
+    /// The number of frames simulated.
+    pub frames: usize,
+    /// The beat at which the simulation has ended.
+    pub beat_time: Time,
+}
+
+impl Simulation {
+    pub fn new(level: Level, config: LevelConfig, music: &MusicInfo) -> Self {
+        Self {
+            player: Player::new(
+                Collider::new(
+                    vec2::ZERO,
+                    Shape::Circle {
+                        radius: config.player.radius,
+                    },
+                ),
+                config.health.max,
+            ),
+            level_state: LevelState::default(),
+            score: Score::new(config.modifiers.multiplier()),
+            state: SimulationState::Playing,
+            last_rhythm: NO_RHYTHM,
+
+            frame: 0,
+            beat_time: Time::ZERO,
+
+            music: music.clone(),
+            level,
+            config,
+        }
+    }
+
+    /// Whether the simulation has finished (either by completing the level or by losing).
+    pub fn is_over(&self) -> bool {
+        !matches!(self.state, SimulationState::Playing)
+    }
+
+    /// Advance the simulation by a single frame.
+    pub fn update(&mut self, player_target: vec2<Coord>, delta_time: Time) -> Vec<GameEvent> {
+        let mut events = Vec::new();
+        if self.is_over() {
+            return events;
+        }
+
+        self.frame += 1;
+        self.player.collider.position = player_target;
+        self.beat_time = self
+            .music
+            .time_to_beat(self.music.beat_to_time(self.beat_time) + delta_time);
+
+        self.level_state = LevelState::render(&self.level, &self.config, self.beat_time, None);
+
+        events.extend(update_lights(
+            &mut self.player,
+            &mut self.score,
+            &mut self.last_rhythm,
+            &self.level_state,
+        ));
+
+        if self.level_state.is_finished {
+            self.state = SimulationState::Finished;
+            return events;
+        }
+
+        if self.config.modifiers.clean_auto {
+            return events;
+        }
+
+        events.extend(update_score(
+            &mut self.player,
+            &mut self.score,
+            &mut self.last_rhythm,
+            &self.level_state,
+            &self.config,
+            delta_time,
+        ));
+
+        if !self.config.modifiers.nofail && self.player.health.is_min() {
+            self.state = SimulationState::Lost;
+        }
+
+        events
+    }
+
+    /// Run the simulation until the end using the given cursor track.
+    pub fn run(mut self, mut cursor: impl CursorTrack, delta_time: Time) -> SimulationResult {
+        while !self.is_over() {
+            let target = cursor.position(&self);
+            self.update(target, delta_time);
+        }
+        self.result()
+    }
+
+    pub fn result(&self) -> SimulationResult {
+        SimulationResult {
+            score: self.score.clone(),
+            completed: self.state == SimulationState::Finished,
+            frames: self.frame,
+            beat_time: self.beat_time,
+        }
+    }
+}
+
+/// Discard the progress made while waiting for the player to start,
+/// so that the run starts in the same state as a new [`Simulation`].
+pub fn reset_run(
+    player: &mut Player,
+    score: &mut Score,
+    last_rhythm: &mut (usize, WaypointId),
+    config: &LevelConfig,
+) {
+    player.reset_distance();
+    player.health.set_ratio(Time::ONE);
+    *score = Score::new(config.modifiers.multiplier());
+    *last_rhythm = NO_RHYTHM;
+}
+
+/// Update the lights around the player and check for a missed rhythm.
+/// Shared by the game and the [`Simulation`].
+pub fn update_lights(
+    player: &mut Player,
+    score: &mut Score,
+    last_rhythm: &mut (usize, WaypointId),
+    level_state: &LevelState,
+) -> Option<GameEvent> {
+    if !player.update_lights(level_state, *last_rhythm) {
+        return None;
+    }
+    score.metrics.discrete.missed_rhythm();
+    collect_rhythm(player, last_rhythm, level_state);
+    Some(GameEvent::Rhythm { perfect: false })
+}
+
+/// Update the health and the score of the player.
+/// Shared by the game and the [`Simulation`].
+pub fn update_score(
+    player: &mut Player,
+    score: &mut Score,
+    last_rhythm: &mut (usize, WaypointId),
+    level_state: &LevelState,
+    config: &LevelConfig,
+    delta_time: Time,
+) -> Vec<GameEvent> {
+    player.update_health(&config.health, delta_time);
+    let events = score.update(player, delta_time);
+    if !events.is_empty() {
+        collect_rhythm(player, last_rhythm, level_state);
+    }
+    events
+}
+
+fn collect_rhythm(
+    player: &Player,
+    last_rhythm: &mut (usize, WaypointId),
+    level_state: &LevelState,
+) {
+    if let Some(rhythm) = player.closest_waypoint(level_state) {
+        *last_rhythm = rhythm;
+    }
+}
+
+/// Simulate the level with the cursor track at a fixed timestep.
+pub fn simulate(
+    level: &Level,
+    config: &LevelConfig,
+    music: &MusicInfo,
+    cursor: impl CursorTrack,
+) -> SimulationResult {
+    Simulation::new(level.clone(), config.clone(), music).run(cursor, r32(FIXED_DELTA_TIME))
+}
+
+/// Auto-play the level following the friendly lights
+/// to check that it can be completed.
+pub fn verify(level: &Level, config: &LevelConfig, music: &MusicInfo) -> SimulationResult {
+    simulate(level, config, music, AutoCursor::default())
+}
+
+/// Maximum number of frames that a replay of the level can contain.
+pub fn max_replay_frames(
+    level: &Level,
+    music: &MusicInfo,
+    start_time: Time,
+    delta_time: Time,
+) -> usize {
+    let duration = (music.beat_to_time(level.last_beat()) - start_time)
+        .as_f32()
+        .max(0.0);
+    ((duration + REPLAY_TIME_MARGIN) / delta_time.as_f32()).ceil() as usize
+}
+
+/// Re-simulate the recorded inputs of the replay.
+/// The simulation stops when the recording ends even if the level is not over.
+///
+/// Replays with an unusual timestep or more frames than the level can last are rejected,
+/// so that the simulation always takes a bounded time.
+pub fn simulate_replay(
+    level: &Level,
+    replay: &Replay,
+    music: &MusicInfo,
+) -> Result<SimulationResult, ReplaySimulationError> {
+    if !(REPLAY_DELTA_TIME_MIN..=REPLAY_DELTA_TIME_MAX).contains(&replay.delta_time.as_f32()) {
+        return Err(ReplaySimulationError::DeltaTime(replay.delta_time));
+    }
+    let max = max_replay_frames(level, music, replay.start_time, replay.delta_time);
+    if replay.frames.len() > max {
+        return Err(ReplaySimulationError::TooLong {
+            frames: replay.frames.len(),
+            max,
+        });
+    }
+
+    let mut simulation = Simulation::new(level.clone(), replay.config.clone(), music);
+    simulation.beat_time = music.time_to_beat(replay.start_time);
+    for &target in &replay.frames {
+        if simulation.is_over() {
+            break;
+        }
+        simulation.update(target, replay.delta_time);
+    }
+    Ok(simulation.result())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A single friendly light that stays in the center for a few beats.
+    fn test_level() -> Level {
+        let mut movement = Movement::default();
+        movement.key_frames.push_back(MoveFrame::scale(4.0, 1.0));
+        Level {
+            events: vec![TimedEvent {
+                beat: r32(1.0),
+                event: Event::Light(LightEvent {
+                    light: LightSerde {
+                        danger: false,
+                        shape: Shape::Circle { radius: r32(1.0) },
+                        movement,
+                    },
+                    telegraph: Telegraph::default(),
+                }),
+            }],
+        }
+    }
+
+    #[test]
+    fn auto_cursor_completes_level() {
+        let result = verify(
+            &test_level(),
+            &LevelConfig::default(),
+            &MusicInfo::default(),
+        );
+        assert!(result.completed);
+        assert!(result.score.calculated.combined > 0);
+    }
+
+    #[test]
+    fn staying_in_the_dark_loses() {
+        let level = test_level();
+        let music = MusicInfo::default();
+        let away = |_: &Simulation| vec2(10.0, 10.0).as_r32();
+
+        let result = simulate(&level, &LevelConfig::default(), &music, away);
+        assert!(!result.completed);
+
+        let nofail = LevelConfig {
+            modifiers: LevelModifiers {
+                nofail: true,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let result = simulate(&level, &nofail, &music, away);
+        assert!(result.completed);
+        assert_eq!(result.score.calculated.combined, 0);
+    }
+
+    /// Play the level following the light and record the inputs.
+    fn record(
+        level: &Level,
+        config: &LevelConfig,
+        music: &MusicInfo,
+    ) -> (Replay, SimulationResult) {
+        let delta_time = r32(FIXED_DELTA_TIME);
+        let mut simulation = Simulation::new(level.clone(), config.clone(), music);
+        let mut cursor = AutoCursor::default();
+        let mut replay = Replay::new(0, level.calculate_hash(), config.clone(), Time::ZERO);
+        while !simulation.is_over() {
+            let target = cursor.position(&simulation);
+            replay.record(target, delta_time);
+            simulation.update(target, delta_time);
+        }
+        (replay, simulation.result())
+    }
+
+    #[test]
+    fn replay_reproduces_score() {
+        let level = test_level();
+        let music = MusicInfo::default();
+        let (replay, recorded) = record(&level, &LevelConfig::default(), &music);
+        assert!(recorded.completed);
+
+        let simulated = simulate_replay(&level, &replay, &music).unwrap();
+        assert!(simulated.completed);
+        assert_eq!(simulated.frames, recorded.frames);
+        assert_eq!(
+            simulated.score.calculated.combined,
+            recorded.score.calculated.combined
+        );
+    }
+
+    #[test]
+    fn start_up_does_not_affect_replay() {
+        let level = test_level();
+        let config = LevelConfig::default();
+        let music = MusicInfo::default();
+        let delta_time = r32(FIXED_DELTA_TIME);
+        // Start while the light is visible, so that the player waits inside of it
+        let start_beat = r32(2.0);
+
+        // Same as the game: the lights are frozen until the player is ready,
+        // and only the frames after that are recorded
+        let mut game = Simulation::new(level.clone(), config.clone(), &music);
+        game.beat_time = start_beat;
+        game.level_state = LevelState::render(&level, &config, start_beat, None);
+        for _ in 0..60 {
+            update_lights(
+                &mut game.player,
+                &mut game.score,
+                &mut game.last_rhythm,
+                &game.level_state,
+            );
+        }
+        reset_run(
+            &mut game.player,
+            &mut game.score,
+            &mut game.last_rhythm,
+            &config,
+        );
+
+        let start_time = music.beat_to_time(start_beat);
+        let mut replay = Replay::new(0, level.calculate_hash(), config.clone(), start_time);
+        let mut cursor = AutoCursor::default();
+        while !game.is_over() {
+            let target = cursor.position(&game);
+            replay.record(target, delta_time);
+            game.update(target, delta_time);
+        }
+
+        let simulated = simulate_replay(&level, &replay, &music).unwrap();
+        assert_eq!(simulated.frames, game.frame);
+        assert_eq!(simulated.completed, game.state == SimulationState::Finished);
+        let (simulated, played) = (&simulated.score, &game.score);
+        assert_eq!(simulated.calculated.combined, played.calculated.combined);
+        assert_eq!(simulated.calculated.accuracy, played.calculated.accuracy);
+        assert_eq!(simulated.calculated.precision, played.calculated.precision);
+        assert_eq!(
+            simulated.metrics.discrete.total,
+            played.metrics.discrete.total
+        );
+    }
+
+    #[test]
+    fn replay_too_long() {
+        let level = test_level();
+        let music = MusicInfo::default();
+        let (mut replay, _) = record(&level, &LevelConfig::default(), &music);
+        let max = max_replay_frames(&level, &music, Time::ZERO, replay.delta_time);
+        replay.frames.resize(max + 1, vec2::ZERO);
+
+        assert_eq!(
+            simulate_replay(&level, &replay, &music).unwrap_err(),
+            ReplaySimulationError::TooLong {
+                frames: max + 1,
+                max
+            }
+        );
+    }
+
+    #[test]
+    fn replay_invalid_timestep() {
+        let level = test_level();
+        let music = MusicInfo::default();
+        let (mut replay, _) = record(&level, &LevelConfig::default(), &music);
+        replay.delta_time = Time::ZERO;
+
+        assert_eq!(
+            simulate_replay(&level, &replay, &music).unwrap_err(),
+            ReplaySimulationError::DeltaTime(Time::ZERO)
+        );
+    }
+}
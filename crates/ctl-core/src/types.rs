@@ -14,12 +14,24 @@ pub struct LevelSet<L = Rc<LevelFull>> {
 }
 
 impl<T: Serialize> LevelSet<T> {
+    /// Encode into a group file with the current version header.
+    pub fn encode(&self) -> bincode::Result<Vec<u8>> {
+        crate::migration::encode_group(self)
+    }
+
     pub fn calculate_hash(&self) -> String {
         let bytes = bincode::serialize(self).expect("group should be serializable");
         crate::util::calculate_hash(&bytes)
     }
 }
 
+impl<L: DeserializeOwned + From<LevelFull>> LevelSet<L> {
+    /// Decode a group file, migrating it from older versions if needed.
+    pub fn decode(bytes: &[u8]) -> Result<Self, crate::migration::GroupFormatError> {
+        crate::migration::decode_group(bytes)
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct LevelFull {
     pub meta: LevelInfo,
@@ -33,10 +45,22 @@ pub struct GroupInfo {
     pub id: Id,
     pub music: MusicInfo,
     pub owner: UserInfo,
+    /// Co-authors of the whole group, who can change any of its difficulties.
+    #[serde(default)]
+    pub authors: Vec<UserInfo>,
     pub levels: Vec<LevelInfo>,
     pub hash: String,
 }
 
+/// A revision of a group kept by the server.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GroupVersion {
+    pub id: Id,
+    pub hash: String,
+    /// Unix timestamp of the upload, `0` if unknown.
+    pub uploaded_at: i64,
+}
+
 #[derive(geng::asset::Load, Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 #[load(serde = "toml")]
 pub struct MusicInfo {
@@ -49,6 +73,15 @@ pub struct MusicInfo {
     pub romanized: Name,
     pub bpm: R32,
     pub authors: Vec<ArtistInfo>,
+    /// Time (in seconds) of the first beat in the track.
+    #[serde(default)]
+    pub offset: Time,
+    /// Tempo changes after the first beat.
+    #[serde(default)]
+    pub tempo: TempoMap,
+    /// Duration (in seconds) of the track, `0` if unknown.
+    #[serde(default)]
+    pub duration: Time,
 }
 
 impl Default for MusicInfo {
@@ -61,6 +94,128 @@ impl Default for MusicInfo {
             romanized: "<romanized>".into(),
             bpm: r32(60.0),
             authors: Vec::new(),
+            offset: Time::ZERO,
+            tempo: TempoMap::default(),
+            duration: Time::ZERO,
+        }
+    }
+}
+
+/// Changes of the tempo over the course of the music.
+/// Before the first section the music plays at [`MusicInfo::bpm`] in 4/4.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(default)]
+pub struct TempoMap {
+    /// Sections sorted by their starting beat.
+    pub sections: Vec<TempoSection>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub struct TempoSection {
+    /// Beat at which the section starts.
+    pub beat: Time,
+    pub bpm: R32,
+    /// Number of beats in a bar, the numerator of the time signature.
+    pub beats_per_bar: u32,
+}
+
+#[derive(thiserror::Error, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TempoError {
+    #[error("Tempo section at beat {0} has an invalid starting beat")]
+    InvalidBeat(Time),
+    #[error("Tempo section at beat {0} has an invalid bpm")]
+    InvalidBpm(Time),
+    #[error("Tempo section at beat {0} has no beats in a bar")]
+    EmptyBar(Time),
+    #[error("Tempo section at beat {0} is out of order or duplicated")]
+    Unsorted(Time),
+}
+
+impl TempoMap {
+    /// Check that the sections are sorted by unique non-negative beats and have valid tempos.
+    pub fn validate(&self) -> Result<(), TempoError> {
+        let mut last: Option<Time> = None;
+        for section in &self.sections {
+            let beat = section.beat;
+            if !beat.as_f32().is_finite() || beat < Time::ZERO {
+                return Err(TempoError::InvalidBeat(beat));
+            }
+            if !section.bpm.as_f32().is_finite() || section.bpm <= R32::ZERO {
+                return Err(TempoError::InvalidBpm(beat));
+            }
+            if section.beats_per_bar == 0 {
+                return Err(TempoError::EmptyBar(beat));
+            }
+            if last.is_some_and(|last| beat <= last) {
+                return Err(TempoError::Unsorted(beat));
+            }
+            last = Some(beat);
+        }
+        Ok(())
+    }
+
+    /// Insert the section keeping the order, replacing the one that starts on the same beat.
+    pub fn insert(&mut self, section: TempoSection) {
+        match self
+            .sections
+            .binary_search_by_key(&section.beat, |section| section.beat)
+        {
+            Ok(i) => self.sections[i] = section,
+            Err(i) => self.sections.insert(i, section),
+        }
+    }
+
+    /// Remove the section that starts on the given beat.
+    pub fn remove(&mut self, beat: Time) -> Option<TempoSection> {
+        let i = self
+            .sections
+            .iter()
+            .position(|section| section.beat == beat)?;
+        Some(self.sections.remove(i))
+    }
+}
+
+/// Container format of a music file.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum MusicFormat {
+    Mp3,
+    Ogg,
+    Wav,
+    Flac,
+}
+
+impl MusicFormat {
+    /// Detect the format by looking at the first bytes of the file.
+    pub fn detect(data: &[u8]) -> Option<Self> {
+        if data.starts_with(b"ID3") || matches!(data, [0xFF, second, ..] if second & 0xE0 == 0xE0) {
+            Some(Self::Mp3)
+        } else if data.starts_with(b"OggS") {
+            Some(Self::Ogg)
+        } else if data.starts_with(b"RIFF") && data.get(8..12) == Some(b"WAVE".as_slice()) {
+            Some(Self::Wav)
+        } else if data.starts_with(b"fLaC") {
+            Some(Self::Flac)
+        } else {
+            None
+        }
+    }
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Mp3 => "mp3",
+            Self::Ogg => "ogg",
+            Self::Wav => "wav",
+            Self::Flac => "flac",
+        }
+    }
+
+    pub fn mime_type(self) -> &'static str {
+        match self {
+            Self::Mp3 => "audio/mpeg",
+            Self::Ogg => "audio/ogg",
+            Self::Wav => "audio/wav",
+            Self::Flac => "audio/flac",
         }
     }
 }
@@ -71,21 +226,101 @@ impl GroupInfo {
         let mut authors: Vec<&str> = self
             .levels
             .iter()
-            .flat_map(|level| level.authors.iter().map(|user| user.name.as_ref()))
+            .flat_map(|level| level.authors.iter())
+            .chain(&self.authors)
+            .map(|user| user.name.as_ref())
             .collect();
         authors.sort();
         authors.dedup();
 
         itertools::Itertools::intersperse(authors.into_iter(), ", ").collect::<String>()
     }
+
+    /// Whether the user is allowed to upload changes to the group.
+    /// Authors of a single difficulty can only change that difficulty.
+    pub fn can_edit(&self, user: Id) -> bool {
+        self.owner.id == user
+            || self.authors.iter().any(|author| author.id == user)
+            || self
+                .levels
+                .iter()
+                .any(|level| level.authors.iter().any(|author| author.id == user))
+    }
 }
 
 impl MusicInfo {
-    /// Returns the duration (in seconds) of a single beat.
+    /// Returns the duration (in seconds) of a single beat at the start of the music.
     pub fn beat_time(&self) -> Time {
         r32(60.0) / self.bpm
     }
 
+    /// Returns the tempo section active at the given beat, `None` before the first tempo change.
+    pub fn section_at(&self, beat: Time) -> Option<&TempoSection> {
+        self.tempo
+            .sections
+            .iter()
+            .rev()
+            .find(|section| section.beat <= beat)
+    }
+
+    pub fn bpm_at(&self, beat: Time) -> R32 {
+        self.section_at(beat)
+            .map_or(self.bpm, |section| section.bpm)
+    }
+
+    pub fn beats_per_bar_at(&self, beat: Time) -> u32 {
+        self.section_at(beat)
+            .map_or(4, |section| section.beats_per_bar)
+    }
+
+    /// Returns the duration (in seconds) of a single beat at the given beat.
+    pub fn beat_time_at(&self, beat: Time) -> Time {
+        r32(60.0) / self.bpm_at(beat)
+    }
+
+    /// Returns the time (in seconds) since the first beat at which the beat is played.
+    pub fn beat_to_time(&self, beat: Time) -> Time {
+        let mut time = Time::ZERO;
+        let mut start = Time::ZERO;
+        let mut beat_time = self.beat_time();
+        for section in &self.tempo.sections {
+            if section.beat >= beat {
+                break;
+            }
+            time += (section.beat - start) * beat_time;
+            start = section.beat;
+            beat_time = r32(60.0) / section.bpm;
+        }
+        time + (beat - start) * beat_time
+    }
+
+    /// Returns the beat played at the given time (in seconds) since the first beat.
+    pub fn time_to_beat(&self, time: Time) -> Time {
+        let mut section_time = Time::ZERO;
+        let mut start = Time::ZERO;
+        let mut beat_time = self.beat_time();
+        for section in &self.tempo.sections {
+            let next_time = section_time + (section.beat - start) * beat_time;
+            if next_time >= time {
+                break;
+            }
+            section_time = next_time;
+            start = section.beat;
+            beat_time = r32(60.0) / section.bpm;
+        }
+        start + (time - section_time) / beat_time
+    }
+
+    /// Returns the beat at the end of the track, `None` if the duration is unknown.
+    pub fn last_beat(&self) -> Option<Time> {
+        (self.duration > Time::ZERO).then(|| self.time_to_beat(self.duration - self.offset))
+    }
+
+    /// Returns the time (in seconds) in the music track at which the beat is played.
+    pub fn beat_to_music_time(&self, beat: Time) -> Time {
+        self.offset + self.beat_to_time(beat)
+    }
+
     /// Return the list of authors in a readable string format.
     pub fn authors(&self) -> String {
         let authors = self.authors.iter().map(|author| author.name.as_ref());
@@ -101,6 +336,9 @@ pub struct LevelInfo {
     pub name: Name,
     pub authors: Vec<UserInfo>,
     pub hash: String,
+    /// Estimated difficulty, see [`crate::difficulty`].
+    /// `None` if the level has not been rated yet.
+    pub difficulty: Option<R32>,
 }
 
 impl Default for LevelInfo {
@@ -110,6 +348,7 @@ impl Default for LevelInfo {
             name: "<level>".into(),
             authors: Vec::new(),
             hash: "".into(),
+            difficulty: None,
         }
     }
 }
@@ -128,6 +367,69 @@ pub struct UserInfo {
     pub name: Name,
 }
 
+/// Public information about a user.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UserProfile {
+    pub user: UserInfo,
+    /// Groups uploaded by the user.
+    pub groups: Vec<GroupInfo>,
+    /// Best scores of the user in every level and category.
+    pub scores: Vec<UserScore>,
+    /// Total number of submitted runs.
+    pub plays: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UserScore {
+    pub level: Id,
+    pub group: Id,
+    pub level_name: Name,
+    pub music_name: Name,
+    pub score: i32,
+    pub category: crate::ScoreCategory,
+    /// Number of submitted runs on that level in the same category.
+    pub plays: u32,
+}
+
+/// Upload limits of a user and how much of them is used.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UserQuota {
+    /// Maximum number of groups the user can own.
+    pub groups: u32,
+    /// Maximum number of groups the user can own for the same music.
+    pub groups_per_song: u32,
+    /// Music of every group owned by the user.
+    pub owned_groups: Vec<Id>,
+    /// Maximum size of a group file (in bytes).
+    pub group_size: usize,
+    /// Maximum size of a music file (in bytes).
+    pub music_size: usize,
+    /// Shortest allowed level duration (in seconds).
+    pub level_min_duration: f32,
+}
+
+impl UserQuota {
+    /// Number of groups the user can still upload.
+    pub fn remaining(&self) -> u32 {
+        self.groups.saturating_sub(self.owned_groups.len() as u32)
+    }
+
+    /// Number of groups the user can still upload for the music.
+    pub fn remaining_for(&self, music: Id) -> u32 {
+        let owned = self.owned_groups.iter().filter(|&&id| id == music).count() as u32;
+        self.groups_per_song
+            .saturating_sub(owned)
+            .min(self.remaining())
+    }
+}
+
+/// Quotas of a specific user, `None` to use the server defaults.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct QuotaOverride {
+    pub groups: Option<u32>,
+    pub groups_per_song: Option<u32>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub struct UserLogin {
     pub id: Id,
@@ -149,7 +451,10 @@ pub struct NewMusic {
     pub name: String,
     pub romanized_name: String,
     pub original: bool,
-    pub bpm: f32,
+    /// Detected from the audio if not specified.
+    pub bpm: Option<f32>,
+    /// Detected from the audio if not specified.
+    pub offset: Option<f32>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -158,6 +463,8 @@ pub struct MusicUpdate {
     pub public: Option<bool>,
     pub original: Option<bool>,
     pub bpm: Option<f32>,
+    pub offset: Option<f32>,
+    pub tempo: Option<TempoMap>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -172,7 +479,120 @@ pub struct NewArtist {
     pub user: Option<Id>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
 pub struct GroupsQuery {
     pub recommended: bool,
+    /// Text to search for in the music name, romanized name, and mappers.
+    pub search: Option<String>,
+    /// Only groups for the music by that artist.
+    pub artist: Option<Id>,
+    /// Only groups owned by that user.
+    pub owner: Option<Id>,
+    pub bpm_min: Option<f32>,
+    pub bpm_max: Option<f32>,
+    pub sort: GroupsSort,
+    /// Cursor from the previous page ([`GroupsPage::next`]) to continue from.
+    pub cursor: Option<String>,
+    /// Maximum number of groups in a page.
+    pub limit: Option<u32>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GroupsSort {
+    #[default]
+    Newest,
+    /// By the number of submitted scores, counting every play of the same player.
+    MostPlayed,
+    Name,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GroupsPage {
+    pub groups: Vec<GroupInfo>,
+    /// Cursor to fetch the next page, `None` if this is the last page.
+    pub next: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn section(beat: f32, bpm: f32, beats_per_bar: u32) -> TempoSection {
+        TempoSection {
+            beat: r32(beat),
+            bpm: r32(bpm),
+            beats_per_bar,
+        }
+    }
+
+    /// 120 bpm for 4 beats, then 60 bpm in 3/4.
+    fn test_music() -> MusicInfo {
+        MusicInfo {
+            bpm: r32(120.0),
+            tempo: TempoMap {
+                sections: vec![section(4.0, 60.0, 3)],
+            },
+            ..default()
+        }
+    }
+
+    #[test]
+    fn beat_to_time_with_sections() {
+        let music = test_music();
+        assert_eq!(music.beat_to_time(r32(2.0)), r32(1.0));
+        assert_eq!(music.beat_to_time(r32(4.0)), r32(2.0));
+        assert_eq!(music.beat_to_time(r32(6.0)), r32(4.0));
+        assert_eq!(music.beats_per_bar_at(r32(2.0)), 4);
+        assert_eq!(music.beats_per_bar_at(r32(5.0)), 3);
+    }
+
+    #[test]
+    fn time_to_beat_with_sections() {
+        let music = test_music();
+        assert_eq!(music.time_to_beat(r32(1.0)), r32(2.0));
+        assert_eq!(music.time_to_beat(r32(2.0)), r32(4.0));
+        assert_eq!(music.time_to_beat(r32(4.0)), r32(6.0));
+    }
+
+    #[test]
+    fn tempo_round_trip() {
+        let mut music = test_music();
+        music.tempo.insert(section(10.0, 180.0, 4));
+        for i in 0..64 {
+            let beat = r32(i as f32 * 0.25);
+            let time = music.beat_to_time(beat);
+            assert!((music.time_to_beat(time) - beat).abs() < r32(1e-4));
+        }
+    }
+
+    #[test]
+    fn validate_tempo() {
+        let valid = TempoMap {
+            sections: vec![section(0.0, 120.0, 4), section(8.0, 90.0, 3)],
+        };
+        assert_eq!(valid.validate(), Ok(()));
+
+        let invalid = [
+            (section(-1.0, 120.0, 4), TempoError::InvalidBeat(r32(-1.0))),
+            (section(1.0, 0.0, 4), TempoError::InvalidBpm(r32(1.0))),
+            (section(1.0, 120.0, 0), TempoError::EmptyBar(r32(1.0))),
+        ];
+        for (section, error) in invalid {
+            let tempo = TempoMap {
+                sections: vec![section],
+            };
+            assert_eq!(tempo.validate(), Err(error));
+        }
+
+        let unsorted = TempoMap {
+            sections: vec![section(8.0, 120.0, 4), section(4.0, 90.0, 4)],
+        };
+        assert_eq!(unsorted.validate(), Err(TempoError::Unsorted(r32(4.0))));
+        let duplicate = TempoMap {
+            sections: vec![section(4.0, 120.0, 4), section(4.0, 90.0, 4)],
+        };
+        assert_eq!(duplicate.validate(), Err(TempoError::Unsorted(r32(4.0))));
+    }
 }
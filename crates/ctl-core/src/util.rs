@@ -6,3 +6,25 @@ pub fn calculate_hash(bytes: &[u8]) -> String {
     hasher.update(bytes);
     HEXLOWER.encode(hasher.finalize().as_ref())
 }
+
+pub fn smoothstep<T: geng::prelude::Float>(t: T) -> T {
+    T::from_f32(3.0) * t * t - T::from_f32(2.0) * t * t * t
+}
+
+/// Evaluate a Bezier curve defined by the control points at `t` in range `0..=1`.
+pub fn bezier<T: geng::prelude::Float>(
+    points: &[geng::prelude::vec2<T>],
+    t: T,
+) -> geng::prelude::vec2<T> {
+    let mut points = points.to_vec();
+    while points.len() > 1 {
+        for i in 0..points.len() - 1 {
+            points[i] = points[i] + (points[i + 1] - points[i]) * t;
+        }
+        points.pop();
+    }
+    points
+        .first()
+        .copied()
+        .unwrap_or(geng::prelude::vec2(T::ZERO, T::ZERO))
+}
@@ -0,0 +1 @@
+ALTER TABLE scores ADD COLUMN verified BIT NOT NULL DEFAULT 0;
@@ -0,0 +1,18 @@
+ALTER TABLE scores ADD COLUMN nofail BIT NOT NULL DEFAULT 0;
+ALTER TABLE scores ADD COLUMN sudden BIT NOT NULL DEFAULT 0;
+ALTER TABLE scores ADD COLUMN hidden BIT NOT NULL DEFAULT 0;
+ALTER TABLE scores ADD COLUMN health TEXT NOT NULL DEFAULT 'normal';
+
+-- Recover the categories of existing scores from the client metadata
+UPDATE scores SET
+    nofail = coalesce(json_extract(extra_info, '$.category.mods.nofail'), 0),
+    sudden = coalesce(json_extract(extra_info, '$.category.mods.sudden'), 0),
+    hidden = coalesce(json_extract(extra_info, '$.category.mods.hidden'), 0),
+    -- Anything but the normal preset (missing fields default to it) is custom
+    health = CASE WHEN
+        abs(coalesce(json_extract(extra_info, '$.category.health.max'), 1.0) - 1.0) < 1e-4
+        AND abs(coalesce(json_extract(extra_info, '$.category.health.dark_decrease_rate'), 0.6) - 0.6) < 1e-4
+        AND abs(coalesce(json_extract(extra_info, '$.category.health.danger_decrease_rate'), 0.75) - 0.75) < 1e-4
+        AND abs(coalesce(json_extract(extra_info, '$.category.health.restore_rate'), 0.35) - 0.35) < 1e-4
+    THEN 'normal' ELSE 'custom' END
+WHERE json_valid(extra_info);
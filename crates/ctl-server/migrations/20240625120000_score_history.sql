@@ -0,0 +1,16 @@
+CREATE TABLE score_history
+(
+    level_id INTEGER NOT NULL,
+    user_id INTEGER NOT NULL,
+    score INTEGER NOT NULL,
+    accuracy REAL NOT NULL,
+    precision REAL NOT NULL,
+    verified BIT NOT NULL,
+    nofail BIT NOT NULL,
+    sudden BIT NOT NULL,
+    hidden BIT NOT NULL,
+    health TEXT NOT NULL,
+    submitted_at INTEGER NOT NULL,
+    FOREIGN KEY(level_id) REFERENCES levels(level_id),
+    FOREIGN KEY(user_id) REFERENCES users(user_id)
+);
@@ -0,0 +1,3 @@
+ALTER TABLE musics ADD COLUMN format TEXT NOT NULL DEFAULT 'mp3';
+ALTER TABLE musics ADD COLUMN duration REAL NOT NULL DEFAULT 0;
+ALTER TABLE musics ADD COLUMN waveform BLOB;
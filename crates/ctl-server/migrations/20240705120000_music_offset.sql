@@ -0,0 +1 @@
+ALTER TABLE musics ADD COLUMN beat_offset REAL NOT NULL DEFAULT 0;
@@ -0,0 +1 @@
+ALTER TABLE musics ADD COLUMN tempo TEXT NOT NULL DEFAULT '{}';
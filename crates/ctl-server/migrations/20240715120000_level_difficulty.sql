@@ -0,0 +1 @@
+ALTER TABLE levels ADD COLUMN difficulty REAL;
@@ -0,0 +1,30 @@
+CREATE TABLE group_versions
+(
+    version_id INTEGER NOT NULL PRIMARY KEY AUTOINCREMENT,
+    group_id INTEGER NOT NULL,
+    hash BLOB NOT NULL,
+    uploaded_at INTEGER NOT NULL,
+    FOREIGN KEY(group_id) REFERENCES groups(group_id)
+);
+
+-- Scores of level revisions that have been replaced
+CREATE TABLE archived_scores
+(
+    version_id INTEGER NOT NULL,
+    level_id INTEGER NOT NULL,
+    user_id INTEGER NOT NULL,
+    score INTEGER NOT NULL,
+    extra_info TEXT,
+    verified BIT NOT NULL,
+    nofail BIT NOT NULL,
+    sudden BIT NOT NULL,
+    hidden BIT NOT NULL,
+    health TEXT NOT NULL,
+    FOREIGN KEY(version_id) REFERENCES group_versions(version_id),
+    FOREIGN KEY(level_id) REFERENCES levels(level_id),
+    FOREIGN KEY(user_id) REFERENCES users(user_id)
+);
+
+-- The current version of existing groups, the upload time is unknown
+INSERT INTO group_versions (group_id, hash, uploaded_at)
+SELECT group_id, hash, 0 FROM groups;
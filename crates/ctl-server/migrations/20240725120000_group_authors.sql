@@ -0,0 +1,8 @@
+-- Co-authors of whole groups, besides the owner
+CREATE TABLE group_authors
+(
+    group_id INTEGER NOT NULL,
+    user_id INTEGER NOT NULL,
+    FOREIGN KEY(group_id) REFERENCES groups(group_id),
+    FOREIGN KEY(user_id) REFERENCES users(user_id)
+);
@@ -0,0 +1,8 @@
+-- Upload quotas of specific users, overriding the server config
+CREATE TABLE user_quotas
+(
+    user_id INTEGER NOT NULL PRIMARY KEY,
+    groups INTEGER,
+    groups_per_song INTEGER,
+    FOREIGN KEY(user_id) REFERENCES users(user_id)
+);
@@ -0,0 +1,45 @@
+//! Transcoding of uploaded music.
+
+use std::num::{NonZeroU32, NonZeroU8};
+
+use ctl_core::audio::Audio;
+use vorbis_rs::{VorbisEncoderBuilder, VorbisError};
+
+/// Number of bars in the preview waveform.
+pub const WAVEFORM_RESOLUTION: usize = 256;
+
+/// Number of frames passed to the vorbis encoder at once.
+const ENCODE_BLOCK_SIZE: usize = 4096;
+
+#[derive(thiserror::Error, Debug)]
+pub enum EncodeError {
+    #[error("audio contains no samples")]
+    Empty,
+    #[error("failed to encode audio: {0}")]
+    Encode(#[from] VorbisError),
+}
+
+/// Encode into an OGG Vorbis file.
+pub fn encode_ogg(audio: &Audio) -> Result<Vec<u8>, EncodeError> {
+    let sample_rate = NonZeroU32::new(audio.sample_rate).ok_or(EncodeError::Empty)?;
+    let channels = u8::try_from(audio.channels.len())
+        .ok()
+        .and_then(NonZeroU8::new)
+        .ok_or(EncodeError::Empty)?;
+
+    let mut bytes = Vec::new();
+    let mut encoder = VorbisEncoderBuilder::new(sample_rate, channels, &mut bytes)?.build()?;
+    let frames = audio.frames();
+    for start in (0..frames).step_by(ENCODE_BLOCK_SIZE) {
+        let end = (start + ENCODE_BLOCK_SIZE).min(frames);
+        let block: Vec<&[f32]> = audio
+            .channels
+            .iter()
+            .map(|channel| &channel[start..end])
+            .collect();
+        encoder.encode_audio_block(&block)?;
+    }
+    encoder.finish()?;
+
+    Ok(bytes)
+}
@@ -26,8 +26,14 @@ pub enum RequestError {
     InvalidName(String),
     #[error("Level data is invalid")]
     InvalidLevel,
-    // #[error("User {0} not found")]
-    // NoSuchUser(Id),
+    #[error("Level {level} has a problem at beat {beat:.2}: {message}")]
+    LevelLint {
+        level: String,
+        beat: f32,
+        message: String,
+    },
+    #[error("User {0} not found")]
+    NoSuchUser(Id),
     #[error("Artist {0} not found")]
     NoSuchArtist(Id),
     #[error("Group {0} not found")]
@@ -36,8 +42,26 @@ pub enum RequestError {
     NoSuchMusic(Id),
     #[error("Level {0} not found")]
     NoSuchLevel(Id),
+    #[error("Group version {0} not found")]
+    NoSuchGroupVersion(Id),
     #[error("Such a level already exists")]
     LevelAlreadyExists,
+    #[error("Level {0} does not belong to the group")]
+    LevelNotInGroup(Id),
+    #[error("Replay does not match the submitted score")]
+    InvalidReplay,
+    #[error("Invalid page cursor")]
+    InvalidCursor,
+    #[error("The music file is too large")]
+    MusicTooLarge,
+    #[error("The file is not a supported audio format")]
+    InvalidMusic,
+    #[error("Music is not available in that format")]
+    UnavailableMusicFormat,
+    #[error("Could not detect the BPM of the music, specify it manually")]
+    UnknownBpm,
+    #[error("Invalid tempo: {0}")]
+    InvalidTempo(String),
     #[error("Server error")]
     FileNotFound(String),
     #[error("Database error")]
@@ -60,13 +84,23 @@ impl RequestError {
             RequestError::InvalidCredentials => StatusCode::UNAUTHORIZED,
             RequestError::InvalidName(_) => StatusCode::BAD_REQUEST,
             RequestError::InvalidLevel => StatusCode::BAD_REQUEST,
+            RequestError::LevelLint { .. } => StatusCode::BAD_REQUEST,
             RequestError::FileNotFound(_) => StatusCode::NOT_FOUND,
-            // RequestError::NoSuchUser(_) => StatusCode::NOT_FOUND,
+            RequestError::NoSuchUser(_) => StatusCode::NOT_FOUND,
             RequestError::NoSuchArtist(_) => StatusCode::NOT_FOUND,
             RequestError::NoSuchMusic(_) => StatusCode::NOT_FOUND,
             RequestError::NoSuchGroup(_) => StatusCode::NOT_FOUND,
             RequestError::NoSuchLevel(_) => StatusCode::NOT_FOUND,
+            RequestError::NoSuchGroupVersion(_) => StatusCode::NOT_FOUND,
             RequestError::LevelAlreadyExists => StatusCode::CONFLICT,
+            RequestError::LevelNotInGroup(_) => StatusCode::BAD_REQUEST,
+            RequestError::InvalidReplay => StatusCode::BAD_REQUEST,
+            RequestError::InvalidCursor => StatusCode::BAD_REQUEST,
+            RequestError::MusicTooLarge => StatusCode::PAYLOAD_TOO_LARGE,
+            RequestError::InvalidMusic => StatusCode::UNSUPPORTED_MEDIA_TYPE,
+            RequestError::UnavailableMusicFormat => StatusCode::NOT_FOUND,
+            RequestError::UnknownBpm => StatusCode::BAD_REQUEST,
+            RequestError::InvalidTempo(_) => StatusCode::BAD_REQUEST,
             RequestError::Sql(_) => StatusCode::INTERNAL_SERVER_ERROR,
             RequestError::Io(_) => StatusCode::INTERNAL_SERVER_ERROR,
         }
@@ -1,6 +1,10 @@
 use super::*;
 
-use ctl_core::types::{ArtistInfo, UserInfo};
+use ctl_core::{
+    prelude::HealthPreset,
+    types::{ArtistInfo, MusicFormat, TempoMap, UserInfo},
+    ScoreCategory,
+};
 use sqlx::FromRow;
 
 pub type DatabasePool = sqlx::SqlitePool; // TODO: behind a trait?
@@ -29,6 +33,38 @@ pub struct ScoreRow {
     pub user_id: Id,
     pub score: Score,
     pub extra_info: Option<String>,
+    /// Whether the score has been verified by simulating a replay.
+    pub verified: bool,
+    pub nofail: bool,
+    pub sudden: bool,
+    pub hidden: bool,
+    pub health: String,
+}
+
+impl ScoreRow {
+    pub fn category(&self) -> ScoreCategory {
+        ScoreCategory {
+            nofail: self.nofail,
+            sudden: self.sudden,
+            hidden: self.hidden,
+            health: parse_health_preset(&self.health),
+        }
+    }
+}
+
+/// Name of the health preset as stored in the database.
+pub fn health_preset_name(preset: HealthPreset) -> &'static str {
+    match preset {
+        HealthPreset::Normal => "normal",
+        HealthPreset::Custom => "custom",
+    }
+}
+
+pub fn parse_health_preset(name: &str) -> HealthPreset {
+    match name {
+        "normal" => HealthPreset::Normal,
+        _ => HealthPreset::Custom,
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
@@ -58,6 +94,31 @@ pub struct MusicRow {
     pub original: bool,
     pub bpm: f32,
     pub public: bool,
+    pub format: String,
+    pub duration: f32,
+    pub beat_offset: f32,
+    /// [`TempoMap`] encoded as json.
+    pub tempo: String,
+}
+
+impl MusicRow {
+    pub fn tempo(&self) -> TempoMap {
+        serde_json::from_str(&self.tempo).unwrap_or_default()
+    }
+}
+
+/// Name of the music format as stored in the database.
+pub fn music_format_name(format: MusicFormat) -> &'static str {
+    format.extension()
+}
+
+pub fn parse_music_format(name: &str) -> MusicFormat {
+    match name {
+        "ogg" => MusicFormat::Ogg,
+        "wav" => MusicFormat::Wav,
+        "flac" => MusicFormat::Flac,
+        _ => MusicFormat::Mp3,
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
@@ -68,10 +129,21 @@ pub struct GroupRow {
     pub hash: String,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct GroupVersionRow {
+    pub version_id: Id,
+    pub group_id: Id,
+    pub hash: String,
+    /// Unix timestamp, `0` for versions uploaded before the history was kept.
+    pub uploaded_at: i64,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
 pub struct LevelRow {
     pub level_id: Id,
     pub hash: String,
     pub group_id: Id,
     pub name: String,
+    /// Levels uploaded before the rating was added have no difficulty.
+    pub difficulty: Option<f32>,
 }
@@ -1,3 +1,4 @@
+mod audio;
 mod database;
 mod prelude;
 mod server;
@@ -13,6 +14,7 @@ use serde::Deserialize;
 const DEFAULT_DATABASE: &str = "sqlite://database.db";
 const DEFAULT_GROUPS: &str = "groups";
 const DEFAULT_SECRETS: &str = "secrets/secrets.toml";
+const DEFAULT_LIMITS: &str = "limits.toml";
 
 #[derive(clap::Parser)]
 struct Opts {
@@ -21,6 +23,33 @@ struct Opts {
 
 struct AppConfig {
     groups_path: PathBuf,
+    limits: AppLimits,
+}
+
+/// Upload limits, the group quotas can be overridden for each user.
+#[derive(Deserialize)]
+#[serde(default)]
+struct AppLimits {
+    /// In bytes.
+    group_size: usize,
+    /// In bytes.
+    music_size: usize,
+    /// In seconds.
+    level_min_duration: f32,
+    groups_per_user: u32,
+    groups_per_user_per_song: u32,
+}
+
+impl Default for AppLimits {
+    fn default() -> Self {
+        Self {
+            group_size: 1024 * 1024,      // 1 MB
+            music_size: 10 * 1024 * 1024, // 10 MB
+            level_min_duration: 30.0,
+            groups_per_user: 5,
+            groups_per_user_per_song: 1,
+        }
+    }
 }
 
 #[derive(Deserialize)]
@@ -55,10 +84,24 @@ async fn main() -> Result<()> {
         dotenv::var("SECRETS_PATH").unwrap_or_else(|_| DEFAULT_SECRETS.to_owned());
     let secrets_path: PathBuf = PathBuf::from(secrets_path);
 
+    let limits_path: String =
+        dotenv::var("LIMITS_PATH").unwrap_or_else(|_| DEFAULT_LIMITS.to_owned());
+    let limits: AppLimits = match std::fs::read_to_string(&limits_path) {
+        Ok(limits) => toml::from_str(&limits)?,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => {
+            warn!("Limits config {} not found, using defaults", limits_path);
+            AppLimits::default()
+        }
+        Err(err) => return Err(err.into()),
+    };
+
     info!("Database: {}", database_url);
     info!("Groups: {:?}", groups_path);
 
-    let config = AppConfig { groups_path };
+    let config = AppConfig {
+        groups_path,
+        limits,
+    };
 
     let secrets: AppSecrets = toml::from_str(&std::fs::read_to_string(&secrets_path)?)?;
 
@@ -2,48 +2,201 @@ use super::*;
 
 use crate::database::types::LevelRow;
 
+use std::path::PathBuf;
+
 use axum::{body::Bytes, extract::DefaultBodyLimit};
-use ctl_core::types::{GroupsQuery, LevelFull, LevelSet};
+use ctl_core::{
+    difficulty::rate_difficulty,
+    lint::{lint_level, Severity},
+    migration::{self, GROUP_VERSION},
+    model::LevelConfig,
+    prelude::r32,
+    types::{GroupVersion, GroupsPage, GroupsQuery, GroupsSort, LevelFull, LevelSet},
+};
+use sqlx::QueryBuilder;
 
-const GROUP_SIZE_LIMIT: usize = 1024 * 1024; // 1 MB
-const GROUPS_PER_USER: usize = 5;
-const GROUPS_PER_USER_PER_SONG: usize = 1;
-/// seconds
-const LEVEL_MIN_DURATION: f32 = 30.0;
+const GROUPS_PAGE_DEFAULT: u32 = 20;
+pub(super) const GROUPS_PAGE_MAX: u32 = 50;
 
-pub fn route(router: Router) -> Router {
+pub fn route(router: Router, limits: &AppLimits) -> Router {
     router
         .route("/groups", get(group_list))
         .route("/group/:group_id", get(group_get))
         .route("/group/:group_id/download", get(download))
+        .route("/group/:group_id/versions", get(group_versions))
+        .route(
+            "/group/:group_id/versions/:version_id/download",
+            get(download_version),
+        )
+        .route(
+            "/group/:group_id/versions/:version_id/rollback",
+            post(rollback),
+        )
+        .route(
+            "/group/:group_id/authors",
+            post(add_group_author).delete(remove_group_author),
+        )
+        .route("/group/:group_id/owner", post(transfer_ownership))
         .route("/group/create", post(group_create))
-        .layer(DefaultBodyLimit::max(GROUP_SIZE_LIMIT))
+        .layer(DefaultBodyLimit::max(limits.group_size))
 }
 
-// TODO: filter, sort, limit, pages
-async fn group_list(
+pub(super) async fn group_list(
     State(app): State<Arc<App>>,
     Query(query): Query<GroupsQuery>,
-) -> Result<Json<Vec<GroupInfo>>> {
-    let music = super::music::music_list(State(app.clone())).await?.0;
+) -> Result<Json<GroupsPage>> {
+    let limit = query
+        .limit
+        .unwrap_or(GROUPS_PAGE_DEFAULT)
+        .clamp(1, GROUPS_PAGE_MAX) as usize;
 
     #[derive(sqlx::FromRow)]
-    struct LevelGroupRow {
+    struct GroupListRow {
         #[sqlx(flatten)]
         group: GroupRow,
-        #[sqlx(flatten)]
-        level: LevelRow,
+        music_name: String,
+        plays: i64,
+    }
+
+    let mut builder = QueryBuilder::new(
+        "
+SELECT * FROM (
+    SELECT groups.*, musics.name AS music_name, (
+        SELECT COUNT(*) FROM score_history
+        JOIN levels ON score_history.level_id = levels.level_id
+        WHERE levels.group_id = groups.group_id
+    ) AS plays
+    FROM groups
+    JOIN musics ON groups.music_id = musics.music_id
+    WHERE 1 = 1",
+    );
+
+    // Filter
+    if query.recommended {
+        builder.push(" AND groups.group_id IN (SELECT group_id FROM groups_recommended)");
+    }
+    if let Some(search) = query
+        .search
+        .as_deref()
+        .map(str::trim)
+        .filter(|search| !search.is_empty())
+    {
+        let pattern = format!("%{}%", escape_like(search));
+        builder
+            .push(" AND (musics.name LIKE ")
+            .push_bind(pattern.clone())
+            .push(" ESCAPE '\\' OR musics.romanized_name LIKE ")
+            .push_bind(pattern.clone())
+            .push(
+                " ESCAPE '\\' OR EXISTS (
+        SELECT 1 FROM levels
+        JOIN level_authors ON levels.level_id = level_authors.level_id
+        JOIN users ON level_authors.user_id = users.user_id
+        WHERE levels.group_id = groups.group_id AND users.username LIKE ",
+            )
+            .push_bind(pattern)
+            .push(" ESCAPE '\\'))");
     }
+    if let Some(artist) = query.artist {
+        builder
+            .push(
+                " AND EXISTS (
+        SELECT 1 FROM music_authors
+        WHERE music_authors.music_id = groups.music_id AND music_authors.artist_id = ",
+            )
+            .push_bind(artist)
+            .push(")");
+    }
+    if let Some(owner) = query.owner {
+        builder.push(" AND groups.owner_id = ").push_bind(owner);
+    }
+    if let Some(bpm) = query.bpm_min {
+        builder.push(" AND musics.bpm >= ").push_bind(bpm);
+    }
+    if let Some(bpm) = query.bpm_max {
+        builder.push(" AND musics.bpm <= ").push_bind(bpm);
+    }
+    builder.push("\n) AS groups_list WHERE 1 = 1");
+
+    // Continue from the cursor and sort
+    let cursor = query
+        .cursor
+        .as_deref()
+        .map(|cursor| GroupsCursor::parse(query.sort, cursor))
+        .transpose()?;
+    match cursor {
+        None => {}
+        Some(GroupsCursor::Newest { id }) => {
+            builder.push(" AND group_id < ").push_bind(id);
+        }
+        Some(GroupsCursor::MostPlayed { plays, id }) => {
+            builder
+                .push(" AND (plays < ")
+                .push_bind(plays)
+                .push(" OR plays = ")
+                .push_bind(plays)
+                .push(" AND group_id < ")
+                .push_bind(id)
+                .push(")");
+        }
+        Some(GroupsCursor::Name { id, name }) => {
+            builder
+                .push(" AND (music_name > ")
+                .push_bind(name.clone())
+                .push(" OR music_name = ")
+                .push_bind(name)
+                .push(" AND group_id > ")
+                .push_bind(id)
+                .push(")");
+        }
+    }
+    builder.push(match query.sort {
+        GroupsSort::Newest => " ORDER BY group_id DESC",
+        GroupsSort::MostPlayed => " ORDER BY plays DESC, group_id DESC",
+        GroupsSort::Name => " ORDER BY music_name ASC, group_id ASC",
+    });
+    // Fetch one more to check if there is a next page
+    builder.push(" LIMIT ").push_bind(limit as i64 + 1);
+
+    let mut rows: Vec<GroupListRow> = builder.build_query_as().fetch_all(&app.database).await?;
 
-    let query = if query.recommended {
-        "SELECT * FROM levels JOIN (
-            SELECT * FROM groups_recommended JOIN groups ON groups_recommended.group_id = groups.group_id
-        ) AS groups ON levels.group_id = groups.group_id"
+    let next = if rows.len() > limit {
+        rows.truncate(limit);
+        rows.last().map(|row| {
+            let cursor = match query.sort {
+                GroupsSort::Newest => GroupsCursor::Newest {
+                    id: row.group.group_id,
+                },
+                GroupsSort::MostPlayed => GroupsCursor::MostPlayed {
+                    plays: row.plays,
+                    id: row.group.group_id,
+                },
+                GroupsSort::Name => GroupsCursor::Name {
+                    id: row.group.group_id,
+                    name: row.music_name.clone(),
+                },
+            };
+            cursor.to_string()
+        })
     } else {
-        "SELECT * FROM levels JOIN groups ON levels.group_id = groups.group_id"
+        None
     };
 
-    let levels: Vec<LevelGroupRow> = sqlx::query_as(query).fetch_all(&app.database).await?;
+    if rows.is_empty() {
+        return Ok(Json(GroupsPage {
+            groups: Vec::new(),
+            next,
+        }));
+    }
+
+    // Fetch levels of the groups in the page
+    let mut builder = QueryBuilder::new("SELECT * FROM levels WHERE group_id IN (");
+    let mut ids = builder.separated(", ");
+    for row in &rows {
+        ids.push_bind(row.group.group_id);
+    }
+    ids.push_unseparated(") ORDER BY ord");
+    let levels: Vec<LevelRow> = builder.build_query_as().fetch_all(&app.database).await?;
 
     #[derive(sqlx::FromRow)]
     struct AuthorRow {
@@ -52,71 +205,166 @@ async fn group_list(
         user: UserRow,
     }
 
-    let authors: Vec<AuthorRow> = sqlx::query_as(
+    let mut builder = QueryBuilder::new(
         "
-    SELECT level_id, users.user_id, username
-    FROM level_authors
-    JOIN users ON level_authors.user_id = users.user_id
-            ",
-    )
-    .fetch_all(&app.database)
-    .await?;
+SELECT level_id, users.user_id, username
+FROM level_authors
+JOIN users ON level_authors.user_id = users.user_id
+WHERE level_id IN (",
+    );
+    let mut ids = builder.separated(", ");
+    for level in &levels {
+        ids.push_bind(level.level_id);
+    }
+    ids.push_unseparated(")");
+    let authors: Vec<AuthorRow> = builder.build_query_as().fetch_all(&app.database).await?;
 
-    let mut groups = Vec::<GroupInfo>::new();
-    for level_row in levels {
-        let authors: Vec<UserInfo> = authors
-            .iter()
-            .filter(|author| author.level_id == level_row.level.level_id)
-            .map(|author| author.user.clone().into())
-            .collect();
+    let mut builder = QueryBuilder::new("SELECT user_id, username FROM users WHERE user_id IN (");
+    let mut ids = builder.separated(", ");
+    for row in &rows {
+        ids.push_bind(row.group.owner_id);
+    }
+    ids.push_unseparated(")");
+    let owners: Vec<UserRow> = builder.build_query_as().fetch_all(&app.database).await?;
 
-        let owner = match authors
-            .iter()
-            .find(|user| user.id == level_row.group.owner_id)
-        {
-            Some(user) => user.clone(),
+    #[derive(sqlx::FromRow)]
+    struct GroupAuthorRow {
+        group_id: Id,
+        #[sqlx(flatten)]
+        user: UserRow,
+    }
+
+    let mut builder = QueryBuilder::new(
+        "
+SELECT group_id, users.user_id, username
+FROM group_authors
+JOIN users ON group_authors.user_id = users.user_id
+WHERE group_id IN (",
+    );
+    let mut ids = builder.separated(", ");
+    for row in &rows {
+        ids.push_bind(row.group.group_id);
+    }
+    ids.push_unseparated(")");
+    let group_authors: Vec<GroupAuthorRow> =
+        builder.build_query_as().fetch_all(&app.database).await?;
+
+    let mut musics = BTreeMap::<Id, MusicInfo>::new();
+    let mut groups = Vec::with_capacity(rows.len());
+    for row in rows {
+        let group = row.group;
+
+        let music = match musics.get(&group.music_id) {
+            Some(music) => music.clone(),
             None => {
-                sqlx::query("SELECT user_id, username FROM users WHERE user_id = ?")
-                    .bind(level_row.group.owner_id)
-                    .try_map(|row: DBRow| {
-                        Ok(UserInfo {
-                            id: row.try_get("user_id")?,
-                            name: row.try_get::<String, _>("username")?.into(),
-                        })
-                    })
-                    .fetch_one(&app.database)
+                let info = super::music::music_get(State(app.clone()), Path(group.music_id))
                     .await?
+                    .0;
+                musics.insert(group.music_id, info.clone());
+                info
             }
         };
 
-        let level_info = LevelInfo {
-            id: level_row.level.level_id,
-            name: level_row.level.name.into(),
-            hash: level_row.level.hash,
+        let owner = owners
+            .iter()
+            .find(|user| user.user_id == group.owner_id)
+            .cloned()
+            .map_or_else(
+                || UserInfo {
+                    id: group.owner_id,
+                    name: "<unknown>".into(),
+                },
+                Into::into,
+            );
+
+        let levels = levels
+            .iter()
+            .filter(|level| level.group_id == group.group_id)
+            .map(|level| LevelInfo {
+                id: level.level_id,
+                name: level.name.clone().into(),
+                hash: level.hash.clone(),
+                difficulty: level.difficulty.map(r32),
+                authors: authors
+                    .iter()
+                    .filter(|author| author.level_id == level.level_id)
+                    .map(|author| author.user.clone().into())
+                    .collect(),
+            })
+            .collect();
+
+        let authors = group_authors
+            .iter()
+            .filter(|author| author.group_id == group.group_id)
+            .map(|author| author.user.clone().into())
+            .collect();
+
+        groups.push(GroupInfo {
+            id: group.group_id,
+            music,
+            owner,
             authors,
+            levels,
+            hash: group.hash,
+        });
+    }
+
+    Ok(Json(GroupsPage { groups, next }))
+}
+
+/// Position in the group list after the last group of a page.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum GroupsCursor {
+    Newest {
+        id: Id,
+    },
+    MostPlayed {
+        plays: i64,
+        id: Id,
+    },
+    /// The name goes last, since it may contain the separator.
+    Name {
+        id: Id,
+        name: String,
+    },
+}
+
+impl GroupsCursor {
+    fn parse(sort: GroupsSort, cursor: &str) -> Result<Self> {
+        let parsed = match sort {
+            GroupsSort::Newest => cursor.parse().ok().map(|id| Self::Newest { id }),
+            GroupsSort::MostPlayed => cursor.split_once(':').and_then(|(plays, id)| {
+                Some(Self::MostPlayed {
+                    plays: plays.parse().ok()?,
+                    id: id.parse().ok()?,
+                })
+            }),
+            GroupsSort::Name => cursor.split_once(':').and_then(|(id, name)| {
+                Some(Self::Name {
+                    id: id.parse().ok()?,
+                    name: name.to_owned(),
+                })
+            }),
         };
+        parsed.ok_or(RequestError::InvalidCursor)
+    }
+}
 
-        let group_i = groups
-            .iter()
-            .position(|g| g.id == level_row.level.group_id)
-            .unwrap_or_else(|| {
-                groups.push(GroupInfo {
-                    id: level_row.level.group_id,
-                    music: music
-                        .iter()
-                        .find(|music| music.id == level_row.group.music_id)
-                        .cloned()
-                        .unwrap_or_default(), // Default should never be reached TODO: warning or smth
-                    owner,
-                    levels: Vec::new(),
-                    hash: String::new(), // TODO
-                });
-                groups.len() - 1
-            });
-        groups[group_i].levels.push(level_info);
+impl std::fmt::Display for GroupsCursor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Newest { id } => write!(f, "{}", id),
+            Self::MostPlayed { plays, id } => write!(f, "{}:{}", plays, id),
+            Self::Name { id, name } => write!(f, "{}:{}", id, name),
+        }
     }
+}
 
-    Ok(Json(groups))
+/// Escape the wildcard characters for a `LIKE ... ESCAPE '\'` pattern.
+fn escape_like(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_")
 }
 
 async fn group_get(
@@ -191,13 +439,17 @@ JOIN users ON level_authors.user_id = users.user_id
             name: level.name.into(),
             hash: level.hash,
             authors,
+            difficulty: level.difficulty.map(r32),
         });
     }
 
+    let group_authors = group_authors(&app.database, group_id).await?;
+
     Ok(Json(GroupInfo {
         id: group_id,
         music,
         owner,
+        authors: group_authors,
         levels,
         hash: group_row.hash,
     }))
@@ -213,10 +465,23 @@ async fn group_create(
     // NOTE: Not parsing into Rc, because we cant hold it across an await point
     // also we want to mutate it
     let parsed_group: LevelSet<LevelFull> =
-        bincode::deserialize(&data).map_err(|_| RequestError::InvalidLevel)?;
-    validate_group(&parsed_group)?;
-
-    music::music_exists(&app, parsed_group.music).await?;
+        LevelSet::decode(&data).map_err(|_| RequestError::InvalidLevel)?;
+    let Json(music) = music::music_get(State(app.clone()), Path(parsed_group.music)).await?;
+    // Linting and rating simulate the whole level, keep them off the async runtime
+    let parsed_group = {
+        let app = app.clone();
+        tokio::task::spawn_blocking(move || {
+            let mut parsed_group = parsed_group;
+            validate_group(&parsed_group, &music, &app.config.limits)?;
+            // Rate the levels ourselves instead of trusting the client
+            for level in &mut parsed_group.levels {
+                level.meta.difficulty = Some(rate_difficulty(&level.data, &music));
+            }
+            Ok::<_, RequestError>(parsed_group)
+        })
+        .await
+        .map_err(|_| RequestError::Internal)??
+    };
 
     let group_id = if parsed_group.id != 0 {
         let id = parsed_group.id;
@@ -231,73 +496,173 @@ async fn group_create(
 
 async fn update_group(app: &App, user: &User, mut parsed_group: LevelSet<LevelFull>) -> Result<()> {
     let group_id = parsed_group.id;
+    // Nothing is saved unless the whole update succeeds
+    let mut transaction = app.database.begin().await?;
+
     let group: Option<GroupRow> = sqlx::query_as("SELECT * FROM groups WHERE group_id = ?")
         .bind(group_id)
-        .fetch_optional(&app.database)
+        .fetch_optional(&mut *transaction)
         .await?;
     let group = group.ok_or(RequestError::NoSuchGroup(group_id))?;
 
+    // The owner does not change with uploads
+    let owner: UserRow = sqlx::query_as("SELECT user_id, username FROM users WHERE user_id = ?")
+        .bind(group.owner_id)
+        .fetch_one(&mut *transaction)
+        .await?;
+    parsed_group.owner = owner.into();
+
+    let old_levels: Vec<LevelRow> =
+        sqlx::query_as("SELECT * FROM levels WHERE group_id = ? ORDER BY ord")
+            .bind(group_id)
+            .fetch_all(&mut *transaction)
+            .await?;
+
+    // The version being replaced
+    let old_version = latest_version(&mut *transaction, group_id).await?;
+
+    let path = group_path(app, group_id)?;
+    let mut files = Vec::new();
+    let old_version_path = version_path(app, old_version.version_id)?;
+    if !old_version_path.exists() && path.exists() {
+        // Versions from before the history was kept only exist as the group file
+        let data = std::fs::read(&path)?;
+        files.push(PendingFile::write(old_version_path, &data)?);
+    }
+
+    // Only levels of this group can be updated
+    for level in &parsed_group.levels {
+        if level.meta.id != 0 && !old_levels.iter().any(|old| old.level_id == level.meta.id) {
+            return Err(RequestError::LevelNotInGroup(level.meta.id));
+        }
+    }
+
     // Check if the player has rights to change the group
-    if user.user_id != group.owner_id {
+    let is_owner = user.user_id == group.owner_id;
+    let co_authors = group_authors(&mut *transaction, group_id).await?;
+    let is_co_author = co_authors.iter().any(|author| author.id == user.user_id);
+    let removes_levels = old_levels.iter().any(|old| {
+        !parsed_group
+            .levels
+            .iter()
+            .any(|level| level.meta.id == old.level_id)
+    });
+    if removes_levels && !is_owner {
         return Err(RequestError::Forbidden);
     }
+    if !is_owner && !is_co_author {
+        // Authors of single difficulties can only change those, and not the order of the levels
+        let same_order = parsed_group
+            .levels
+            .iter()
+            .map(|level| level.meta.id)
+            .eq(old_levels.iter().map(|old| old.level_id));
+        if !same_order {
+            return Err(RequestError::Forbidden);
+        }
+        let authored: Vec<Id> = sqlx::query("SELECT level_id FROM level_authors WHERE user_id = ?")
+            .bind(user.user_id)
+            .try_map(|row: DBRow| row.try_get("level_id"))
+            .fetch_all(&mut *transaction)
+            .await?;
+        for level in &parsed_group.levels {
+            if authored.contains(&level.meta.id) {
+                continue;
+            }
+            let unchanged = old_levels.iter().any(|old| {
+                old.level_id == level.meta.id
+                    && old.hash == level.data.calculate_hash()
+                    && old.name.as_str() == level.meta.name.as_ref()
+            });
+            if !unchanged {
+                return Err(RequestError::Forbidden);
+            }
+        }
+    }
 
-    // Verify owner
-    parsed_group.owner = UserInfo {
-        id: user.user_id,
-        name: user.username.clone().into(),
-    };
+    // Remove levels missing from the update
+    for old_level in &old_levels {
+        if !parsed_group
+            .levels
+            .iter()
+            .any(|level| level.meta.id == old_level.level_id)
+        {
+            debug!(
+                "Removing level {} from group {}",
+                old_level.level_id, group_id
+            );
+            delete_level(&mut transaction, old_level.level_id).await?;
+        }
+    }
 
     // Update levels
-    // TODO: remove removed levels
     for (order, level) in parsed_group.levels.iter_mut().enumerate() {
         let order = order as i64;
         level.meta.hash = level.data.calculate_hash(); // Make sure the hash is valid
         if level.meta.id == 0 {
             // Create
             level.meta.id = sqlx::query(
-                "INSERT INTO levels (hash, group_id, name, ord) VALUES (?, ?, ?, ?) RETURNING level_id",
+                "INSERT INTO levels (hash, group_id, name, ord, difficulty) VALUES (?, ?, ?, ?, ?) RETURNING level_id",
             )
             .bind(&level.meta.hash)
             .bind(group_id)
             .bind(level.meta.name.as_ref())
             .bind(order)
+            .bind(level.meta.difficulty.map(|value| value.as_f32()))
             .try_map(|row: DBRow| row.try_get("level_id"))
-            .fetch_one(&app.database)
+            .fetch_one(&mut *transaction)
             .await?;
+
+            sqlx::query("INSERT INTO level_authors (level_id, user_id) VALUES (?, ?)")
+                .bind(level.meta.id)
+                .bind(user.user_id)
+                .execute(&mut *transaction)
+                .await?;
         } else {
-            let old_level: Option<LevelRow> =
-                sqlx::query_as("SELECT * FROM levels WHERE level_id = ? AND group_id = ?")
-                    .bind(level.meta.id)
-                    .bind(group_id)
-                    .fetch_optional(&app.database)
-                    .await?;
-            if let Some(old_level) = old_level {
-                if old_level.hash != level.meta.hash {
-                    // Reset the leaderboard
-                    sqlx::query("DELETE FROM scores WHERE level_id = ?")
-                        .bind(old_level.level_id)
-                        .execute(&app.database)
-                        .await?;
-                }
-
-                // Update
+            let old_level = old_levels
+                .iter()
+                .find(|old| old.level_id == level.meta.id)
+                .ok_or(RequestError::LevelNotInGroup(level.meta.id))?;
+            if old_level.hash != level.meta.hash {
+                // Reset the leaderboard, keeping the old scores with the old version
                 sqlx::query(
-                "UPDATE levels SET hash = ?, name = ?, ord = ? WHERE level_id = ? AND group_id = ?",
+                    "
+INSERT INTO archived_scores
+(version_id, level_id, user_id, score, extra_info, verified, nofail, sudden, hidden, health)
+SELECT ?, level_id, user_id, score, extra_info, verified, nofail, sudden, hidden, health
+FROM scores WHERE level_id = ?
+                    ",
                 )
-                .bind(&level.meta.hash)
-                .bind(level.meta.name.as_ref())
-                .bind(order)
-                .bind(level.meta.id)
-                .bind(group_id)
-                .execute(&app.database)
+                .bind(old_version.version_id)
+                .bind(old_level.level_id)
+                .execute(&mut *transaction)
                 .await?;
-            } else {
-                // TODO: maybe invalidate request
+                sqlx::query("DELETE FROM scores WHERE level_id = ?")
+                    .bind(old_level.level_id)
+                    .execute(&mut *transaction)
+                    .await?;
             }
+
+            // Update
+            sqlx::query(
+                "UPDATE levels SET hash = ?, name = ?, ord = ?, difficulty = ? WHERE level_id = ? AND group_id = ?",
+            )
+            .bind(&level.meta.hash)
+            .bind(level.meta.name.as_ref())
+            .bind(order)
+            .bind(level.meta.difficulty.map(|value| value.as_f32()))
+            .bind(level.meta.id)
+            .bind(group_id)
+            .execute(&mut *transaction)
+            .await?;
         }
     }
 
+    // Authors are managed by the server
+    for level in &mut parsed_group.levels {
+        level.meta.authors = level_authors(&mut *transaction, level.meta.id).await?;
+    }
+
     // Disallow further mutation to make sure the hash is valid
     let parsed_group = parsed_group;
     let hash = parsed_group.calculate_hash();
@@ -306,13 +671,11 @@ async fn update_group(app: &App, user: &User, mut parsed_group: LevelSet<LevelFu
     sqlx::query("UPDATE groups SET hash = ? WHERE group_id = ?")
         .bind(&hash)
         .bind(group_id)
-        .execute(&app.database)
+        .execute(&mut *transaction)
         .await?;
+    let version_id = insert_version(&mut transaction, group_id, &hash).await?;
 
     // Check path
-    let dir_path = app.config.groups_path.join("levels");
-    std::fs::create_dir_all(&dir_path)?;
-    let path = dir_path.join(group_id.to_string());
     debug!("Saving group file at {:?}", path);
 
     if !path.exists() {
@@ -323,37 +686,279 @@ async fn update_group(app: &App, user: &User, mut parsed_group: LevelSet<LevelFu
     }
 
     // Write to file
-    let data = bincode::serialize(&parsed_group).map_err(|_| RequestError::Internal)?;
-    std::fs::write(path, data)?;
+    let data = parsed_group.encode().map_err(|_| RequestError::Internal)?;
+    files.push(PendingFile::write(version_path(app, version_id)?, &data)?);
+    files.push(PendingFile::write(path, &data)?);
+
+    transaction.commit().await?;
+    for file in files {
+        file.persist()?;
+    }
     debug!("Saved group file successfully");
 
     Ok(())
 }
 
-async fn new_group(app: &App, user: &User, mut parsed_group: LevelSet<LevelFull>) -> Result<Id> {
-    // Check if the user already has groups
-    let user_groups: Vec<GroupRow> = sqlx::query_as("SELECT * FROM groups WHERE owner_id = ?")
-        .bind(user.user_id)
-        .fetch_all(&app.database)
+/// Co-authors of the whole group.
+async fn group_authors(
+    executor: impl sqlx::SqliteExecutor<'_>,
+    group_id: Id,
+) -> Result<Vec<UserInfo>> {
+    let authors: Vec<UserRow> = sqlx::query_as(
+        "
+SELECT users.user_id, username
+FROM group_authors
+JOIN users ON group_authors.user_id = users.user_id
+WHERE group_id = ?
+        ",
+    )
+    .bind(group_id)
+    .fetch_all(executor)
+    .await?;
+    Ok(authors.into_iter().map(Into::into).collect())
+}
+
+async fn level_authors(
+    executor: impl sqlx::SqliteExecutor<'_>,
+    level_id: Id,
+) -> Result<Vec<UserInfo>> {
+    let authors: Vec<UserRow> = sqlx::query_as(
+        "
+SELECT users.user_id, username
+FROM level_authors
+JOIN users ON level_authors.user_id = users.user_id
+WHERE level_id = ?
+        ",
+    )
+    .bind(level_id)
+    .fetch_all(executor)
+    .await?;
+    Ok(authors.into_iter().map(Into::into).collect())
+}
+
+/// Rewrite the group file with the owner and the authors from the database,
+/// and commit the transaction.
+/// The levels stay the same, so the latest version is updated in place instead of adding one.
+pub(super) async fn save_group_authors(
+    app: &App,
+    mut transaction: sqlx::Transaction<'_, sqlx::Sqlite>,
+    group_id: Id,
+) -> Result<()> {
+    let owner: UserRow = sqlx::query_as(
+        "
+SELECT users.user_id, username
+FROM groups
+JOIN users ON groups.owner_id = users.user_id
+WHERE group_id = ?
+        ",
+    )
+    .bind(group_id)
+    .fetch_optional(&mut *transaction)
+    .await?
+    .ok_or(RequestError::NoSuchGroup(group_id))?;
+
+    let path = group_path(app, group_id)?;
+    let data = std::fs::read(&path)?;
+    let mut group: LevelSet<LevelFull> =
+        LevelSet::decode(&data).map_err(|_| RequestError::Internal)?;
+    group.owner = owner.into();
+    for level in &mut group.levels {
+        level.meta.authors = level_authors(&mut *transaction, level.meta.id).await?;
+    }
+
+    let version = latest_version(&mut *transaction, group_id).await?;
+
+    let hash = group.calculate_hash();
+    sqlx::query("UPDATE groups SET hash = ? WHERE group_id = ?")
+        .bind(&hash)
+        .bind(group_id)
+        .execute(&mut *transaction)
+        .await?;
+    sqlx::query("UPDATE group_versions SET hash = ? WHERE version_id = ?")
+        .bind(&hash)
+        .bind(version.version_id)
+        .execute(&mut *transaction)
+        .await?;
+
+    let data = group.encode().map_err(|_| RequestError::Internal)?;
+    let version_file = PendingFile::write(version_path(app, version.version_id)?, &data)?;
+    let file = PendingFile::write(path, &data)?;
+
+    transaction.commit().await?;
+    version_file.persist()?;
+    file.persist()?;
+
+    Ok(())
+}
+
+/// Check that the user is the owner of the group.
+pub(super) async fn check_group_owner(
+    executor: impl sqlx::SqliteExecutor<'_>,
+    user: &User,
+    group_id: Id,
+) -> Result<GroupRow> {
+    let group: Option<GroupRow> = sqlx::query_as("SELECT * FROM groups WHERE group_id = ?")
+        .bind(group_id)
+        .fetch_optional(executor)
+        .await?;
+    let group = group.ok_or(RequestError::NoSuchGroup(group_id))?;
+    if group.owner_id != user.user_id {
+        return Err(RequestError::Forbidden);
+    }
+    Ok(group)
+}
+
+/// Check that the user exists.
+pub(super) async fn user_exists(
+    executor: impl sqlx::SqliteExecutor<'_>,
+    user_id: Id,
+) -> Result<()> {
+    let check = sqlx::query("SELECT null FROM users WHERE user_id = ?")
+        .bind(user_id)
+        .fetch_optional(executor)
+        .await?;
+    if check.is_none() {
+        return Err(RequestError::NoSuchUser(user_id));
+    }
+    Ok(())
+}
+
+async fn add_group_author(
+    session: AuthSession,
+    State(app): State<Arc<App>>,
+    Path(group_id): Path<Id>,
+    Query(author): Query<IdQuery>,
+) -> Result<()> {
+    let user = check_user(&session).await?;
+    let group = check_group_owner(&app.database, user, group_id).await?;
+    user_exists(&app.database, author.id).await?;
+
+    if author.id == group.owner_id {
+        // The owner can already change everything
+        return Ok(());
+    }
+
+    sqlx::query(
+        "
+INSERT INTO group_authors (group_id, user_id)
+SELECT ?1, ?2 WHERE NOT EXISTS (SELECT null FROM group_authors WHERE group_id = ?1 AND user_id = ?2)
+        ",
+    )
+    .bind(group_id)
+    .bind(author.id)
+    .execute(&app.database)
+    .await?;
+
+    Ok(())
+}
+
+async fn remove_group_author(
+    session: AuthSession,
+    State(app): State<Arc<App>>,
+    Path(group_id): Path<Id>,
+    Query(author): Query<IdQuery>,
+) -> Result<()> {
+    let user = check_user(&session).await?;
+    if author.id != user.user_id {
+        // Co-authors can leave on their own
+        check_group_owner(&app.database, user, group_id).await?;
+    }
+
+    sqlx::query("DELETE FROM group_authors WHERE group_id = ? AND user_id = ?")
+        .bind(group_id)
+        .bind(author.id)
+        .execute(&app.database)
+        .await?;
+
+    Ok(())
+}
+
+async fn transfer_ownership(
+    session: AuthSession,
+    State(app): State<Arc<App>>,
+    Path(group_id): Path<Id>,
+    Query(new_owner): Query<IdQuery>,
+) -> Result<()> {
+    let user = check_user(&session).await?;
+    let mut transaction = app.database.begin().await?;
+    let group = check_group_owner(&mut *transaction, user, group_id).await?;
+    user_exists(&mut *transaction, new_owner.id).await?;
+
+    if new_owner.id == group.owner_id {
+        return Ok(());
+    }
+
+    // The new owner has to be able to own another group
+    check_quota(&app, &mut transaction, new_owner.id, group.music_id).await?;
+
+    sqlx::query("UPDATE groups SET owner_id = ? WHERE group_id = ?")
+        .bind(new_owner.id)
+        .bind(group_id)
+        .execute(&mut *transaction)
+        .await?;
+
+    // The previous owner stays as a co-author
+    sqlx::query("DELETE FROM group_authors WHERE group_id = ? AND user_id = ?")
+        .bind(group_id)
+        .bind(new_owner.id)
+        .execute(&mut *transaction)
+        .await?;
+    sqlx::query("INSERT INTO group_authors (group_id, user_id) VALUES (?, ?)")
+        .bind(group_id)
+        .bind(group.owner_id)
+        .execute(&mut *transaction)
         .await?;
-    if user_groups.len() >= GROUPS_PER_USER {
+
+    save_group_authors(&app, transaction, group_id).await
+}
+
+/// Delete the level together with its authors and scores.
+async fn delete_level(connection: &mut sqlx::SqliteConnection, level_id: Id) -> Result<()> {
+    for query in [
+        "DELETE FROM scores WHERE level_id = ?",
+        "DELETE FROM score_history WHERE level_id = ?",
+        "DELETE FROM archived_scores WHERE level_id = ?",
+        "DELETE FROM level_authors WHERE level_id = ?",
+        "DELETE FROM levels WHERE level_id = ?",
+    ] {
+        sqlx::query(query)
+            .bind(level_id)
+            .execute(&mut *connection)
+            .await?;
+    }
+    Ok(())
+}
+
+/// Check that the user can own one more group for the music.
+async fn check_quota(
+    app: &App,
+    connection: &mut sqlx::SqliteConnection,
+    user_id: Id,
+    music_id: Id,
+) -> Result<()> {
+    let quota = super::users::user_quota(app, connection, user_id).await?;
+    if quota.remaining() == 0 {
         return Err(RequestError::TooManyGroups);
     }
-    if user_groups
-        .iter()
-        .filter(|group| group.music_id == parsed_group.music)
-        .count()
-        >= GROUPS_PER_USER_PER_SONG
-    {
+    if quota.remaining_for(music_id) == 0 {
         return Err(RequestError::TooManyGroupsForSong);
     }
+    Ok(())
+}
+
+async fn new_group(app: &App, user: &User, mut parsed_group: LevelSet<LevelFull>) -> Result<Id> {
+    // Nothing is saved unless the whole upload succeeds
+    let mut transaction = app.database.begin().await?;
+
+    // Check if the user already has groups
+    check_quota(app, &mut transaction, user.user_id, parsed_group.music).await?;
 
     // Check if such a level already exists
     for level in &mut parsed_group.levels {
         level.meta.hash = level.data.calculate_hash();
         let conflict = sqlx::query("SELECT null FROM levels WHERE hash = ?")
             .bind(&level.meta.hash)
-            .fetch_optional(&app.database)
+            .fetch_optional(&mut *transaction)
             .await?;
         if conflict.is_some() {
             return Err(RequestError::LevelAlreadyExists);
@@ -374,7 +979,7 @@ async fn new_group(app: &App, user: &User, mut parsed_group: LevelSet<LevelFull>
     .bind(user.user_id)
     .bind("")
     .try_map(|row: DBRow| row.try_get("group_id"))
-    .fetch_one(&app.database)
+    .fetch_one(&mut *transaction)
     .await?;
     parsed_group.id = group_id;
 
@@ -385,21 +990,22 @@ async fn new_group(app: &App, user: &User, mut parsed_group: LevelSet<LevelFull>
         // Check if such a level already exists
         let conflict = sqlx::query("SELECT null FROM levels WHERE hash = ?")
             .bind(&level.meta.hash)
-            .fetch_optional(&app.database)
+            .fetch_optional(&mut *transaction)
             .await?;
         if conflict.is_some() {
             return Err(RequestError::LevelAlreadyExists);
         }
 
         level.meta.id = sqlx::query(
-            "INSERT INTO levels (hash, group_id, name, ord) VALUES (?, ?, ?, ?) RETURNING level_id",
+            "INSERT INTO levels (hash, group_id, name, ord, difficulty) VALUES (?, ?, ?, ?, ?) RETURNING level_id",
         )
         .bind(&level.meta.hash)
         .bind(group_id)
         .bind(level.meta.name.as_ref())
         .bind(order)
+        .bind(level.meta.difficulty.map(|value| value.as_f32()))
         .try_map(|row: DBRow| row.try_get("level_id"))
-        .fetch_one(&app.database)
+        .fetch_one(&mut *transaction)
         .await?;
 
         level.meta.authors = vec![UserInfo {
@@ -409,7 +1015,7 @@ async fn new_group(app: &App, user: &User, mut parsed_group: LevelSet<LevelFull>
         sqlx::query("INSERT INTO level_authors (level_id, user_id) VALUES (?, ?)")
             .bind(level.meta.id)
             .bind(user.user_id)
-            .execute(&app.database)
+            .execute(&mut *transaction)
             .await?;
     }
 
@@ -421,13 +1027,12 @@ async fn new_group(app: &App, user: &User, mut parsed_group: LevelSet<LevelFull>
     sqlx::query("UPDATE groups SET hash = ? WHERE group_id = ?")
         .bind(&hash)
         .bind(group_id)
-        .execute(&app.database)
+        .execute(&mut *transaction)
         .await?;
+    let version_id = insert_version(&mut transaction, group_id, &hash).await?;
 
     // Check path
-    let dir_path = app.config.groups_path.join("levels");
-    std::fs::create_dir_all(&dir_path)?;
-    let path = dir_path.join(group_id.to_string());
+    let path = group_path(app, group_id)?;
     debug!("Saving group file at {:?}", path);
 
     if path.exists() {
@@ -436,13 +1041,269 @@ async fn new_group(app: &App, user: &User, mut parsed_group: LevelSet<LevelFull>
     }
 
     // Write to file
-    let data = bincode::serialize(&parsed_group).map_err(|_| RequestError::Internal)?;
-    std::fs::write(path, data)?;
+    let data = parsed_group.encode().map_err(|_| RequestError::Internal)?;
+    let version_file = PendingFile::write(version_path(app, version_id)?, &data)?;
+    let file = PendingFile::write(path, &data)?;
+
+    transaction.commit().await?;
+    version_file.persist()?;
+    file.persist()?;
     debug!("Saved group file successfully");
 
     Ok(group_id)
 }
 
+/// Record a new version of the group.
+async fn insert_version(
+    connection: &mut sqlx::SqliteConnection,
+    group_id: Id,
+    hash: &str,
+) -> Result<Id> {
+    let version_id = sqlx::query(
+        "INSERT INTO group_versions (group_id, hash, uploaded_at) VALUES (?, ?, ?) RETURNING version_id",
+    )
+    .bind(group_id)
+    .bind(hash)
+    .bind(time::OffsetDateTime::now_utc().unix_timestamp())
+    .try_map(|row: DBRow| row.try_get("version_id"))
+    .fetch_one(&mut *connection)
+    .await?;
+    Ok(version_id)
+}
+
+/// The current version of the group.
+async fn latest_version(
+    executor: impl sqlx::SqliteExecutor<'_>,
+    group_id: Id,
+) -> Result<GroupVersionRow> {
+    let version: Option<GroupVersionRow> = sqlx::query_as(
+        "SELECT * FROM group_versions WHERE group_id = ? ORDER BY version_id DESC LIMIT 1",
+    )
+    .bind(group_id)
+    .fetch_optional(executor)
+    .await?;
+    version.ok_or(RequestError::Internal)
+}
+
+async fn group_versions(
+    State(app): State<Arc<App>>,
+    Path(group_id): Path<Id>,
+) -> Result<Json<Vec<GroupVersion>>> {
+    let group_row = sqlx::query("SELECT null FROM groups WHERE group_id = ?")
+        .bind(group_id)
+        .fetch_optional(&app.database)
+        .await?;
+    if group_row.is_none() {
+        return Err(RequestError::NoSuchGroup(group_id));
+    }
+
+    let versions: Vec<GroupVersionRow> =
+        sqlx::query_as("SELECT * FROM group_versions WHERE group_id = ? ORDER BY version_id")
+            .bind(group_id)
+            .fetch_all(&app.database)
+            .await?;
+    let versions = versions
+        .into_iter()
+        .map(|version| GroupVersion {
+            id: version.version_id,
+            hash: version.hash,
+            uploaded_at: version.uploaded_at,
+        })
+        .collect();
+
+    Ok(Json(versions))
+}
+
+async fn get_version(app: &App, group_id: Id, version_id: Id) -> Result<GroupVersionRow> {
+    let version: Option<GroupVersionRow> =
+        sqlx::query_as("SELECT * FROM group_versions WHERE version_id = ? AND group_id = ?")
+            .bind(version_id)
+            .bind(group_id)
+            .fetch_optional(&app.database)
+            .await?;
+    version.ok_or(RequestError::NoSuchGroupVersion(version_id))
+}
+
+async fn download_version(
+    State(app): State<Arc<App>>,
+    Path((group_id, version_id)): Path<(Id, Id)>,
+) -> Result<impl IntoResponse> {
+    let version = get_version(&app, group_id, version_id).await?;
+
+    let mut file_path = version_path(&app, version.version_id)?;
+    if !file_path.exists() {
+        // Versions from before the history was kept only exist as the group file
+        file_path = group_path(&app, group_id)?;
+    }
+    send_file(file_path, content_level()).await
+}
+
+async fn rollback(
+    session: AuthSession,
+    State(app): State<Arc<App>>,
+    Path((group_id, version_id)): Path<(Id, Id)>,
+) -> Result<()> {
+    let user = check_user(&session).await?;
+    // Co-authors could otherwise bring back levels that the owner has removed
+    let group = check_group_owner(&app.database, user, group_id).await?;
+    let version = get_version(&app, group_id, version_id).await?;
+
+    let mut file_path = version_path(&app, version.version_id)?;
+    if !file_path.exists() {
+        // Versions from before the history was kept only exist as the group file
+        file_path = group_path(&app, group_id)?;
+    }
+    let data = tokio::fs::read(file_path).await?;
+    let mut parsed_group: LevelSet<LevelFull> =
+        LevelSet::decode(&data).map_err(|_| RequestError::InvalidLevel)?;
+    parsed_group.id = group_id;
+    parsed_group.music = group.music_id;
+
+    // The limits and the lints might have changed since the version was uploaded
+    let Json(music) = music::music_get(State(app.clone()), Path(group.music_id)).await?;
+    let parsed_group = {
+        let app = app.clone();
+        tokio::task::spawn_blocking(move || {
+            validate_group(&parsed_group, &music, &app.config.limits)?;
+            Ok::<_, RequestError>(parsed_group)
+        })
+        .await
+        .map_err(|_| RequestError::Internal)??
+    };
+
+    // Levels removed since then are uploaded anew
+    let levels: Vec<Id> = sqlx::query("SELECT level_id FROM levels WHERE group_id = ?")
+        .bind(group_id)
+        .try_map(|row: DBRow| row.try_get("level_id"))
+        .fetch_all(&app.database)
+        .await?;
+    for level in &mut parsed_group.levels {
+        if !levels.contains(&level.meta.id) {
+            level.meta.id = 0;
+        }
+    }
+
+    update_group(&app, user, parsed_group).await
+}
+
+/// Re-encode the group files saved in an older format.
+/// The stored hashes are calculated from the encoded levels, so they are recalculated as well.
+pub(super) async fn upgrade_group_files(app: &App) -> Result<()> {
+    let groups: Vec<GroupRow> = sqlx::query_as("SELECT * FROM groups")
+        .fetch_all(&app.database)
+        .await?;
+
+    for group in groups {
+        let group_id = group.group_id;
+        let path = group_path(app, group_id)?;
+        let data = match std::fs::read(&path) {
+            Ok(data) => data,
+            Err(err) => {
+                warn!("Group {} file could not be read: {}", group_id, err);
+                continue;
+            }
+        };
+        match migration::group_version(&data) {
+            Ok(GROUP_VERSION) => continue,
+            Ok(version) => info!(
+                "Upgrading group {} from version {} to {}",
+                group_id, version, GROUP_VERSION
+            ),
+            Err(err) => {
+                warn!("Group {} file is invalid: {}", group_id, err);
+                continue;
+            }
+        }
+        let mut parsed_group: LevelSet<LevelFull> = match LevelSet::decode(&data) {
+            Ok(group) => group,
+            Err(err) => {
+                warn!("Group {} file is invalid: {}", group_id, err);
+                continue;
+            }
+        };
+
+        let mut transaction = app.database.begin().await?;
+        for level in &mut parsed_group.levels {
+            level.meta.hash = level.data.calculate_hash();
+            sqlx::query("UPDATE levels SET hash = ? WHERE level_id = ? AND group_id = ?")
+                .bind(&level.meta.hash)
+                .bind(level.meta.id)
+                .bind(group_id)
+                .execute(&mut *transaction)
+                .await?;
+        }
+
+        let parsed_group = parsed_group;
+        let hash = parsed_group.calculate_hash();
+        sqlx::query("UPDATE groups SET hash = ? WHERE group_id = ?")
+            .bind(&hash)
+            .bind(group_id)
+            .execute(&mut *transaction)
+            .await?;
+        // The current version refers to the same file
+        sqlx::query("UPDATE group_versions SET hash = ? WHERE group_id = ? AND hash = ?")
+            .bind(&hash)
+            .bind(group_id)
+            .bind(&group.hash)
+            .execute(&mut *transaction)
+            .await?;
+
+        let data = parsed_group.encode().map_err(|_| RequestError::Internal)?;
+        let file = PendingFile::write(path, &data)?;
+        transaction.commit().await?;
+        file.persist()?;
+    }
+
+    Ok(())
+}
+
+/// Path to the group file, making sure its directory exists.
+fn group_path(app: &App, group_id: Id) -> Result<PathBuf> {
+    let dir_path = app.config.groups_path.join("levels");
+    std::fs::create_dir_all(&dir_path)?;
+    Ok(dir_path.join(group_id.to_string()))
+}
+
+/// Path to the file of a group version, making sure its directory exists.
+fn version_path(app: &App, version_id: Id) -> Result<PathBuf> {
+    let dir_path = app.config.groups_path.join("versions");
+    std::fs::create_dir_all(&dir_path)?;
+    Ok(dir_path.join(version_id.to_string()))
+}
+
+/// A file written to a temporary location next to its destination.
+/// It is moved in place by [`PendingFile::persist`], or removed if dropped before that.
+pub(super) struct PendingFile {
+    temp_path: PathBuf,
+    path: PathBuf,
+}
+
+impl PendingFile {
+    pub(super) fn write(path: PathBuf, data: &[u8]) -> Result<Self> {
+        // Unique, so that concurrent writes to the same destination do not mix
+        let mut temp_path = path.clone().into_os_string();
+        temp_path.push(format!(".{}.tmp", uuid::Uuid::new_v4()));
+        let file = Self {
+            temp_path: temp_path.into(),
+            path,
+        };
+        std::fs::write(&file.temp_path, data)?;
+        Ok(file)
+    }
+
+    pub(super) fn persist(self) -> Result<()> {
+        std::fs::rename(&self.temp_path, &self.path)?;
+        Ok(())
+    }
+}
+
+impl Drop for PendingFile {
+    fn drop(&mut self) {
+        // Does nothing if the file has been persisted
+        let _ = std::fs::remove_file(&self.temp_path);
+    }
+}
+
 async fn download(
     State(app): State<Arc<App>>,
     Path(group_id): Path<Id>,
@@ -464,21 +1325,156 @@ async fn download(
     send_file(file_path, content_level()).await
 }
 
-// TODO: move to core, so the client can reuse it
-fn validate_group(group: &LevelSet<LevelFull>) -> Result<()> {
+fn validate_group(
+    group: &LevelSet<LevelFull>,
+    music: &MusicInfo,
+    limits: &AppLimits,
+) -> Result<()> {
     if group.levels.is_empty() {
         return Err(RequestError::NoLevels);
     }
 
-    // TODO: check empty space
-
     for level in &group.levels {
-        // TODO: check realtime
-        // but i think the format will change to use realtime inside the level
-        let duration = level.data.last_beat().as_f32();
-        if dbg!(duration) < LEVEL_MIN_DURATION {
+        let duration = music.beat_to_time(level.data.last_beat()).as_f32();
+        if duration < limits.level_min_duration {
             return Err(RequestError::LevelTooSmall);
         }
+
+        let diagnostics = lint_level(&level.data, &LevelConfig::default(), music);
+        if let Some(error) = diagnostics
+            .into_iter()
+            .find(|diagnostic| diagnostic.severity == Severity::Error)
+        {
+            return Err(RequestError::LevelLint {
+                level: level.meta.name.to_string(),
+                beat: error.beat.as_f32(),
+                message: error.kind.to_string(),
+            });
+        }
     }
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use ctl_core::model::{
+        Event, Level, LightEvent, LightSerde, MoveFrame, Movement, Shape, Telegraph, TimedEvent,
+    };
+
+    /// A friendly light that stays in the center for `duration` beats.
+    fn light(beat: f32, duration: f32) -> TimedEvent {
+        let mut movement = Movement::default();
+        movement
+            .key_frames
+            .push_back(MoveFrame::scale(duration, 1.0));
+        TimedEvent {
+            beat: r32(beat),
+            event: Event::Light(LightEvent {
+                light: LightSerde {
+                    danger: false,
+                    shape: Shape::Circle { radius: r32(1.0) },
+                    movement,
+                },
+                telegraph: Telegraph::default(),
+            }),
+        }
+    }
+
+    fn group(levels: Vec<Vec<TimedEvent>>) -> LevelSet<LevelFull> {
+        LevelSet {
+            id: 0,
+            music: 0,
+            owner: UserInfo {
+                id: 0,
+                name: "owner".into(),
+            },
+            levels: levels
+                .into_iter()
+                .map(|events| LevelFull {
+                    meta: LevelInfo::default(),
+                    data: Level { events },
+                })
+                .collect(),
+        }
+    }
+
+    fn validate(group: &LevelSet<LevelFull>) -> Result<()> {
+        validate_group(group, &MusicInfo::default(), &AppLimits::default())
+    }
+
+    #[test]
+    fn valid_group() {
+        assert!(validate(&group(vec![vec![light(0.0, 40.0)]])).is_ok());
+    }
+
+    #[test]
+    fn group_without_levels() {
+        assert!(matches!(
+            validate(&group(Vec::new())),
+            Err(RequestError::NoLevels)
+        ));
+    }
+
+    #[test]
+    fn level_too_short() {
+        let group = group(vec![vec![light(0.0, 40.0)], vec![light(0.0, 4.0)]]);
+        assert!(matches!(validate(&group), Err(RequestError::LevelTooSmall)));
+    }
+
+    #[test]
+    fn level_with_lint_error() {
+        // Too long in the dark between the lights
+        let group = group(vec![vec![light(0.0, 4.0), light(30.0, 4.0)]]);
+        assert!(matches!(
+            validate(&group),
+            Err(RequestError::LevelLint { .. })
+        ));
+    }
+
+    #[test]
+    fn escape_like_wildcards() {
+        assert_eq!(escape_like("100%_done"), "100\\%\\_done");
+        assert_eq!(escape_like("back\\slash"), "back\\\\slash");
+        assert_eq!(escape_like("plain"), "plain");
+    }
+
+    #[test]
+    fn cursor_round_trip() {
+        let cursors = [
+            (GroupsSort::Newest, GroupsCursor::Newest { id: 7 }),
+            (
+                GroupsSort::MostPlayed,
+                GroupsCursor::MostPlayed { plays: 42, id: 7 },
+            ),
+            (
+                GroupsSort::Name,
+                GroupsCursor::Name {
+                    id: 7,
+                    name: "Re: Light".into(),
+                },
+            ),
+        ];
+        for (sort, cursor) in cursors {
+            let parsed = GroupsCursor::parse(sort, &cursor.to_string()).unwrap();
+            assert_eq!(parsed, cursor);
+        }
+    }
+
+    #[test]
+    fn invalid_cursor() {
+        for (sort, cursor) in [
+            (GroupsSort::Newest, "abc"),
+            (GroupsSort::Newest, "1:2"),
+            (GroupsSort::MostPlayed, "42"),
+            (GroupsSort::MostPlayed, "x:7"),
+            (GroupsSort::Name, "name:7"),
+        ] {
+            assert!(matches!(
+                GroupsCursor::parse(sort, cursor),
+                Err(RequestError::InvalidCursor)
+            ));
+        }
+    }
+}
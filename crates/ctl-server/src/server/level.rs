@@ -2,13 +2,29 @@ use super::*;
 
 use crate::database::types::LevelRow;
 
-use ctl_core::{ScoreEntry, SubmitScore};
+use ctl_core::{
+    prelude::{r32, CalculatedScore, HealthPreset, Replay, Time},
+    simulation,
+    types::{LevelFull, LevelSet},
+    ScoreCategory, ScoreEntry, ScoresQuery, SubmitScore,
+};
+use sqlx::QueryBuilder;
+
+/// Relative difference allowed between the submitted and the simulated score,
+/// to account for floating point differences between platforms.
+const SCORE_VERIFY_TOLERANCE: f32 = 0.005;
 
 pub fn route(router: Router) -> Router {
-    router.route("/level/:level_id", get(level_get)).route(
-        "/level/:level_id/scores",
-        get(fetch_scores).post(submit_score),
-    )
+    router
+        .route("/level/:level_id", get(level_get))
+        .route(
+            "/level/:level_id/scores",
+            get(fetch_scores).post(submit_score),
+        )
+        .route(
+            "/level/:level_id/authors",
+            post(add_level_author).delete(remove_level_author),
+        )
 }
 
 async fn level_get(
@@ -47,12 +63,14 @@ WHERE level_id = ?
         name: level.name.into(),
         hash: level.hash,
         authors,
+        difficulty: level.difficulty.map(r32),
     }))
 }
 
 async fn fetch_scores(
     State(app): State<Arc<App>>,
     Path(level_id): Path<Id>,
+    Query(query): Query<ScoresQuery>,
 ) -> Result<Json<Vec<ScoreEntry>>> {
     // Check that the level exists
     let check = sqlx::query("SELECT null FROM levels WHERE level_id = ?")
@@ -64,38 +82,62 @@ async fn fetch_scores(
     }
 
     #[derive(sqlx::FromRow)]
-    struct ScoreRow {
+    struct ScoreUserRow {
         #[sqlx(flatten)]
         user: UserRow,
-        score: Score,
-        extra_info: Option<String>,
+        #[sqlx(flatten)]
+        score: ScoreRow,
     }
 
     // Fetch scores
-    let scores: Vec<ScoreRow> = sqlx::query_as(
+    let mut builder = QueryBuilder::new(
         "
-SELECT users.user_id, username, score, extra_info
+SELECT *
 FROM scores
 JOIN users ON scores.user_id = users.user_id
-WHERE level_id = ?
-        ",
-    )
-    .bind(level_id)
-    .fetch_all(&app.database)
-    .await?;
+WHERE level_id = ",
+    );
+    builder.push_bind(level_id);
+    if let Some(nofail) = query.nofail {
+        builder.push(" AND nofail = ").push_bind(nofail);
+    }
+    if let Some(sudden) = query.sudden {
+        builder.push(" AND sudden = ").push_bind(sudden);
+    }
+    if let Some(hidden) = query.hidden {
+        builder.push(" AND hidden = ").push_bind(hidden);
+    }
+    if let Some(health) = query.health {
+        builder
+            .push(" AND health = ")
+            .push_bind(health_preset_name(health));
+    }
+    builder.push(" ORDER BY score DESC");
+    let scores: Vec<ScoreUserRow> = builder.build_query_as().fetch_all(&app.database).await?;
 
-    let scores = scores
+    let mut scores: Vec<ScoreEntry> = scores
         .into_iter()
-        .map(|score| ScoreEntry {
-            user: UserInfo {
-                id: score.user.user_id,
-                name: score.user.username.into(),
-            },
-            score: score.score,
-            extra_info: score.extra_info,
+        .enumerate()
+        .map(|(i, row)| ScoreEntry {
+            user: row.user.into(),
+            category: row.score.category(),
+            score: row.score.score,
+            extra_info: row.score.extra_info,
+            verified: row.score.verified,
+            rank: i as u32 + 1,
         })
         .collect();
 
+    // Select the requested part of the leaderboard
+    if let Some(limit) = query.limit {
+        let limit = limit as usize;
+        let start = query
+            .around
+            .and_then(|user| scores.iter().position(|entry| entry.user.id == user))
+            .map_or(0, |position| position.saturating_sub(limit / 2));
+        scores = scores.into_iter().skip(start).take(limit).collect();
+    }
+
     Ok(Json(scores))
 }
 
@@ -108,45 +150,258 @@ async fn submit_score(
     let user = check_user(&session).await?;
 
     // Check that the level exists
-    let check = sqlx::query("SELECT null FROM levels WHERE level_id = ?")
+    let level: Option<LevelRow> = sqlx::query_as("SELECT * FROM levels WHERE level_id = ?")
         .bind(level_id)
         .fetch_optional(&app.database)
         .await?;
-    if check.is_none() {
+    let Some(level) = level else {
         return Err(RequestError::NoSuchLevel(level_id));
+    };
+
+    // Verify the score
+    let simulated = match &score.replay {
+        Some(replay) => {
+            let category = ScoreCategory::new(&replay.config.modifiers, &replay.config.health);
+            if category != score.category {
+                return Err(RequestError::InvalidReplay);
+            }
+            verify_replay(&app, &level, replay, score.score).await?
+        }
+        None => None,
+    };
+    let verified = simulated.is_some();
+    // Prefer the simulated stats, the submitted ones cannot be checked
+    let (accuracy, precision) = match &simulated {
+        Some(simulated) => (simulated.accuracy.as_f32(), simulated.precision.as_f32()),
+        None => (score.accuracy, score.precision),
+    };
+
+    // Only a simulated run proves the category, the rest go to the custom leaderboard
+    let mut category = score.category;
+    if !verified {
+        category.health = HealthPreset::Custom;
     }
+    let health = health_preset_name(category.health);
 
-    // Insert new score
-    let current: Option<ScoreRow> =
-        sqlx::query_as("SELECT * FROM scores WHERE level_id = ? AND user_id = ?")
-            .bind(level_id)
-            .bind(user.user_id)
-            .fetch_optional(&app.database)
-            .await?;
+    // Record the run in the history
+    sqlx::query(
+        "
+INSERT INTO score_history
+(level_id, user_id, score, accuracy, precision, verified, nofail, sudden, hidden, health, submitted_at)
+VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+        ",
+    )
+    .bind(level_id)
+    .bind(user.user_id)
+    .bind(score.score)
+    .bind(accuracy)
+    .bind(precision)
+    .bind(verified)
+    .bind(category.nofail)
+    .bind(category.sudden)
+    .bind(category.hidden)
+    .bind(health)
+    .bind(time::OffsetDateTime::now_utc().unix_timestamp())
+    .execute(&app.database)
+    .await?;
+
+    // Insert new score, keeping the best one in each category
+    let current: Option<ScoreRow> = sqlx::query_as(
+        "
+SELECT * FROM scores
+WHERE level_id = ? AND user_id = ? AND nofail = ? AND sudden = ? AND hidden = ? AND health = ?
+        ",
+    )
+    .bind(level_id)
+    .bind(user.user_id)
+    .bind(category.nofail)
+    .bind(category.sudden)
+    .bind(category.hidden)
+    .bind(health)
+    .fetch_optional(&app.database)
+    .await?;
 
     if let Some(current) = current {
         if score.score > current.score {
             sqlx::query(
-                "UPDATE scores SET score = ?, extra_info = ? WHERE level_id = ? AND user_id = ?",
+                "
+UPDATE scores SET score = ?, extra_info = ?, verified = ?
+WHERE level_id = ? AND user_id = ? AND nofail = ? AND sudden = ? AND hidden = ? AND health = ?
+                ",
             )
             .bind(score.score)
             .bind(&score.extra_info)
+            .bind(verified)
             .bind(level_id)
             .bind(user.user_id)
+            .bind(category.nofail)
+            .bind(category.sudden)
+            .bind(category.hidden)
+            .bind(health)
             .execute(&app.database)
             .await?;
         }
     } else {
         sqlx::query(
-            "INSERT INTO scores (level_id, user_id, score, extra_info) VALUES (?, ?, ?, ?)",
+            "
+INSERT INTO scores (level_id, user_id, score, extra_info, verified, nofail, sudden, hidden, health)
+VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
+            ",
         )
         .bind(level_id)
         .bind(user.user_id)
         .bind(score.score)
         .bind(&score.extra_info)
+        .bind(verified)
+        .bind(category.nofail)
+        .bind(category.sudden)
+        .bind(category.hidden)
+        .bind(health)
         .execute(&app.database)
         .await?;
     }
 
     Ok(())
 }
+
+/// Re-simulate the replay against the stored level data
+/// and check that it results in the submitted score.
+/// Returns the simulated score, or `None` if the replay cannot be verified, but might be legit,
+/// and an error if the replay does not match the score.
+async fn verify_replay(
+    app: &Arc<App>,
+    level: &LevelRow,
+    replay: &Replay,
+    score: Score,
+) -> Result<Option<CalculatedScore>> {
+    // Only the config of the category is accepted, so it cannot be tweaked in the player's favor
+    let Some(config) =
+        ScoreCategory::new(&replay.config.modifiers, &replay.config.health).level_config()
+    else {
+        return Ok(None);
+    };
+    if replay.config != config {
+        debug!("Replay verification failed: config does not match the category");
+        return Err(RequestError::InvalidReplay);
+    }
+
+    if replay.level_hash != level.hash || replay.start_time != Time::ZERO {
+        // Played an older version of the level or only a part of it
+        return Ok(None);
+    }
+
+    let group: GroupRow = sqlx::query_as("SELECT * FROM groups WHERE group_id = ?")
+        .bind(level.group_id)
+        .fetch_one(&app.database)
+        .await?;
+    let music = super::music::music_get(State(app.clone()), Path(group.music_id))
+        .await?
+        .0;
+
+    let path = app
+        .config
+        .groups_path
+        .join("levels")
+        .join(level.group_id.to_string());
+    let data = tokio::fs::read(path).await?;
+    let group: LevelSet<LevelFull> = LevelSet::decode(&data).map_err(|_| RequestError::Internal)?;
+    let Some(level_data) = group
+        .levels
+        .iter()
+        .find(|data| data.meta.id == level.level_id)
+    else {
+        error!(
+            "Level {} is missing from the group file {}",
+            level.level_id, level.group_id
+        );
+        return Err(RequestError::Internal);
+    };
+
+    // Simulating takes a while
+    let level_data = level_data.data.clone();
+    let replay = replay.clone();
+    let result = tokio::task::spawn_blocking(move || {
+        simulation::simulate_replay(&level_data, &replay, &music)
+    })
+    .await
+    .map_err(|_| RequestError::Internal)?
+    .map_err(|err| {
+        debug!("Replay verification failed: {}", err);
+        RequestError::InvalidReplay
+    })?;
+    let simulated = result.score.calculated;
+    let tolerance = (simulated.combined.abs() as f32 * SCORE_VERIFY_TOLERANCE).ceil() as Score;
+    if (simulated.combined - score).abs() > tolerance {
+        debug!(
+            "Replay verification failed: submitted {}, simulated {}",
+            score, simulated.combined
+        );
+        return Err(RequestError::InvalidReplay);
+    }
+
+    Ok(Some(simulated))
+}
+
+/// Check that the user owns the group of the level.
+async fn check_level_owner(
+    connection: &mut sqlx::SqliteConnection,
+    user: &User,
+    level_id: Id,
+) -> Result<Id> {
+    let level: Option<LevelRow> = sqlx::query_as("SELECT * FROM levels WHERE level_id = ?")
+        .bind(level_id)
+        .fetch_optional(&mut *connection)
+        .await?;
+    let level = level.ok_or(RequestError::NoSuchLevel(level_id))?;
+    super::group::check_group_owner(&mut *connection, user, level.group_id).await?;
+    Ok(level.group_id)
+}
+
+async fn add_level_author(
+    session: AuthSession,
+    State(app): State<Arc<App>>,
+    Path(level_id): Path<Id>,
+    Query(author): Query<IdQuery>,
+) -> Result<()> {
+    let user = check_user(&session).await?;
+    let mut transaction = app.database.begin().await?;
+    let group_id = check_level_owner(&mut transaction, user, level_id).await?;
+    super::group::user_exists(&mut *transaction, author.id).await?;
+
+    let check = sqlx::query("SELECT null FROM level_authors WHERE level_id = ? AND user_id = ?")
+        .bind(level_id)
+        .bind(author.id)
+        .fetch_optional(&mut *transaction)
+        .await?;
+    if check.is_some() {
+        // Already an author
+        return Ok(());
+    }
+
+    sqlx::query("INSERT INTO level_authors (level_id, user_id) VALUES (?, ?)")
+        .bind(level_id)
+        .bind(author.id)
+        .execute(&mut *transaction)
+        .await?;
+
+    super::group::save_group_authors(&app, transaction, group_id).await
+}
+
+async fn remove_level_author(
+    session: AuthSession,
+    State(app): State<Arc<App>>,
+    Path(level_id): Path<Id>,
+    Query(author): Query<IdQuery>,
+) -> Result<()> {
+    let user = check_user(&session).await?;
+    let mut transaction = app.database.begin().await?;
+    let group_id = check_level_owner(&mut transaction, user, level_id).await?;
+
+    sqlx::query("DELETE FROM level_authors WHERE level_id = ? AND user_id = ?")
+        .bind(level_id)
+        .bind(author.id)
+        .execute(&mut *transaction)
+        .await?;
+
+    super::group::save_group_authors(&app, transaction, group_id).await
+}
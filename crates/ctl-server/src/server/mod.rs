@@ -15,7 +15,7 @@ use crate::{
         types::*,
     },
     prelude::*,
-    AppConfig, AppSecrets,
+    AppConfig, AppLimits, AppSecrets,
 };
 
 use std::collections::BTreeMap;
@@ -75,6 +75,10 @@ pub async fn run(
         account_links: RwLock::new(BTreeMap::new()),
     });
 
+    group::upgrade_group_files(&app)
+        .await
+        .wrap_err("failed to upgrade the group files")?;
+
     // Session layer
     let session_store = SqliteStore::new(app.database.clone());
     session_store.migrate().await?;
@@ -101,7 +105,7 @@ pub async fn run(
         .merge(artists::router());
 
     let router = music::route(router);
-    let router = group::route(router);
+    let router = group::route(router, &app.config.limits);
     let router = level::route(router);
 
     let client = Client::builder()
@@ -229,14 +233,10 @@ async fn send_file(
     Ok((headers, body))
 }
 
-fn content_mp3() -> String {
-    "audio/mpeg".to_owned()
+fn content_music(format: ctl_core::types::MusicFormat) -> String {
+    format.mime_type().to_owned()
 }
 
-// fn content_wav() -> String {
-//     "audio/wav".to_owned()
-// }
-
 fn content_level() -> String {
     "application/octet-stream".to_owned()
 }
@@ -1,15 +1,15 @@
-use crate::database::types::MusicRow;
+use crate::{audio::WAVEFORM_RESOLUTION, database::types::MusicRow};
 
 use super::*;
 
 use ctl_core::{
+    analysis::OnsetEnvelope,
+    audio::{Audio, AudioError},
     prelude::r32,
-    types::{ArtistInfo, MusicUpdate, NewMusic},
+    types::{ArtistInfo, MusicFormat, MusicUpdate, NewMusic},
 };
 use sqlx::FromRow;
 
-const MUSIC_SIZE_LIMIT: usize = 10 * 1024 * 1024; // 10 MB
-
 pub fn route(router: Router) -> Router {
     router
         .route("/music", get(music_list))
@@ -19,6 +19,7 @@ pub fn route(router: Router) -> Router {
             post(add_author).delete(remove_author),
         )
         .route("/music/:music_id/download", get(download))
+        .route("/music/:music_id/waveform", get(waveform))
         .route("/music/create", post(music_create))
 }
 
@@ -67,6 +68,9 @@ JOIN artists ON music_authors.artist_id = artists.artist_id
                 romanized: music.romanized_name.into(),
                 bpm: r32(music.bpm),
                 authors,
+                offset: r32(music.beat_offset),
+                tempo: music.tempo(),
+                duration: r32(music.duration),
             }
         })
         .collect();
@@ -107,6 +111,9 @@ WHERE music_id = ?
         romanized: music.romanized_name.into(),
         bpm: r32(music.bpm),
         authors,
+        offset: r32(music.beat_offset),
+        tempo: music.tempo(),
+        duration: r32(music.duration),
     };
     Ok(Json(music))
 }
@@ -121,44 +128,82 @@ async fn music_create(
 
     music.name = validate_name(music.name)?;
 
-    // TODO: check that file is mp3 format
     // Download the file
-    let data = axum::body::to_bytes(body, MUSIC_SIZE_LIMIT)
+    let data = axum::body::to_bytes(body, app.config.limits.music_size)
         .await
-        .expect("not bytes idk");
+        .map_err(|_| RequestError::MusicTooLarge)?;
+
+    // Validate
+    let format = MusicFormat::detect(&data).ok_or(RequestError::InvalidMusic)?;
+    let audio = decode_audio(data.to_vec(), format).await?.map_err(|err| {
+        debug!("Uploaded music failed to decode: {}", err);
+        RequestError::InvalidMusic
+    })?;
+    let duration = audio.duration();
+
+    // Analysis goes through the whole track, keep it off the async runtime
+    let (bpm, offset) = (music.bpm, music.offset);
+    let (audio, waveform, timing) = tokio::task::spawn_blocking(move || {
+        let waveform = audio.waveform(WAVEFORM_RESOLUTION);
+        let envelope = OnsetEnvelope::new(&audio.mono(), audio.sample_rate);
+        let timing = bpm.or_else(|| envelope.estimate_bpm()).map(|bpm| {
+            let offset = offset.unwrap_or_else(|| envelope.estimate_offset(bpm));
+            (bpm, offset)
+        });
+        (audio, waveform, timing)
+    })
+    .await
+    .map_err(|_| RequestError::Internal)?;
+    let (bpm, offset) = timing.ok_or(RequestError::UnknownBpm)?;
+    debug!("Music timing: {:.2} bpm, first beat at {:.3}s", bpm, offset);
+
+    // Transcode for the clients that cannot decode the original
+    let ogg = if format == MusicFormat::Ogg {
+        None
+    } else {
+        Some(encode_ogg(audio).await?)
+    };
 
     // Commit to database
+    let mut transaction = app.database.begin().await?;
     let music_id: Id = sqlx::query(
-        "INSERT INTO musics (name, romanized_name, public, original, bpm) VALUES (?, ?, ?, ?, ?) RETURNING music_id",
+        "INSERT INTO musics (name, romanized_name, public, original, bpm, beat_offset, format, duration, waveform) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?) RETURNING music_id",
     )
     .bind(music.name)
     .bind(music.romanized_name)
     .bind(false)
     .bind(music.original)
-    .bind(music.bpm)
+    .bind(bpm)
+    .bind(offset)
+    .bind(music_format_name(format))
+    .bind(duration)
+    .bind(waveform)
     .try_map(|row: DBRow| row.try_get("music_id"))
-    .fetch_one(&app.database)
+    .fetch_one(&mut *transaction)
     .await?;
-    debug!("New music committed to the database");
 
     // Check path
-    let dir_path = app.config.groups_path.join("music");
-    std::fs::create_dir_all(&dir_path)?;
-    let path = dir_path.join(music_id.to_string());
+    let path = music_path(&app, music_id);
+    std::fs::create_dir_all(path.parent().expect("music path has a parent"))?;
     debug!("Saving music file at {:?}", path);
 
-    // let Some(music_path) = path.to_str() else {
-    //     error!("Music path is not valid unicode");
-    //     return Err(RequestError::Internal);
-    // };
-
     if path.exists() {
         error!("Duplicate music ID generated: {}", music_id);
         return Err(RequestError::Internal);
     }
 
-    // Write file to path
-    std::fs::write(&path, data)?;
+    // Write the files first, so the row never points to a missing file
+    let file = group::PendingFile::write(path.clone(), &data)?;
+    let ogg_file = ogg
+        .map(|ogg| group::PendingFile::write(path.with_extension("ogg"), &ogg))
+        .transpose()?;
+
+    transaction.commit().await?;
+    debug!("New music committed to the database");
+    file.persist()?;
+    if let Some(ogg_file) = ogg_file {
+        ogg_file.persist()?;
+    }
     debug!("Saved music file successfully");
 
     Ok(Json(music_id))
@@ -172,19 +217,39 @@ async fn music_update(
 ) -> Result<()> {
     check_auth(&session, &app, AuthorityLevel::Admin).await?;
 
+    if update.bpm.is_some_and(|bpm| !bpm.is_finite() || bpm <= 0.0) {
+        return Err(RequestError::InvalidTempo("bpm must be positive".into()));
+    }
+    if let Some(tempo) = &update.tempo {
+        tempo
+            .validate()
+            .map_err(|err| RequestError::InvalidTempo(err.to_string()))?;
+    }
+
     let result = sqlx::query(
         "
 UPDATE musics
 SET name = COALESCE(?, name),
     public = COALESCE(?, public),
     original = COALESCE(?, original),
-    bpm = COALESCE(?, bpm)
+    bpm = COALESCE(?, bpm),
+    beat_offset = COALESCE(?, beat_offset),
+    tempo = COALESCE(?, tempo)
 WHERE music_id = ?",
     )
     .bind(&update.name)
     .bind(update.public)
     .bind(update.original)
     .bind(update.bpm)
+    .bind(update.offset)
+    .bind(
+        update
+            .tempo
+            .as_ref()
+            .map(serde_json::to_string)
+            .transpose()
+            .map_err(|_| RequestError::Internal)?,
+    )
     .bind(music_id)
     .execute(&app.database)
     .await?;
@@ -257,23 +322,88 @@ async fn remove_author(
     Ok(())
 }
 
+#[derive(Deserialize)]
+struct DownloadQuery {
+    /// Format to download the music in, the original one if not specified.
+    format: Option<MusicFormat>,
+}
+
 async fn download(
     State(app): State<Arc<App>>,
     Path(music_id): Path<Id>,
+    Query(query): Query<DownloadQuery>,
 ) -> Result<impl IntoResponse> {
-    let id: Option<Id> = sqlx::query("SELECT music_id FROM musics WHERE music_id = ?")
+    let format: Option<String> = sqlx::query("SELECT format FROM musics WHERE music_id = ?")
         .bind(music_id)
-        .try_map(|row: DBRow| row.try_get("music_id"))
+        .try_map(|row: DBRow| row.try_get("format"))
         .fetch_optional(&app.database)
         .await?;
 
-    let Some(music_id) = id else {
+    let Some(format) = format else {
+        return Err(RequestError::NoSuchMusic(music_id));
+    };
+    let original = parse_music_format(&format);
+
+    let path = music_path(&app, music_id);
+    match query.format {
+        None => send_file(path, content_music(original)).await,
+        Some(format) if format == original => send_file(path, content_music(original)).await,
+        Some(MusicFormat::Ogg) => {
+            let ogg_path = path.with_extension("ogg");
+            if !ogg_path.exists() {
+                // Uploaded before the music was transcoded on upload
+                debug!("Transcoding music {} to ogg", music_id);
+                let data = std::fs::read(&path)?;
+                let audio = decode_audio(data, original).await?.map_err(|err| {
+                    error!("Stored music {} failed to decode: {}", music_id, err);
+                    RequestError::Internal
+                })?;
+                // Concurrent downloads may transcode at the same time,
+                // so the file only appears once it is complete
+                let ogg = encode_ogg(audio).await?;
+                group::PendingFile::write(ogg_path.clone(), &ogg)?.persist()?;
+            }
+            send_file(ogg_path, content_music(MusicFormat::Ogg)).await
+        }
+        Some(_) => Err(RequestError::UnavailableMusicFormat),
+    }
+}
+
+async fn waveform(State(app): State<Arc<App>>, Path(music_id): Path<Id>) -> Result<Json<Vec<u8>>> {
+    let waveform: Option<Option<Vec<u8>>> =
+        sqlx::query("SELECT waveform FROM musics WHERE music_id = ?")
+            .bind(music_id)
+            .try_map(|row: DBRow| row.try_get("waveform"))
+            .fetch_optional(&app.database)
+            .await?;
+
+    let Some(waveform) = waveform else {
         return Err(RequestError::NoSuchMusic(music_id));
     };
+    // Music uploaded before the analysis was added has no waveform
+    Ok(Json(waveform.unwrap_or_default()))
+}
 
-    // Check path
-    let dir_path = app.config.groups_path.join("music");
-    let path = dir_path.join(music_id.to_string());
+fn music_path(app: &App, music_id: Id) -> std::path::PathBuf {
+    app.config
+        .groups_path
+        .join("music")
+        .join(music_id.to_string())
+}
 
-    send_file(path, content_mp3()).await
+/// Decode the audio on a blocking thread, since it takes a while.
+async fn decode_audio(data: Vec<u8>, format: MusicFormat) -> Result<Result<Audio, AudioError>> {
+    tokio::task::spawn_blocking(move || Audio::decode(data, format))
+        .await
+        .map_err(|_| RequestError::Internal)
+}
+
+async fn encode_ogg(audio: Audio) -> Result<Vec<u8>> {
+    tokio::task::spawn_blocking(move || crate::audio::encode_ogg(&audio))
+        .await
+        .map_err(|_| RequestError::Internal)?
+        .map_err(|err| {
+            error!("Failed to transcode music: {}", err);
+            RequestError::Internal
+        })
 }
@@ -1,10 +1,192 @@
 use super::*;
 
+use ctl_core::types::{GroupsQuery, QuotaOverride, UserProfile, UserQuota, UserScore};
+
 pub fn router() -> Router {
-    Router::new().route("/user/me", get(user_me))
+    Router::new()
+        .route("/user/me", get(user_me))
+        .route("/user/me/quota", get(user_me_quota))
+        .route("/user/:user_id", get(user_profile))
+        .route(
+            "/user/:user_id/quota",
+            get(get_user_quota).put(set_user_quota),
+        )
 }
 
 pub async fn user_me(session: AuthSession) -> Result<String> {
     let user = session.user.as_ref().ok_or(RequestError::Unathorized)?;
     Ok(user.username.clone())
 }
+
+async fn user_profile(
+    State(app): State<Arc<App>>,
+    Path(user_id): Path<Id>,
+) -> Result<Json<UserProfile>> {
+    let user: Option<UserRow> =
+        sqlx::query_as("SELECT user_id, username FROM users WHERE user_id = ?")
+            .bind(user_id)
+            .fetch_optional(&app.database)
+            .await?;
+    let Some(user) = user else {
+        return Err(RequestError::NoSuchUser(user_id));
+    };
+
+    // Uploaded groups
+    let mut groups = Vec::new();
+    let mut query = GroupsQuery {
+        owner: Some(user_id),
+        limit: Some(super::group::GROUPS_PAGE_MAX),
+        ..Default::default()
+    };
+    loop {
+        let Json(page) = super::group::group_list(State(app.clone()), Query(query.clone())).await?;
+        groups.extend(page.groups);
+        match page.next {
+            Some(next) => query.cursor = Some(next),
+            None => break,
+        }
+    }
+
+    // Best scores
+    #[derive(sqlx::FromRow)]
+    struct UserScoreRow {
+        #[sqlx(flatten)]
+        score: ScoreRow,
+        group_id: Id,
+        level_name: String,
+        music_name: String,
+        plays: i64,
+    }
+
+    let scores: Vec<UserScoreRow> = sqlx::query_as(
+        "
+SELECT scores.*, levels.group_id, levels.name AS level_name, musics.name AS music_name, (
+    SELECT COUNT(*) FROM score_history
+    WHERE score_history.level_id = scores.level_id AND score_history.user_id = scores.user_id
+    AND score_history.nofail = scores.nofail AND score_history.sudden = scores.sudden
+    AND score_history.hidden = scores.hidden AND score_history.health = scores.health
+) AS plays
+FROM scores
+JOIN levels ON scores.level_id = levels.level_id
+JOIN groups ON levels.group_id = groups.group_id
+JOIN musics ON groups.music_id = musics.music_id
+WHERE scores.user_id = ?
+ORDER BY scores.score DESC
+        ",
+    )
+    .bind(user_id)
+    .fetch_all(&app.database)
+    .await?;
+
+    let scores = scores
+        .into_iter()
+        .map(|row| UserScore {
+            level: row.score.level_id,
+            group: row.group_id,
+            level_name: row.level_name.into(),
+            music_name: row.music_name.into(),
+            score: row.score.score,
+            category: row.score.category(),
+            plays: row.plays as u32,
+        })
+        .collect();
+
+    let plays: i64 = sqlx::query("SELECT COUNT(*) AS plays FROM score_history WHERE user_id = ?")
+        .bind(user_id)
+        .try_map(|row: DBRow| row.try_get("plays"))
+        .fetch_one(&app.database)
+        .await?;
+
+    Ok(Json(UserProfile {
+        user: user.into(),
+        groups,
+        scores,
+        plays: plays as u32,
+    }))
+}
+
+/// Upload quota of the user, taking their overrides into account.
+pub(super) async fn user_quota(
+    app: &App,
+    connection: &mut sqlx::SqliteConnection,
+    user_id: Id,
+) -> Result<UserQuota> {
+    let limits = &app.config.limits;
+
+    let quota: Option<QuotaOverride> =
+        sqlx::query("SELECT groups, groups_per_song FROM user_quotas WHERE user_id = ?")
+            .bind(user_id)
+            .try_map(|row: DBRow| {
+                Ok(QuotaOverride {
+                    groups: row.try_get("groups")?,
+                    groups_per_song: row.try_get("groups_per_song")?,
+                })
+            })
+            .fetch_optional(&mut *connection)
+            .await?;
+    let quota = quota.unwrap_or_default();
+
+    let owned_groups: Vec<Id> = sqlx::query("SELECT music_id FROM groups WHERE owner_id = ?")
+        .bind(user_id)
+        .try_map(|row: DBRow| row.try_get("music_id"))
+        .fetch_all(&mut *connection)
+        .await?;
+
+    Ok(UserQuota {
+        groups: quota.groups.unwrap_or(limits.groups_per_user),
+        groups_per_song: quota
+            .groups_per_song
+            .unwrap_or(limits.groups_per_user_per_song),
+        owned_groups,
+        group_size: limits.group_size,
+        music_size: limits.music_size,
+        level_min_duration: limits.level_min_duration,
+    })
+}
+
+async fn user_me_quota(
+    session: AuthSession,
+    State(app): State<Arc<App>>,
+) -> Result<Json<UserQuota>> {
+    let user = check_user(&session).await?;
+    let mut connection = app.database.acquire().await?;
+    let quota = user_quota(&app, &mut connection, user.user_id).await?;
+    Ok(Json(quota))
+}
+
+async fn get_user_quota(
+    session: AuthSession,
+    State(app): State<Arc<App>>,
+    Path(user_id): Path<Id>,
+) -> Result<Json<UserQuota>> {
+    check_auth(&session, &app, AuthorityLevel::Admin).await?;
+    super::group::user_exists(&app.database, user_id).await?;
+
+    let mut connection = app.database.acquire().await?;
+    let quota = user_quota(&app, &mut connection, user_id).await?;
+    Ok(Json(quota))
+}
+
+async fn set_user_quota(
+    session: AuthSession,
+    State(app): State<Arc<App>>,
+    Path(user_id): Path<Id>,
+    Json(quota): Json<QuotaOverride>,
+) -> Result<()> {
+    check_auth(&session, &app, AuthorityLevel::Admin).await?;
+    super::group::user_exists(&app.database, user_id).await?;
+
+    sqlx::query(
+        "
+INSERT INTO user_quotas (user_id, groups, groups_per_song) VALUES (?, ?, ?)
+ON CONFLICT(user_id) DO UPDATE SET groups = excluded.groups, groups_per_song = excluded.groups_per_song
+        ",
+    )
+    .bind(user_id)
+    .bind(quota.groups)
+    .bind(quota.groups_per_song)
+    .execute(&app.database)
+    .await?;
+
+    Ok(())
+}
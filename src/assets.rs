@@ -102,6 +102,7 @@ pub struct Shaders {
     pub sdf: ugli::Program,
     pub solid: ugli::Program,
     pub light: ugli::Program,
+    pub light_sdf: ugli::Program,
     pub masked: ugli::Program,
     pub texture: ugli::Program,
 }
@@ -24,6 +24,14 @@ pub enum Command {
     },
     Music(MusicArgs),
     Artist(ArtistArgs),
+    Pattern(PatternArgs),
+    /// Auto-play every level in a group file without a window
+    /// and report whether it can be completed.
+    Verify {
+        path: PathBuf,
+        #[clap(long)]
+        bpm: f32,
+    },
 }
 
 #[derive(clap::Args)]
@@ -38,6 +46,12 @@ pub struct ArtistArgs {
     pub command: ArtistCommand,
 }
 
+#[derive(clap::Args)]
+pub struct PatternArgs {
+    #[command(subcommand)]
+    pub command: PatternCommand,
+}
+
 #[derive(clap::Subcommand)]
 pub enum MusicCommand {
     Author(MusicAuthorArgs),
@@ -50,8 +64,13 @@ pub enum MusicCommand {
         romanized_name: Option<String>,
         #[clap(long)]
         original: bool,
+        /// Detected by the server if not specified.
         #[clap(long)]
-        bpm: f32,
+        bpm: Option<f32>,
+        /// Time (in seconds) of the first beat.
+        /// Detected by the server if not specified.
+        #[clap(long)]
+        offset: Option<f32>,
     },
     /// Update music info.
     Update {
@@ -64,6 +83,11 @@ pub enum MusicCommand {
         original: Option<bool>,
         #[clap(long)]
         bpm: Option<f32>,
+        #[clap(long)]
+        offset: Option<f32>,
+        /// Path to the music meta file to copy the tempo map from.
+        #[clap(long)]
+        tempo_from: Option<PathBuf>,
     },
 }
 
@@ -100,6 +124,14 @@ pub enum ArtistCommand {
     },
 }
 
+#[derive(clap::Subcommand)]
+pub enum PatternCommand {
+    /// Copy a pattern file into the editor pattern library.
+    Import { path: PathBuf },
+    /// Save a pattern from the editor pattern library into a file.
+    Export { name: String, path: PathBuf },
+}
+
 impl Command {
     pub async fn execute(self, context: Context, secrets: Option<Secrets>) -> Result<()> {
         let client = if let Some(secrets) = &secrets {
@@ -146,7 +178,7 @@ impl Command {
                     levels,
                 };
 
-                let data = bincode::serialize(&group)?;
+                let data = group.encode()?;
                 let path = path.with_file_name(format!(
                     "{}.ctl",
                     path.file_name().unwrap().to_str().unwrap()
@@ -170,12 +202,14 @@ impl Command {
                         romanized_name,
                         original,
                         bpm,
+                        offset,
                     } => {
                         let music = ctl_client::core::types::NewMusic {
                             romanized_name: romanized_name.unwrap_or(name.clone()),
                             name,
                             original,
                             bpm,
+                            offset,
                         };
                         log::info!("Uploading music from {:?}: {:?}", path, music);
 
@@ -191,12 +225,24 @@ impl Command {
                         public,
                         original,
                         bpm,
+                        offset,
+                        tempo_from,
                     } => {
+                        let tempo = match tempo_from {
+                            Some(path) => {
+                                let meta: ctl_client::core::types::MusicInfo =
+                                    file::load_detect(path).await?;
+                                Some(meta.tempo)
+                            }
+                            None => None,
+                        };
                         let update = ctl_client::core::types::MusicUpdate {
                             name,
                             public,
                             original,
                             bpm,
+                            offset,
+                            tempo,
                         };
                         log::info!("Updating music {}: {:#?}", id, update);
 
@@ -224,6 +270,56 @@ impl Command {
                     },
                 }
             }
+            Command::Verify { path, bpm } => {
+                use ctl_client::core::{
+                    model::LevelConfig,
+                    simulation,
+                    types::{LevelSet, MusicInfo},
+                };
+
+                let bytes = std::fs::read(&path)?;
+                let group: LevelSet = LevelSet::decode(&bytes)?;
+                let music = MusicInfo {
+                    bpm: r32(bpm),
+                    ..default()
+                };
+                let config = LevelConfig::default();
+                for level in &group.levels {
+                    let result = simulation::verify(&level.data, &config, &music);
+                    let score = result.score.calculated;
+                    log::info!(
+                        "Level {:?}: completed: {}, score: {}, accuracy: {:.2}%, frames: {}",
+                        level.meta.name,
+                        result.completed,
+                        score.combined,
+                        score.accuracy.as_f32() * 100.0,
+                        result.frames,
+                    );
+                }
+            }
+            Command::Pattern(pattern) => {
+                use ctl_client::core::model::Pattern;
+
+                let library = crate::local::fs::all_patterns_path();
+                match pattern.command {
+                    PatternCommand::Import { path } => {
+                        let data = std::fs::read_to_string(&path)?;
+                        let pattern = Pattern::decode(&data)
+                            .with_context(|| format!("invalid pattern file {:?}", path))?;
+                        std::fs::create_dir_all(&library)?;
+                        std::fs::write(library.join(pattern.file_name()), pattern.encode()?)?;
+                        log::info!("Imported pattern {:?}", pattern.name);
+                    }
+                    PatternCommand::Export { name, path } => {
+                        let file = library.join(Pattern::name_to_file(&name));
+                        let data = std::fs::read_to_string(file)
+                            .context("pattern not found in the library")?;
+                        let pattern = Pattern::decode(&data)?;
+                        std::fs::write(&path, pattern.encode()?)?;
+                        log::info!("Exported pattern {:?} to {:?}", pattern.name, path);
+                    }
+                }
+            }
             Command::Artist(artist) => {
                 let client = client.expect("Cannot update artists without secrets");
                 match artist.command {
@@ -142,7 +142,8 @@ impl MusicManager {
     }
 
     pub fn play_from_beat(&self, music: &CachedMusic, beat: Time) {
-        let time = Duration::from_secs_f64((beat * music.meta.beat_time()).as_f32() as f64);
+        let time = music.meta.beat_to_music_time(beat).max(Time::ZERO);
+        let time = Duration::from_secs_f64(time.as_f32() as f64);
         self.play_from(music, time)
     }
 }
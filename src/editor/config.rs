@@ -1,12 +1,16 @@
 use super::*;
 
+/// Subdivisions of a beat that the time can be snapped to.
+pub const BEAT_DIVISORS: [u32; 8] = [1, 2, 3, 4, 6, 8, 12, 16];
+
 #[derive(geng::asset::Load, Debug, Clone, Serialize, Deserialize)]
 #[load(serde = "ron")]
 pub struct EditorConfig {
     pub grid: GridConfig,
     /// How much of the music to playback when scrolling (in beats).
     pub playback_duration: Time,
-    pub scroll_slow: Time,
+    /// Default subdivision of a beat, one of [`BEAT_DIVISORS`].
+    pub beat_divisor: u32,
     pub scroll_normal: Time,
     pub scroll_fast: Time,
     pub theme: EditorTheme,
@@ -27,3 +31,26 @@ pub struct EditorTheme {
     pub hover: Color,
     pub select: Color,
 }
+
+/// Duration (in beats) of a single subdivision of the beat.
+pub fn beat_division(divisor: u32) -> Time {
+    r32(1.0 / divisor.max(1) as f32)
+}
+
+/// Round the beat to the closest subdivision.
+pub fn snap_beat(beat: Time, divisor: u32) -> Time {
+    let divisor = divisor.max(1) as f32;
+    r32((beat.as_f32() * divisor).round() / divisor)
+}
+
+/// Returns the divisor `delta` steps away in [`BEAT_DIVISORS`].
+pub fn next_beat_divisor(divisor: u32, delta: isize) -> u32 {
+    let current = BEAT_DIVISORS
+        .iter()
+        .position(|&other| other >= divisor)
+        .unwrap_or(BEAT_DIVISORS.len() - 1);
+    let next = current
+        .saturating_add_signed(delta)
+        .min(BEAT_DIVISORS.len() - 1);
+    BEAT_DIVISORS[next]
+}
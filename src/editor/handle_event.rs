@@ -41,8 +41,9 @@ impl EditorState {
         let shift = window.is_key_pressed(geng::Key::ShiftLeft);
         let alt = window.is_key_pressed(geng::Key::AltLeft);
 
+        let divisor = self.editor.beat_divisor;
         let scroll_speed = if shift {
-            self.editor.config.scroll_slow
+            beat_division(divisor)
         } else if alt {
             self.editor.config.scroll_fast
         } else {
@@ -51,11 +52,23 @@ impl EditorState {
 
         match event {
             geng::Event::KeyPress { key } => match key {
+                geng::Key::ArrowLeft if ctrl => {
+                    level_editor.shift_selection(-scroll_speed);
+                }
+                geng::Key::ArrowRight if ctrl => {
+                    level_editor.shift_selection(scroll_speed);
+                }
                 geng::Key::ArrowLeft => {
-                    level_editor.scroll_time(-scroll_speed);
+                    level_editor.scroll_time(-scroll_speed, divisor);
                 }
                 geng::Key::ArrowRight => {
-                    level_editor.scroll_time(scroll_speed);
+                    level_editor.scroll_time(scroll_speed, divisor);
+                }
+                geng::Key::BracketLeft => {
+                    self.editor.beat_divisor = next_beat_divisor(divisor, -1);
+                }
+                geng::Key::BracketRight => {
+                    self.editor.beat_divisor = next_beat_divisor(divisor, 1);
                 }
                 geng::Key::F => {
                     if ctrl {
@@ -65,9 +78,44 @@ impl EditorState {
                         self.editor.visualize_beat = !self.editor.visualize_beat
                     }
                 }
+                geng::Key::X if ctrl => {
+                    if let Some(clipboard) = level_editor.cut_selection() {
+                        self.editor.clipboard = clipboard;
+                    }
+                }
                 geng::Key::X => {
                     if !level_editor.delete_waypoint_selected() {
-                        level_editor.delete_light_selected();
+                        level_editor.delete_selection();
+                    }
+                }
+                geng::Key::C if ctrl => {
+                    if let Some(clipboard) = level_editor.copy_selection() {
+                        self.editor.clipboard = clipboard;
+                    }
+                }
+                geng::Key::V if ctrl => {
+                    level_editor.paste(
+                        self.editor.clipboard.events(),
+                        Placement::at(self.editor.cursor_world_pos_snapped),
+                    );
+                }
+                geng::Key::A if ctrl => {
+                    if let State::Idle = level_editor.state {
+                        level_editor.selection.clear();
+                        level_editor.selection.extend(
+                            level_editor
+                                .level
+                                .events
+                                .iter()
+                                .enumerate()
+                                .filter(|(_, event)| matches!(event.event, Event::Light(_)))
+                                .map(|(event, _)| LightId { event }),
+                        );
+                    }
+                }
+                geng::Key::M => {
+                    if let State::Idle = level_editor.state {
+                        level_editor.flip_selection(!shift);
                     }
                 }
                 geng::Key::S if ctrl => {
@@ -89,12 +137,11 @@ impl EditorState {
                     // Toggle danger
                     match &mut level_editor.state {
                         State::Idle => {
-                            if let Some(event) = level_editor
-                                .selected_light
-                                .and_then(|i| level_editor.level.events.get_mut(i.event))
-                            {
-                                if let Event::Light(event) = &mut event.event {
-                                    event.light.danger = !event.light.danger;
+                            for id in level_editor.selection.lights() {
+                                if let Some(event) = level_editor.level.events.get_mut(id.event) {
+                                    if let Event::Light(event) = &mut event.event {
+                                        event.light.danger = !event.light.danger;
+                                    }
                                 }
                             }
                         }
@@ -128,9 +175,11 @@ impl EditorState {
                     match &mut level_editor.state {
                         State::Idle => {
                             // Cancel selection
-                            level_editor.selected_light = None;
+                            level_editor.selection.clear();
                         }
-                        State::Movement { .. } | State::Place { .. } => {
+                        State::Movement { .. }
+                        | State::Place { .. }
+                        | State::PlacePattern { .. } => {
                             // Cancel creation
                             level_editor.state = State::Idle;
                         }
@@ -166,12 +215,15 @@ impl EditorState {
                             start_beat: level_editor.current_beat,
                             old_state: Box::new(level_editor.state.clone()),
                         };
-                        // TODO: future proof in case level beat time is not constant
-                        level_editor.real_time = level_editor.current_beat
-                            * level_editor.static_level.group.music.meta.beat_time();
-                        self.context.music.play_from(
+                        level_editor.real_time = level_editor
+                            .static_level
+                            .group
+                            .music
+                            .meta
+                            .beat_to_time(level_editor.current_beat);
+                        self.context.music.play_from_beat(
                             &level_editor.static_level.group.music,
-                            time::Duration::from_secs_f64(level_editor.real_time.as_f32() as f64),
+                            level_editor.current_beat,
                         );
                     }
                 }
@@ -219,6 +271,7 @@ impl EditorState {
                             let zoom = (zoom + scroll.as_f32()).clamp(5.0, 50.0);
                             timeline.rescale(zoom);
                         } else if let State::Place { .. }
+                        | State::PlacePattern { .. }
                         | State::Movement { .. }
                         | State::Waypoints {
                             state: WaypointsState::New,
@@ -239,18 +292,20 @@ impl EditorState {
                                             light.light.movement.get_frame_mut(selected)
                                         {
                                             let delta = scroll * r32(0.1);
-                                            frame.scale =
-                                                (frame.scale + delta).clamp(r32(0.2), r32(2.0));
+                                            frame.scale = frame.scale.map(|scale| {
+                                                (scale + delta).clamp(r32(0.2), r32(2.0))
+                                            });
                                         }
                                     }
                                 }
                             }
                         } else if let Some(event) = level_editor
-                            .selected_light
+                            .selection
+                            .single()
                             .and_then(|light| level_editor.level.events.get_mut(light.event))
                         {
                             // Control fade time
-                            let change = scroll * self.editor.config.scroll_slow;
+                            let change = scroll * beat_division(divisor);
                             if let Event::Light(light) = &mut event.event {
                                 let movement = &mut light.light.movement;
                                 if shift {
@@ -298,9 +353,9 @@ impl EditorState {
                         }
                         State::Idle => {
                             // Cancel selection
-                            level_editor.selected_light = None;
+                            level_editor.selection.clear();
                         }
-                        State::Place { .. } => {
+                        State::Place { .. } | State::PlacePattern { .. } => {
                             // Cancel creation
                             level_editor.state = State::Idle;
                         }
@@ -336,15 +391,15 @@ impl EditorState {
         };
 
         match &mut level_editor.state {
-            State::Idle | State::Place { .. } => {
-                if let Some(&shape) = self
+            State::Idle | State::Place { .. } | State::PlacePattern { .. } => {
+                if let Some(shape) = self
                     .editor
                     .config
                     .shapes
                     .get((digit as usize).saturating_sub(1))
                 {
                     level_editor.state = State::Place {
-                        shape,
+                        shape: shape.clone(),
                         danger: false,
                     };
                 }
@@ -377,6 +432,7 @@ impl EditorState {
         let Some(level_editor) = &mut self.editor.level_edit else {
             return;
         };
+        let divisor = self.editor.beat_divisor;
 
         // if let Some(drag) = &self.drag {
         //     let DragTarget::Waypoint {
@@ -401,7 +457,7 @@ impl EditorState {
                             let next = WaypointId::Frame(next_i);
                             let next_time = light.light.movement.get_time(next);
 
-                            let min_lerp = r32(0.25);
+                            let min_lerp = beat_division(divisor);
                             let max_delta =
                                 next_time.map_or(r32(100.0), |time| time - min_lerp - beat);
 
@@ -431,7 +487,7 @@ impl EditorState {
         }
 
         // Scroll current time
-        level_editor.scroll_time(delta);
+        level_editor.scroll_time(delta, divisor);
     }
 
     fn start_drag(&mut self, target: DragTarget) {
@@ -457,14 +513,22 @@ impl EditorState {
         };
 
         if let Some(drag) = self.drag.take() {
-            if let DragTarget::Light { double, .. } = drag.target {
-                if double
-                    && drag.from_world == self.editor.cursor_world_pos_snapped
-                    && level_editor.real_time - drag.from_real_time < r32(0.5)
-                {
-                    // See waypoints
-                    level_editor.view_waypoints();
+            match drag.target {
+                DragTarget::Lights { double, .. } => {
+                    if double
+                        && drag.from_world == self.editor.cursor_world_pos_snapped
+                        && level_editor.real_time - drag.from_real_time < r32(0.5)
+                    {
+                        // See waypoints
+                        level_editor.view_waypoints();
+                    }
                 }
+                DragTarget::Area { add } => {
+                    if let Some(area) = level_editor.selection_area.take() {
+                        level_editor.select_area(area, add);
+                    }
+                }
+                DragTarget::Waypoint { .. } => {}
             }
 
             level_editor.save_state(default());
@@ -478,16 +542,18 @@ impl EditorState {
 
         let Some(drag) = &mut self.drag else { return };
         match drag.target {
-            DragTarget::Light {
-                event,
-                initial_time,
-                initial_translation,
-                ..
-            } => {
-                if let Some(event) = level_editor.level.events.get_mut(event) {
+            DragTarget::Lights { ref events, .. } => {
+                let time_delta = snap_beat(
+                    level_editor.current_beat - drag.from_beat,
+                    self.editor.beat_divisor,
+                );
+                for &(event, initial_time, initial_translation) in events {
+                    let Some(event) = level_editor.level.events.get_mut(event) else {
+                        continue;
+                    };
                     if let Event::Light(light) = &mut event.event {
                         // Move temporaly
-                        event.beat = level_editor.current_beat - drag.from_beat + initial_time;
+                        event.beat = initial_time + time_delta;
 
                         // Move spatially
                         let movement = &mut light.light.movement;
@@ -497,10 +563,19 @@ impl EditorState {
                         movement.initial.translation += delta;
                         for frame in &mut movement.key_frames {
                             frame.transform.translation += delta;
+                            for control in &mut frame.curve {
+                                *control += delta;
+                            }
                         }
                     }
                 }
             }
+            DragTarget::Area { .. } => {
+                level_editor.selection_area = Some(Aabb2::from_corners(
+                    drag.from_world,
+                    self.editor.cursor_world_pos,
+                ));
+            }
             DragTarget::Waypoint {
                 event,
                 waypoint,
@@ -524,32 +599,52 @@ impl EditorState {
         let Some(level_editor) = &mut self.editor.level_edit else {
             return;
         };
+        let shift = self
+            .context
+            .geng
+            .window()
+            .is_key_pressed(geng::Key::ShiftLeft);
 
         match &mut level_editor.state {
             State::Idle => {
                 // Select a light
                 if let Some(event) = level_editor.level_state.hovered_event() {
                     let light_id = LightId { event };
-                    let double = level_editor.selected_light == Some(light_id);
-                    level_editor.selected_light = Some(light_id);
-                    if let Some(e) = level_editor.level.events.get(event) {
-                        if let Event::Light(light) = &e.event {
-                            let target = DragTarget::Light {
-                                double,
-                                event,
-                                initial_time: e.beat,
-                                initial_translation: light.light.movement.initial.translation,
-                            };
-                            self.start_drag(target);
+                    if shift {
+                        level_editor.selection.toggle(light_id);
+                    } else {
+                        let double = level_editor.selection.single() == Some(light_id);
+                        if !level_editor.selection.contains(light_id) {
+                            level_editor.selection.select(light_id);
                         }
+                        let events = level_editor
+                            .selection
+                            .lights()
+                            .iter()
+                            .filter_map(|id| {
+                                let event = level_editor.level.events.get(id.event)?;
+                                match &event.event {
+                                    Event::Light(light) => Some((
+                                        id.event,
+                                        event.beat,
+                                        light.light.movement.initial.translation,
+                                    )),
+                                    Event::PaletteSwap => None,
+                                }
+                            })
+                            .collect();
+                        self.start_drag(DragTarget::Lights { double, events });
                     }
                 } else {
-                    // Deselect
-                    level_editor.selected_light = None;
+                    if !shift {
+                        // Deselect
+                        level_editor.selection.clear();
+                    }
+                    self.start_drag(DragTarget::Area { add: shift });
                 }
             }
             State::Place { shape, danger } => {
-                let shape = *shape;
+                let shape = shape.clone();
                 let danger = *danger;
 
                 // Fade in
@@ -557,7 +652,7 @@ impl EditorState {
                     initial: Transform {
                         translation: self.editor.cursor_world_pos_snapped,
                         rotation: level_editor.place_rotation.normalized_2pi(),
-                        scale: level_editor.place_scale,
+                        scale: vec2::splat(level_editor.place_scale),
                     },
                     ..default()
                 };
@@ -575,6 +670,19 @@ impl EditorState {
                     redo_stack: Vec::new(),
                 };
             }
+            State::PlacePattern { pattern } => {
+                let pattern = pattern.clone();
+                level_editor.paste(
+                    &pattern.events,
+                    Placement {
+                        position: self.editor.cursor_world_pos_snapped,
+                        rotation: level_editor.place_rotation,
+                        scale: level_editor.place_scale,
+                    },
+                );
+                // Keep stamping until cancelled
+                level_editor.state = State::PlacePattern { pattern };
+            }
             State::Movement {
                 start_beat,
                 light,
@@ -582,14 +690,17 @@ impl EditorState {
             } => {
                 // TODO: check negative time
                 let last_beat = *start_beat + light.light.movement.movement_duration();
-                light.light.movement.key_frames.push_back(MoveFrame {
-                    lerp_time: level_editor.current_beat - last_beat, // in beats
-                    transform: Transform {
+                let divisor = self.editor.beat_divisor;
+                let lerp_time = snap_beat(level_editor.current_beat - last_beat, divisor)
+                    .max(beat_division(divisor));
+                light.light.movement.key_frames.push_back(MoveFrame::new(
+                    lerp_time, // in beats
+                    Transform {
                         translation: self.editor.cursor_world_pos_snapped,
                         rotation: level_editor.place_rotation,
-                        scale: level_editor.place_scale,
+                        scale: vec2::splat(level_editor.place_scale),
                     },
-                });
+                ));
                 redo_stack.clear();
             }
             State::Playing { .. } => {}
@@ -637,7 +748,7 @@ impl EditorState {
                                     let mut transform = Transform {
                                         translation: self.editor.cursor_world_pos_snapped,
                                         rotation: level_editor.place_rotation,
-                                        scale: level_editor.place_scale,
+                                        scale: vec2::splat(level_editor.place_scale),
                                     };
                                     match i.checked_sub(1) {
                                         None => {
@@ -650,10 +761,9 @@ impl EditorState {
                                             let time = level_editor.current_beat
                                                 - light.light.movement.fade_in
                                                 - light.telegraph.precede_time; // Extra time for fade in and telegraph
-                                            light.light.movement.key_frames.push_front(MoveFrame {
-                                                lerp_time: event.beat - time,
-                                                transform,
-                                            });
+                                            light.light.movement.key_frames.push_front(
+                                                MoveFrame::new(event.beat - time, transform),
+                                            );
                                             event.beat = time;
                                         }
                                         Some(i) => {
@@ -669,10 +779,7 @@ impl EditorState {
 
                                                 light.light.movement.key_frames.insert(
                                                     i,
-                                                    MoveFrame {
-                                                        lerp_time,
-                                                        transform,
-                                                    },
+                                                    MoveFrame::new(lerp_time, transform),
                                                 );
 
                                                 if let Some(next) =
@@ -724,6 +831,8 @@ impl EditorState {
             })
         {
             frame.rotation += delta;
+        } else if let State::Idle = level_editor.state {
+            level_editor.rotate_selection(delta);
         }
     }
 }
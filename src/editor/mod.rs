@@ -1,10 +1,12 @@
 mod config;
 mod handle_event;
+mod selection;
 mod state;
 mod ui;
 
 pub use self::{
     config::*,
+    selection::*,
     state::{State, *},
     ui::*,
 };
@@ -12,6 +14,7 @@ pub use self::{
 use crate::{
     game::{PlayGroup, PlayLevel},
     leaderboard::Leaderboard,
+    local::CachedMusic,
     prelude::*,
     render::editor::{EditorRender, RenderOptions},
     ui::{widget::ConfirmPopup, UiContext},
@@ -51,14 +54,18 @@ pub struct Drag {
 
 #[derive(Debug)]
 pub enum DragTarget {
-    /// Move the whole light event through time and space.
-    Light {
+    /// Move the selected light events through time and space.
+    Lights {
         /// Whether it was the second click on the light.
         /// If the drag is short, waypoints will be toggled.
         double: bool,
-        event: usize,
-        initial_time: Time,
-        initial_translation: vec2<Coord>,
+        /// Index, initial beat and initial translation of each dragged event.
+        events: Vec<(usize, Time, vec2<Coord>)>,
+    },
+    /// Select the lights inside the area.
+    Area {
+        /// Whether to add to the existing selection.
+        add: bool,
     },
     Waypoint {
         event: usize,
@@ -96,7 +103,9 @@ pub struct LevelEditor {
     pub level_state: EditorLevelState,
     pub current_beat: Time,
     pub real_time: Time,
-    pub selected_light: Option<LightId>,
+    pub selection: Selection,
+    /// Area currently being selected.
+    pub selection_area: Option<Aabb2<Coord>>,
 
     /// State that will be saved in the undo stack.
     /// (Not every operation gets saved)
@@ -137,6 +146,10 @@ pub struct Editor {
     pub view_zoom: f32,
     pub music_timer: Time,
     pub snap_to_grid: bool,
+    /// Number of subdivisions of a beat that the time snaps to.
+    pub beat_divisor: u32,
+    /// Events copied from the level.
+    pub clipboard: Clipboard,
     /// Whether to visualize the lights' movement for the current beat.
     pub visualize_beat: bool,
     /// Whether to only render the selected light.
@@ -165,7 +178,8 @@ impl LevelEditor {
             level_state: EditorLevelState::default(),
             current_beat: Time::ZERO,
             real_time: Time::ZERO,
-            selected_light: None,
+            selection: Selection::default(),
+            selection_area: None,
             place_rotation: Angle::ZERO,
             place_scale: Coord::ONE,
             state: State::Idle,
@@ -186,7 +200,7 @@ impl LevelEditor {
     }
 
     pub fn select_waypoint(&mut self, light_id: LightId, waypoint_id: WaypointId) {
-        self.selected_light = Some(light_id);
+        self.selection.select(light_id);
         if let Some(waypoints) = &mut self.level_state.waypoints {
             waypoints.selected = Some(waypoint_id);
         } else {
@@ -203,23 +217,6 @@ impl LevelEditor {
         }
     }
 
-    fn delete_light_selected(&mut self) -> bool {
-        let Some(id) = self.selected_light else {
-            return false;
-        };
-        self.delete_light(id);
-        true
-    }
-
-    fn delete_light(&mut self, id: LightId) {
-        if id.event >= self.level.events.len() {
-            return;
-        }
-        self.level.events.swap_remove(id.event);
-        self.selected_light = None;
-        self.save_state(default());
-    }
-
     fn delete_waypoint_selected(&mut self) -> bool {
         let Some(waypoints) = &self.level_state.waypoints else {
             return false;
@@ -274,6 +271,40 @@ impl LevelEditor {
         }
         self.save_state(default());
     }
+
+    /// Replace the music info used by the editor.
+    /// The change is written to the cache when the level is saved.
+    pub fn update_music(&mut self, meta: MusicInfo) {
+        let music = Rc::new(CachedMusic {
+            meta,
+            music: Rc::clone(&self.static_level.group.music.music),
+        });
+        self.static_level.group.music = Rc::clone(&music);
+        self.model.level.group.music = music;
+    }
+
+    /// Start a new tempo section at the current beat.
+    pub fn place_tempo_marker(&mut self, bpm: R32) {
+        if self.current_beat <= Time::ZERO {
+            return;
+        }
+        let mut meta = self.static_level.group.music.meta.clone();
+        let beats_per_bar = meta.beats_per_bar_at(self.current_beat);
+        meta.tempo.insert(TempoSection {
+            beat: self.current_beat,
+            bpm,
+            beats_per_bar,
+        });
+        self.update_music(meta);
+    }
+
+    /// Remove the tempo section starting at the current beat.
+    pub fn remove_tempo_marker(&mut self) {
+        let mut meta = self.static_level.group.music.meta.clone();
+        if meta.tempo.remove(self.current_beat).is_some() {
+            self.update_music(meta);
+        }
+    }
 }
 
 impl EditorState {
@@ -306,6 +337,8 @@ impl EditorState {
                 visualize_beat: true,
                 show_only_selected: false,
                 snap_to_grid: true,
+                beat_divisor: config.beat_divisor,
+                clipboard: Clipboard::default(),
                 music_timer: Time::ZERO,
 
                 group,
@@ -370,20 +403,22 @@ impl EditorState {
                     &level_editor.static_level.group.music,
                     level_editor.current_beat,
                 );
-                self.editor.music_timer = level_editor.static_level.group.music.meta.beat_time()
-                    * self.editor.config.playback_duration;
+                let music = &level_editor.static_level.group.music.meta;
+                self.editor.music_timer = music
+                    .beat_to_time(level_editor.current_beat + self.editor.config.playback_duration)
+                    - music.beat_to_time(level_editor.current_beat);
             }
             level_editor.was_scrolling_time = false;
         }
 
         level_editor.scrolling_time = false;
 
+        let music = &level_editor.static_level.group.music.meta;
         if let State::Playing { .. } = level_editor.state {
-            level_editor.current_beat =
-                level_editor.real_time / level_editor.static_level.group.music.meta.beat_time();
+            level_editor.current_beat = music.time_to_beat(level_editor.real_time);
         } else if let Some(replay) = &mut level_editor.dynamic_segment {
-            replay.current_beat +=
-                replay.speed * delta_time / level_editor.static_level.group.music.meta.beat_time();
+            replay.current_beat = music
+                .time_to_beat(music.beat_to_time(replay.current_beat) + replay.speed * delta_time);
             if replay.current_beat > replay.end_beat {
                 replay.current_beat = replay.start_beat;
             }
@@ -418,8 +453,12 @@ impl EditorState {
         };
 
         let level = crate::game::PlayLevel {
-            start_time: level_editor.current_beat
-                * level_editor.static_level.group.music.meta.beat_time(), // TODO: nonlinear time
+            start_time: level_editor
+                .static_level
+                .group
+                .music
+                .meta
+                .beat_to_time(level_editor.current_beat),
             level: Rc::new(LevelFull {
                 meta: level_editor.static_level.level.meta.clone(),
                 data: level_editor.level.clone(),
@@ -529,6 +568,7 @@ impl Editor {
                 name: "New Diff".into(),
                 authors: Vec::new(),
                 hash: String::new(),
+                difficulty: None,
             },
             data: Level::new(),
         }));
@@ -600,6 +640,7 @@ impl Editor {
                 level: level.clone(),
                 config: LevelConfig::default(),
                 start_time: Time::ZERO,
+                practice: None,
             };
             let model = Model::empty(
                 self.context.clone(),
@@ -626,6 +667,14 @@ impl Editor {
             return;
         };
 
+        let meta = &level_editor.static_level.group.music.meta;
+        if *meta != self.group.music.meta {
+            match self.context.local.update_music(meta.clone()) {
+                Some(music) => self.group.music = music,
+                None => log::error!("Failed to update the music cache"),
+            }
+        }
+
         if let Some((group, level)) = self.context.local.update_level(
             level_editor.static_level.group.group_index,
             level_editor.static_level.level_index,
@@ -654,7 +703,8 @@ impl Editor {
             };
             let level_changed =
                 level_editor.level != cached.data || *level_editor.name != *cached.meta.name;
-            if level_changed {
+            let music_changed = level_editor.static_level.group.music.meta != self.group.music.meta;
+            if level_changed || music_changed {
                 return true;
             }
         }
@@ -693,7 +743,7 @@ impl LevelEditor {
                     redo_stack.push(frame);
                 }
             }
-            State::Place { .. } => {}
+            State::Place { .. } | State::PlacePattern { .. } => {}
             State::Idle | State::Waypoints { .. } => {
                 if let Some(mut level) = self.undo_stack.pop() {
                     std::mem::swap(&mut level, &mut self.level);
@@ -715,7 +765,7 @@ impl LevelEditor {
                     light.light.movement.key_frames.push_back(frame);
                 }
             }
-            State::Place { .. } => {}
+            State::Place { .. } | State::PlacePattern { .. } => {}
             State::Idle | State::Waypoints { .. } => {
                 if let Some(mut level) = self.redo_stack.pop() {
                     std::mem::swap(&mut level, &mut self.level);
@@ -808,7 +858,7 @@ impl LevelEditor {
     fn view_waypoints(&mut self) {
         match self.state {
             State::Idle => {
-                if let Some(selected) = self.selected_light {
+                if let Some(selected) = self.selection.single() {
                     self.state = State::Waypoints {
                         event: selected.event,
                         state: WaypointsState::Idle,
@@ -822,14 +872,14 @@ impl LevelEditor {
         }
     }
 
-    fn scroll_time(&mut self, delta: Time) {
+    pub fn scroll_time(&mut self, delta: Time, beat_divisor: u32) {
         let margin = r32(1000.0);
         let min = Time::ZERO;
         let max = margin + self.level.last_beat();
         let target = (self.current_beat + delta).clamp(min, max);
 
-        // Align with quarter beats
-        self.current_beat = ((target.as_f32() * 4.0).round() / 4.0).as_r32();
+        // Align with the beat subdivisions
+        self.current_beat = snap_beat(target, beat_divisor);
 
         self.scrolling_time = true;
     }
@@ -850,10 +900,8 @@ impl LevelEditor {
                 if let Some(replay) = &self.dynamic_segment {
                     Some(replay.current_beat)
                 } else {
-                    Some(
-                        time + (self.real_time / self.static_level.group.music.meta.beat_time())
-                            .fract(),
-                    )
+                    let beat_time = self.static_level.group.music.meta.beat_time_at(time);
+                    Some(time + (self.real_time / beat_time).fract())
                 }
             } else {
                 None
@@ -861,15 +909,15 @@ impl LevelEditor {
             (Some(time), dynamic)
         };
 
-        let selected_level = show_only_selected
-            .then(|| {
-                self.selected_light
-                    .and_then(|id| self.level.events.get(id.event))
-                    .map(|selected| Level {
-                        events: vec![selected.clone()],
-                    })
-            })
-            .flatten();
+        let selected_level = (show_only_selected && !self.selection.is_empty()).then(|| Level {
+            events: self
+                .selection
+                .lights()
+                .iter()
+                .filter_map(|id| self.level.events.get(id.event))
+                .cloned()
+                .collect(),
+        });
         let level = selected_level.as_ref().unwrap_or(&self.level);
 
         let static_level =
@@ -894,7 +942,7 @@ impl LevelEditor {
                 if let Event::Light(light_event) = &event.event {
                     let event_time = event.beat + light_event.telegraph.precede_time;
                     // If some waypoints overlap, render the temporaly closest one
-                    let base_collider = Collider::new(vec2::ZERO, light_event.light.shape);
+                    let base_collider = Collider::new(vec2::ZERO, light_event.light.shape.clone());
                     let mut points: Vec<_> = light_event
                         .light
                         .movement
@@ -949,7 +997,7 @@ impl LevelEditor {
                                     collider: base_collider.transformed(Transform {
                                         translation: cursor_world_pos_snapped,
                                         rotation: self.place_rotation,
-                                        scale: self.place_scale,
+                                        scale: vec2::splat(self.place_scale),
                                     }),
                                 },
                                 new_time,
@@ -0,0 +1,315 @@
+use super::*;
+
+/// Lights selected in the editor.
+#[derive(Debug, Clone, Default)]
+pub struct Selection {
+    lights: Vec<LightId>,
+}
+
+impl Selection {
+    pub fn is_empty(&self) -> bool {
+        self.lights.is_empty()
+    }
+
+    pub fn lights(&self) -> &[LightId] {
+        &self.lights
+    }
+
+    pub fn contains(&self, id: LightId) -> bool {
+        self.lights.contains(&id)
+    }
+
+    /// Returns the selected light if it is the only one selected.
+    pub fn single(&self) -> Option<LightId> {
+        match self.lights.as_slice() {
+            [id] => Some(*id),
+            _ => None,
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.lights.clear();
+    }
+
+    /// Select only the given light.
+    pub fn select(&mut self, id: LightId) {
+        self.lights = vec![id];
+    }
+
+    /// Add the light to the selection or remove it if it is already selected.
+    pub fn toggle(&mut self, id: LightId) {
+        if let Some(i) = self.lights.iter().position(|&light| light == id) {
+            self.lights.remove(i);
+        } else {
+            self.lights.push(id);
+        }
+    }
+
+    pub fn extend(&mut self, ids: impl IntoIterator<Item = LightId>) {
+        for id in ids {
+            if !self.contains(id) {
+                self.lights.push(id);
+            }
+        }
+    }
+}
+
+/// Copied events, positioned relative to the place they were copied from.
+#[derive(Debug, Clone, Default)]
+pub struct Clipboard {
+    /// Beats are relative to the earliest light in the selection,
+    /// and positions are relative to the center of the selection.
+    events: Vec<TimedEvent>,
+}
+
+impl Clipboard {
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+
+    pub fn events(&self) -> &[TimedEvent] {
+        &self.events
+    }
+
+    /// Turn the copied events into a named pattern.
+    pub fn into_pattern(self, name: impl Into<String>) -> Pattern {
+        Pattern {
+            name: name.into(),
+            events: self.events,
+        }
+    }
+}
+
+/// Where and how pasted events are put into the level.
+#[derive(Debug, Clone, Copy)]
+pub struct Placement {
+    pub position: vec2<Coord>,
+    pub rotation: Angle<Coord>,
+    pub scale: Coord,
+}
+
+impl Placement {
+    /// Place the events at the position without rotating or scaling them.
+    pub fn at(position: vec2<Coord>) -> Self {
+        Self {
+            position,
+            rotation: Angle::ZERO,
+            scale: Coord::ONE,
+        }
+    }
+
+    pub fn apply_point(&self, point: vec2<Coord>) -> vec2<Coord> {
+        self.position + (point * self.scale).rotate(self.rotation)
+    }
+
+    pub fn apply(&self, transform: &Transform) -> Transform {
+        Transform {
+            translation: self.apply_point(transform.translation),
+            rotation: (transform.rotation + self.rotation).normalized_2pi(),
+            scale: transform.scale * self.scale,
+        }
+    }
+
+    fn apply_movement(&self, movement: &mut Movement) {
+        movement.initial = self.apply(&movement.initial);
+        for frame in &mut movement.key_frames {
+            frame.transform = self.apply(&frame.transform);
+            for control in &mut frame.curve {
+                *control = self.apply_point(*control);
+            }
+        }
+    }
+}
+
+/// Beat at which the light appears on the timeline.
+fn light_beat(event: &TimedEvent) -> Time {
+    match &event.event {
+        Event::Light(light) => event.beat + light.telegraph.precede_time,
+        Event::PaletteSwap => event.beat,
+    }
+}
+
+/// Apply the mapping to every waypoint of the movement, including the curve control points.
+fn map_movement(
+    movement: &mut Movement,
+    point: &impl Fn(vec2<Coord>) -> vec2<Coord>,
+    rotation: &impl Fn(Angle<Coord>) -> Angle<Coord>,
+) {
+    let mut map_transform = |transform: &mut Transform| {
+        transform.translation = point(transform.translation);
+        transform.rotation = rotation(transform.rotation).normalized_2pi();
+    };
+    map_transform(&mut movement.initial);
+    for frame in &mut movement.key_frames {
+        map_transform(&mut frame.transform);
+        for control in &mut frame.curve {
+            *control = point(*control);
+        }
+    }
+}
+
+impl LevelEditor {
+    fn selected_events(&self) -> impl Iterator<Item = &TimedEvent> {
+        self.selection
+            .lights()
+            .iter()
+            .filter_map(|id| self.level.events.get(id.event))
+    }
+
+    /// Center of the bounding box around the initial positions of the selected lights.
+    fn selection_center(&self) -> Option<vec2<Coord>> {
+        let positions = self
+            .selected_events()
+            .filter_map(|event| match &event.event {
+                Event::Light(light) => Some(light.light.movement.initial.translation),
+                Event::PaletteSwap => None,
+            });
+        Aabb2::points_bounding_box(positions).map(|aabb| aabb.center())
+    }
+
+    /// Apply the mapping to the positions and rotations of all selected lights.
+    fn map_selection(
+        &mut self,
+        point: impl Fn(vec2<Coord>) -> vec2<Coord>,
+        rotation: impl Fn(Angle<Coord>) -> Angle<Coord>,
+    ) {
+        for id in self.selection.lights() {
+            if let Some(event) = self.level.events.get_mut(id.event) {
+                if let Event::Light(light) = &mut event.event {
+                    map_movement(&mut light.light.movement, &point, &rotation);
+                }
+            }
+        }
+    }
+
+    /// Select all lights visible at the current beat inside the area.
+    pub fn select_area(&mut self, area: Aabb2<Coord>, add: bool) {
+        if !add {
+            self.selection.clear();
+        }
+        if let Some(level) = &self.level_state.static_level {
+            let ids = level
+                .lights
+                .iter()
+                .filter(|light| area.contains(light.collider.position))
+                .filter_map(|light| light.event_id)
+                .map(|event| LightId { event });
+            self.selection.extend(ids);
+        }
+    }
+
+    /// Rotate the selected lights around the center of the selection.
+    pub fn rotate_selection(&mut self, delta: Angle<Coord>) {
+        let Some(center) = self.selection_center() else {
+            return;
+        };
+        self.map_selection(
+            |pos| center + (pos - center).rotate(delta),
+            |rotation| rotation + delta,
+        );
+        self.save_state(default());
+    }
+
+    /// Mirror the selected lights relative to the center of the selection.
+    pub fn flip_selection(&mut self, horizontal: bool) {
+        let Some(center) = self.selection_center() else {
+            return;
+        };
+        if horizontal {
+            self.map_selection(
+                |pos| vec2(center.x * r32(2.0) - pos.x, pos.y),
+                |rotation| Angle::from_radians(r32(f32::PI) - rotation.as_radians()),
+            );
+        } else {
+            self.map_selection(
+                |pos| vec2(pos.x, center.y * r32(2.0) - pos.y),
+                |rotation| Angle::from_radians(-rotation.as_radians()),
+            );
+        }
+        self.save_state(default());
+    }
+
+    /// Move the selected lights through time.
+    pub fn shift_selection(&mut self, delta: Time) {
+        for id in self.selection.lights() {
+            if let Some(event) = self.level.events.get_mut(id.event) {
+                event.beat += delta;
+            }
+        }
+        self.save_state(default());
+    }
+
+    pub fn delete_selection(&mut self) -> bool {
+        if self.selection.is_empty() {
+            return false;
+        }
+
+        // Remove from the back so that the other indices stay valid
+        let mut events: Vec<usize> = self.selection.lights().iter().map(|id| id.event).collect();
+        events.sort_unstable();
+        events.dedup();
+        for i in events.into_iter().rev() {
+            if i < self.level.events.len() {
+                self.level.events.remove(i);
+            }
+        }
+
+        self.selection.clear();
+        self.level_state.waypoints = None;
+        self.save_state(default());
+        true
+    }
+
+    pub fn copy_selection(&self) -> Option<Clipboard> {
+        let center = self.selection_center()?;
+        let start = self.selected_events().map(light_beat).min()?;
+
+        let events = self
+            .selected_events()
+            .cloned()
+            .map(|mut event| {
+                event.beat -= start;
+                if let Event::Light(light) = &mut event.event {
+                    map_movement(
+                        &mut light.light.movement,
+                        &|pos| pos - center,
+                        &|rotation| rotation,
+                    );
+                }
+                event
+            })
+            .collect();
+        Some(Clipboard { events })
+    }
+
+    pub fn cut_selection(&mut self) -> Option<Clipboard> {
+        let clipboard = self.copy_selection()?;
+        self.delete_selection();
+        Some(clipboard)
+    }
+
+    /// Paste the events starting at the current beat using the placement.
+    /// The pasted events become the new selection.
+    pub fn paste(&mut self, events: &[TimedEvent], placement: Placement) {
+        if events.is_empty() {
+            return;
+        }
+
+        self.selection.clear();
+        for event in events {
+            let mut event = event.clone();
+            event.beat += self.current_beat;
+            if let Event::Light(light) = &mut event.event {
+                placement.apply_movement(&mut light.light.movement);
+            }
+            self.selection.extend([LightId {
+                event: self.level.events.len(),
+            }]);
+            self.level.events.push(event);
+        }
+
+        self.state = State::Idle;
+        self.level_state.waypoints = None;
+        self.save_state(default());
+    }
+}
@@ -8,6 +8,10 @@ pub enum State {
         shape: Shape,
         danger: bool,
     },
+    /// Stamp a saved pattern at the cursor.
+    PlacePattern {
+        pattern: Rc<Pattern>,
+    },
     /// Specify a movement path for the light.
     Movement {
         /// Time when the movement starts (after fade in and telegraph).
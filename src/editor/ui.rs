@@ -1,7 +1,9 @@
 mod config;
 mod edit;
+mod lint;
+mod patterns;
 
-pub use self::{config::*, edit::*};
+pub use self::{config::*, edit::*, lint::*, patterns::*};
 
 use super::*;
 
@@ -9,10 +11,17 @@ use crate::ui::{layout::AreaOps, widget::*};
 
 const HELP: &str = "
 Scroll / Arrow keys - move through time
-Hold Shift / Alt - scroll slower / faster
+Hold Shift / Alt - scroll by beat subdivision / faster
+[ / ] - change beat subdivision
 Space - play music
 Q / E - rotate
 Ctrl+Scroll - scale lights
+Shift+Click / Drag - select multiple lights
+Ctrl+C / Ctrl+X / Ctrl+V - copy / cut / paste
+Ctrl+Arrow keys - move selection through time
+M / Shift+M - flip selection
+Patterns tab - save the selection and pick patterns to stamp
+Problems tab - click a problem to jump to it
 F1 - Hide UI
 ";
 
@@ -27,6 +36,8 @@ pub struct EditorUI {
     pub help: IconWidget,
     pub tab_edit: ButtonWidget,
     pub tab_config: ButtonWidget,
+    pub tab_patterns: ButtonWidget,
+    pub tab_lint: ButtonWidget,
 
     pub unsaved: TextWidget,
     pub save: ButtonWidget,
@@ -34,6 +45,8 @@ pub struct EditorUI {
     pub help_text: TextWidget,
     pub edit: EditorEditWidget,
     pub config: EditorConfigWidget,
+    pub patterns: EditorPatternsWidget,
+    pub lint: EditorLintWidget,
 }
 
 impl EditorUI {
@@ -49,6 +62,8 @@ impl EditorUI {
             help: IconWidget::new(&assets.sprites.help),
             tab_edit: ButtonWidget::new("Edit"),
             tab_config: ButtonWidget::new("Config"),
+            tab_patterns: ButtonWidget::new("Patterns"),
+            tab_lint: ButtonWidget::new("Problems"),
 
             unsaved: TextWidget::new("Save to apply changes").aligned(vec2(1.0, 0.5)),
             save: ButtonWidget::new("Save"),
@@ -59,6 +74,16 @@ impl EditorUI {
                 w.hide();
                 w
             },
+            patterns: {
+                let mut w = EditorPatternsWidget::new(assets);
+                w.hide();
+                w
+            },
+            lint: {
+                let mut w = EditorLintWidget::new();
+                w.hide();
+                w
+            },
             edit: EditorEditWidget::new(context),
         }
     }
@@ -153,7 +178,12 @@ impl EditorUI {
             self.help_text.hide();
         }
 
-        let tabs = [&mut self.tab_edit, &mut self.tab_config];
+        let tabs = [
+            &mut self.tab_edit,
+            &mut self.tab_config,
+            &mut self.tab_patterns,
+            &mut self.tab_lint,
+        ];
         let tab = Aabb2::point(top_bar.bottom_left())
             .extend_positive(vec2(layout_size * 5.0, top_bar.height()));
         let tabs_pos = tab.stack(vec2(tab.width() + layout_size, 0.0), tabs.len());
@@ -164,9 +194,23 @@ impl EditorUI {
         if self.tab_edit.text.state.clicked {
             self.edit.show();
             self.config.hide();
+            self.patterns.hide();
+            self.lint.hide();
         } else if self.tab_config.text.state.clicked {
             self.edit.hide();
             self.config.show();
+            self.patterns.hide();
+            self.lint.hide();
+        } else if self.tab_patterns.text.state.clicked {
+            self.edit.hide();
+            self.config.hide();
+            self.patterns.show();
+            self.lint.hide();
+        } else if self.tab_lint.text.state.clicked {
+            self.edit.hide();
+            self.config.hide();
+            self.patterns.hide();
+            self.lint.show();
         }
 
         let save = top_bar.cut_right(layout_size * 5.0);
@@ -191,6 +235,24 @@ impl EditorUI {
             self.config
                 .update(main.extend_up(-3.0 * layout_size), context, editor);
         }
+        if self.patterns.state.visible {
+            self.patterns
+                .update(main.extend_up(-3.0 * layout_size), context, editor);
+            if self.patterns.picked() {
+                // Go place the pattern
+                self.patterns.hide();
+                self.edit.show();
+            }
+        }
+        if self.lint.state.visible {
+            self.lint
+                .update(main.extend_up(-3.0 * layout_size), context, editor);
+            if self.lint.picked() {
+                // Go look at the problem
+                self.lint.hide();
+                self.edit.show();
+            }
+        }
 
         context.can_focus
     }
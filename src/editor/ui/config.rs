@@ -20,8 +20,8 @@ pub struct EditorConfigWidget {
     pub timeline: TextWidget,
     /// Normal time scroll.
     pub scroll_by: ValueWidget<Time>, // TODO: 1/4 instead of 0.25
-    /// Slow time scroll.
-    pub shift_scroll: ValueWidget<Time>,
+    /// Subdivision of a beat, also used for slow time scroll.
+    pub beat_snap: ButtonWidget,
     /// Fast time scroll.
     pub alt_scroll: ValueWidget<Time>,
     // pub snap_to: CheckboxWidget,
@@ -47,12 +47,7 @@ impl EditorConfigWidget {
 
             timeline: TextWidget::new("Timeline"),
             scroll_by: ValueWidget::new("Scroll by", r32(1.0), r32(0.25)..=r32(4.0), r32(0.25)),
-            shift_scroll: ValueWidget::new(
-                "Shift scroll",
-                r32(0.25),
-                r32(0.125)..=r32(1.0),
-                r32(0.125),
-            ),
+            beat_snap: ButtonWidget::new("Snap"),
             alt_scroll: ValueWidget::new("Alt scroll", r32(10.0), r32(1.0)..=r32(20.0), r32(0.5)),
         }
     }
@@ -220,9 +215,18 @@ impl StatefulWidget for EditorConfigWidget {
         self.scroll_by
             .update(scroll_by, context, &mut state.config.scroll_normal);
 
-        let shift_scroll = bar.cut_top(context.font_size);
-        self.shift_scroll
-            .update(shift_scroll, context, &mut state.config.scroll_slow);
+        let beat_snap = bar.cut_top(context.font_size);
+        self.beat_snap.text.text = format!("Snap 1/{}", state.beat_divisor).into();
+        self.beat_snap.update(beat_snap, context);
+        if self.beat_snap.text.state.clicked {
+            // Cycle through all divisors
+            let next = next_beat_divisor(state.beat_divisor, 1);
+            state.beat_divisor = if next == state.beat_divisor {
+                BEAT_DIVISORS[0]
+            } else {
+                next
+            };
+        }
 
         let alt_scroll = bar.cut_top(context.font_size);
         self.alt_scroll
@@ -60,6 +60,15 @@ pub struct EditorEditWidget {
     pub snap_grid: CheckboxWidget,
     pub grid_size: ValueWidget<f32>,
 
+    pub tempo: TextWidget,
+    pub tempo_bpm: ValueWidget<f32>,
+    pub tempo_marker: ButtonWidget,
+
+    pub selection: TextWidget,
+    pub selection_delete: ButtonWidget,
+    pub selection_flip_x: ButtonWidget,
+    pub selection_flip_y: ButtonWidget,
+
     pub light: TextWidget,
     pub light_delete: ButtonWidget,
     pub light_danger: CheckboxWidget,
@@ -71,9 +80,13 @@ pub struct EditorEditWidget {
     pub current_waypoint: TextWidget,
     pub next_waypoint: IconButtonWidget,
     pub waypoint_delete: ButtonWidget,
-    pub waypoint_scale: ValueWidget<f32>,
+    pub waypoint_scale_x: ValueWidget<f32>,
+    pub waypoint_scale_y: ValueWidget<f32>,
     /// Angle in degrees.
     pub waypoint_angle: ValueWidget<f32>,
+    pub waypoint_easing: ButtonWidget,
+    /// Bend of the curve relative to the distance from the previous waypoint.
+    pub waypoint_curve: ValueWidget<f32>,
 
     pub current_beat: TextWidget,
     pub timeline: TimelineWidget,
@@ -104,6 +117,15 @@ impl EditorEditWidget {
             snap_grid: CheckboxWidget::new("Grid snap"),
             grid_size: ValueWidget::new("Grid size", 16.0, 2.0..=32.0, 1.0),
 
+            tempo: TextWidget::new("Tempo"),
+            tempo_bpm: ValueWidget::new("BPM", 120.0, 30.0..=300.0, 1.0),
+            tempo_marker: ButtonWidget::new("Add marker"),
+
+            selection: TextWidget::new("Selection"),
+            selection_delete: ButtonWidget::new("delete"),
+            selection_flip_x: ButtonWidget::new("Flip X"),
+            selection_flip_y: ButtonWidget::new("Flip Y"),
+
             light: TextWidget::new("Light"),
             light_delete: ButtonWidget::new("delete"),
             light_danger: CheckboxWidget::new("Danger"),
@@ -120,8 +142,11 @@ impl EditorEditWidget {
             current_waypoint: TextWidget::new("0"),
             next_waypoint: IconButtonWidget::new_normal(&assets.sprites.arrow_right),
             waypoint_delete: ButtonWidget::new("delete"),
-            waypoint_scale: ValueWidget::new("Scale", 1.0, 0.25..=2.0, 0.25),
+            waypoint_scale_x: ValueWidget::new("Scale X", 1.0, 0.25..=2.0, 0.25),
+            waypoint_scale_y: ValueWidget::new("Scale Y", 1.0, 0.25..=2.0, 0.25),
             waypoint_angle: ValueWidget::new("Angle", 0.0, 0.0..=360.0, 15.0).wrapping(),
+            waypoint_easing: ButtonWidget::new("Smooth"),
+            waypoint_curve: ValueWidget::new("Curve", 0.0, -1.0..=1.0, 0.1),
 
             current_beat: TextWidget::default().aligned(vec2(0.5, 0.0)),
             timeline: TimelineWidget::new(context.clone()),
@@ -305,6 +330,56 @@ impl StatefulWidget for EditorEditWidget {
             editor.grid_size = r32(10.0 / value);
             context.update_focus(self.grid_size.state.hovered);
 
+            bar.cut_top(layout_size * 1.5);
+            left_bar = bar;
+        }
+
+        {
+            // Tempo
+            let mut bar = left_bar;
+
+            let tempo = bar.cut_top(title_size);
+            update!(self.tempo, tempo);
+            self.tempo.options.size = title_size;
+
+            let meta = &level_editor.static_level.group.music.meta;
+            let bpm = meta.bpm_at(level_editor.current_beat).as_f32();
+            let on_marker = meta
+                .section_at(level_editor.current_beat)
+                .map_or(false, |section| section.beat == level_editor.current_beat);
+
+            let bpm_pos = bar.cut_top(button_height);
+            bar.cut_top(spacing);
+            let mut value = bpm;
+            update!(self.tempo_bpm, bpm_pos, &mut value);
+            context.update_focus(self.tempo_bpm.state.hovered);
+            if value != bpm {
+                level_editor.place_tempo_marker(r32(value));
+            }
+
+            // The tempo at the first beat comes from the music itself
+            if level_editor.current_beat > Time::ZERO {
+                self.tempo_marker.show();
+                let marker = bar.cut_top(button_height);
+                bar.cut_top(spacing);
+                update!(self.tempo_marker, marker);
+                self.tempo_marker.text.text = if on_marker {
+                    "Remove marker"
+                } else {
+                    "Add marker"
+                }
+                .into();
+                if self.tempo_marker.text.state.clicked {
+                    if on_marker {
+                        level_editor.remove_tempo_marker();
+                    } else {
+                        level_editor.place_tempo_marker(r32(bpm));
+                    }
+                }
+            } else {
+                self.tempo_marker.hide();
+            }
+
             bar.cut_top(layout_size * 1.5);
             // left_bar = bar;
         }
@@ -312,7 +387,8 @@ impl StatefulWidget for EditorEditWidget {
         {
             // Light
             let selected = level_editor
-                .selected_light
+                .selection
+                .single()
                 .and_then(|i| level_editor.level.events.get_mut(i.event))
                 .filter(|event| matches!(event.event, Event::Light(_)));
 
@@ -386,19 +462,79 @@ impl StatefulWidget for EditorEditWidget {
 
                         // Delayed actions
                         if self.light_delete.text.state.clicked {
-                            level_editor.delete_light_selected();
+                            level_editor.delete_selection();
                         }
                     }
                 }
             }
         }
 
+        if level_editor.selection.lights().len() > 1 {
+            // Multiple lights
+            self.selection.show();
+            self.selection_delete.show();
+            self.selection_flip_x.show();
+            self.selection_flip_y.show();
+
+            let mut bar = right_bar;
+
+            let title = bar.cut_top(title_size);
+            update!(self.selection, title);
+            self.selection.options.size = title_size;
+            self.selection.text =
+                format!("{} lights", level_editor.selection.lights().len()).into();
+
+            let delete = bar.cut_top(button_height);
+            bar.cut_top(spacing);
+            update!(self.selection_delete, delete);
+            if self.selection_delete.text.state.clicked {
+                level_editor.delete_selection();
+            }
+            self.tooltip
+                .update(&self.selection_delete.text.state, "X", context);
+
+            let flip_x = bar.cut_top(button_height);
+            bar.cut_top(spacing);
+            update!(self.selection_flip_x, flip_x);
+            if self.selection_flip_x.text.state.clicked {
+                level_editor.flip_selection(true);
+            }
+            self.tooltip
+                .update(&self.selection_flip_x.text.state, "M", context);
+
+            let flip_y = bar.cut_top(button_height);
+            bar.cut_top(spacing);
+            update!(self.selection_flip_y, flip_y);
+            if self.selection_flip_y.text.state.clicked {
+                level_editor.flip_selection(false);
+            }
+            self.tooltip
+                .update(&self.selection_flip_y.text.state, "S-M", context);
+
+            bar.cut_top(layout_size * 1.5);
+            right_bar = bar;
+        } else {
+            self.selection.hide();
+            self.selection_delete.hide();
+            self.selection_flip_x.hide();
+            self.selection_flip_y.hide();
+        }
+
         let mut waypoint = false;
         if let Some(waypoints) = &mut level_editor.level_state.waypoints {
             if let Some(selected) = waypoints.selected {
                 if let Some(event) = level_editor.level.events.get_mut(waypoints.event) {
                     if let Event::Light(light) = &mut event.event {
                         let frames = light.light.movement.key_frames.len();
+                        let prev_translation = match selected {
+                            WaypointId::Initial => None,
+                            WaypointId::Frame(i) => light
+                                .light
+                                .movement
+                                .timed_positions()
+                                .nth(i)
+                                .map(|(_, transform, _)| transform.translation),
+                        };
                         if let Some(frame) = light.light.movement.get_frame_mut(selected) {
                             // Waypoint
                             waypoint = true;
@@ -406,7 +542,8 @@ impl StatefulWidget for EditorEditWidget {
                             self.next_waypoint.show();
                             self.current_waypoint.show();
                             self.waypoint_delete.show();
-                            self.waypoint_scale.show();
+                            self.waypoint_scale_x.show();
+                            self.waypoint_scale_y.show();
                             self.waypoint_angle.show();
 
                             let mut bar = right_bar;
@@ -453,10 +590,17 @@ impl StatefulWidget for EditorEditWidget {
 
                             let scale = bar.cut_top(button_height);
                             bar.cut_top(spacing);
-                            let mut value = frame.scale.as_f32();
-                            update!(self.waypoint_scale, scale, &mut value);
-                            frame.scale = r32(value);
-                            context.update_focus(self.waypoint_scale.state.hovered);
+                            let mut value = frame.scale.x.as_f32();
+                            update!(self.waypoint_scale_x, scale, &mut value);
+                            frame.scale.x = r32(value);
+                            context.update_focus(self.waypoint_scale_x.state.hovered);
+
+                            let scale = bar.cut_top(button_height);
+                            bar.cut_top(spacing);
+                            let mut value = frame.scale.y.as_f32();
+                            update!(self.waypoint_scale_y, scale, &mut value);
+                            frame.scale.y = r32(value);
+                            context.update_focus(self.waypoint_scale_y.state.hovered);
 
                             let angle = bar.cut_top(button_height);
                             bar.cut_top(spacing);
@@ -467,6 +611,53 @@ impl StatefulWidget for EditorEditWidget {
                             self.tooltip
                                 .update(&self.waypoint_angle.state, "Q/E", context);
 
+                            if let (Some(from), Some(frame)) = (
+                                prev_translation,
+                                light.light.movement.get_move_frame_mut(selected),
+                            ) {
+                                self.waypoint_easing.show();
+                                self.waypoint_curve.show();
+
+                                let easing = bar.cut_top(button_height);
+                                bar.cut_top(spacing);
+                                self.waypoint_easing.update(easing, context);
+                                if self.waypoint_easing.text.state.clicked {
+                                    frame.easing = enum_iterator::next_cycle(&frame.easing)
+                                        .unwrap_or_default();
+                                }
+                                self.waypoint_easing.text.text = frame.easing.name().into();
+
+                                // The curve is controlled by a single point
+                                // offset from the middle of the segment
+                                let from = from.as_f32();
+                                let to = frame.transform.translation.as_f32();
+                                let middle = (from + to) / 2.0;
+                                let normal = (to - from).rotate_90();
+                                let bend = match frame.curve.as_slice() {
+                                    [control] if normal.len_sqr() > 0.0 => {
+                                        vec2::dot(control.as_f32() - middle, normal)
+                                            / normal.len_sqr()
+                                    }
+                                    _ => 0.0,
+                                };
+
+                                let curve = bar.cut_top(button_height);
+                                bar.cut_top(spacing);
+                                let mut value = bend;
+                                update!(self.waypoint_curve, curve, &mut value);
+                                context.update_focus(self.waypoint_curve.state.hovered);
+                                if value != bend {
+                                    frame.curve = if value.abs() < 1e-3 {
+                                        Vec::new()
+                                    } else {
+                                        vec![(middle + normal * value).as_r32()]
+                                    };
+                                }
+                            } else {
+                                self.waypoint_easing.hide();
+                                self.waypoint_curve.hide();
+                            }
+
                             // Delayed actions
                             if self.waypoint_delete.text.state.clicked {
                                 level_editor.delete_waypoint_selected();
@@ -481,22 +672,30 @@ impl StatefulWidget for EditorEditWidget {
             self.next_waypoint.hide();
             self.current_waypoint.hide();
             self.waypoint_delete.hide();
-            self.waypoint_scale.hide();
+            self.waypoint_scale_x.hide();
+            self.waypoint_scale_y.hide();
             self.waypoint_angle.hide();
+            self.waypoint_easing.hide();
+            self.waypoint_curve.hide();
         }
 
         {
             let current_beat = bottom_bar.cut_top(font_size * 1.0);
             update!(self.current_beat, current_beat);
-            self.current_beat.text = format!("Beat: {:.2}", level_editor.current_beat).into();
+            self.current_beat.text = format!(
+                "Beat: {:.2}  Snap: 1/{}",
+                level_editor.current_beat, editor.beat_divisor
+            )
+            .into();
 
             let timeline = bottom_bar.cut_top(font_size * 1.0);
             let was_pressed = self.timeline.state.pressed;
+            self.timeline.beat_divisor = editor.beat_divisor;
             update!(self.timeline, timeline, level_editor);
 
             if self.timeline.clickable.pressed {
                 let time = self.timeline.get_cursor_time();
-                level_editor.scroll_time(time - level_editor.current_beat);
+                level_editor.scroll_time(time - level_editor.current_beat, editor.beat_divisor);
             }
             let replay = level_editor
                 .dynamic_segment
@@ -0,0 +1,118 @@
+use super::*;
+
+use ctl_client::core::lint::{lint_level, Diagnostic};
+
+pub struct EditorLintWidget {
+    pub state: WidgetState,
+
+    pub title: TextWidget,
+    pub no_problems: TextWidget,
+    pub problems: Vec<TextWidget>,
+
+    /// The version of the level that the diagnostics were computed for.
+    linted: Option<Level>,
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+impl Default for EditorLintWidget {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl EditorLintWidget {
+    pub fn new() -> Self {
+        Self {
+            state: WidgetState::new(),
+
+            title: TextWidget::new("Problems"),
+            no_problems: TextWidget::new("No problems found"),
+            problems: Vec::new(),
+
+            linted: None,
+            diagnostics: Vec::new(),
+        }
+    }
+
+    /// Whether a problem has been picked for inspection this frame.
+    pub fn picked(&self) -> bool {
+        self.problems
+            .iter()
+            .any(|problem| problem.state.visible && problem.state.clicked)
+    }
+
+    /// Check the level again if it has changed since the last check.
+    fn relint(&mut self, level_editor: &LevelEditor) {
+        if self.linted.as_ref() == Some(&level_editor.level) {
+            return;
+        }
+
+        self.diagnostics = lint_level(
+            &level_editor.level,
+            &level_editor.model.level.config,
+            &level_editor.static_level.group.music.meta,
+        );
+        self.linted = Some(level_editor.level.clone());
+        self.problems = self
+            .diagnostics
+            .iter()
+            .map(|diagnostic| {
+                TextWidget::new(format!(
+                    "{:.2}: {}",
+                    diagnostic.beat.as_f32(),
+                    diagnostic.kind
+                ))
+                .aligned(vec2(0.0, 0.5))
+            })
+            .collect();
+    }
+}
+
+impl StatefulWidget for EditorLintWidget {
+    type State = Editor;
+
+    fn state_mut(&mut self) -> &mut WidgetState {
+        &mut self.state
+    }
+
+    fn update(&mut self, position: Aabb2<f32>, context: &mut UiContext, state: &mut Self::State) {
+        self.state.update(position, context);
+
+        let Some(level_editor) = &mut state.level_edit else {
+            return;
+        };
+        self.relint(level_editor);
+
+        let width = context.layout_size * 20.0;
+        let mut bar = Aabb2::point(vec2(position.center().x - width / 2.0, position.max.y))
+            .extend_right(width)
+            .extend_down(position.height());
+
+        let title = bar.cut_top(context.font_size);
+        self.title.update(title, context);
+
+        if self.diagnostics.is_empty() {
+            self.no_problems.show();
+            let text = bar.cut_top(context.font_size);
+            self.no_problems.update(text, context);
+        } else {
+            self.no_problems.hide();
+        }
+
+        for (problem, diagnostic) in self.problems.iter_mut().zip(&self.diagnostics) {
+            let row = bar.cut_top(context.font_size);
+            problem.update(row, context);
+            if problem.state.clicked {
+                // Jump to the problem
+                level_editor.scroll_time(
+                    diagnostic.beat - level_editor.current_beat,
+                    state.beat_divisor,
+                );
+                level_editor.selection.clear();
+                if let Some(event) = diagnostic.event {
+                    level_editor.selection.select(LightId { event });
+                }
+            }
+        }
+    }
+}
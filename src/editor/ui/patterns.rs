@@ -0,0 +1,134 @@
+use super::*;
+
+pub struct EditorPatternsWidget {
+    pub assets: Rc<Assets>,
+    pub state: WidgetState,
+
+    pub save_title: TextWidget,
+    pub pattern_name: InputWidget,
+    pub save: ButtonWidget,
+
+    pub library: TextWidget,
+    pub all_pattern_names: Vec<(IconWidget, TextWidget)>,
+}
+
+impl EditorPatternsWidget {
+    pub fn new(assets: &Rc<Assets>) -> Self {
+        Self {
+            assets: assets.clone(),
+            state: WidgetState::new(),
+
+            save_title: TextWidget::new("New Pattern"),
+            pattern_name: InputWidget::new("Name", false),
+            save: ButtonWidget::new("Save selection"),
+
+            library: TextWidget::new("Patterns"),
+            all_pattern_names: Vec::new(),
+        }
+    }
+
+    /// Whether a pattern has been picked for placement this frame.
+    pub fn picked(&self) -> bool {
+        self.all_pattern_names
+            .iter()
+            .any(|(_, name)| name.state.visible && name.state.clicked)
+    }
+}
+
+impl StatefulWidget for EditorPatternsWidget {
+    type State = Editor;
+
+    fn state_mut(&mut self) -> &mut WidgetState {
+        &mut self.state
+    }
+
+    fn update(&mut self, position: Aabb2<f32>, context: &mut UiContext, state: &mut Self::State) {
+        self.state.update(position, context);
+
+        let main = position;
+
+        let width = context.layout_size * 10.0;
+        let spacing = context.layout_size * 5.0;
+
+        let columns = 2;
+        let total_width = columns as f32 * width + (columns - 1) as f32 * spacing;
+        let column = Aabb2::point(vec2(main.center().x - total_width / 2.0, main.max.y))
+            .extend_right(width)
+            .extend_down(main.height());
+
+        let columns = column.stack(vec2(width + spacing, 0.0), columns);
+
+        let mut bar = columns[0];
+        let title = bar.cut_top(context.font_size);
+        self.save_title.update(title, context);
+
+        let name = bar.cut_top(context.font_size);
+        self.pattern_name.update(name, context);
+
+        let save = bar.cut_top(context.font_size);
+        let can_save = !self.pattern_name.raw.trim().is_empty()
+            && state
+                .level_edit
+                .as_ref()
+                .is_some_and(|level_editor| !level_editor.selection.is_empty());
+        if can_save {
+            self.save.show();
+            self.save.update(save, context);
+            if self.save.text.state.clicked {
+                let name = self.pattern_name.raw.trim().to_owned();
+                if let Some(clipboard) = state
+                    .level_edit
+                    .as_ref()
+                    .and_then(|level_editor| level_editor.copy_selection())
+                {
+                    state
+                        .context
+                        .local
+                        .save_pattern(clipboard.into_pattern(name));
+                }
+            }
+        } else {
+            self.save.hide();
+        }
+
+        let mut bar = columns[1];
+        let library = bar.cut_top(context.font_size);
+        self.library.update(library, context);
+
+        let patterns = state.context.local.inner.borrow().patterns.clone();
+        if self.all_pattern_names.len() != patterns.len() {
+            self.all_pattern_names = patterns
+                .iter()
+                .map(|_| {
+                    (
+                        IconWidget::new(&self.assets.sprites.button_close),
+                        TextWidget::new(""),
+                    )
+                })
+                .collect();
+        }
+
+        for (i, ((delete, name), pattern)) in
+            self.all_pattern_names.iter_mut().zip(&patterns).enumerate()
+        {
+            let mut row = bar.cut_top(context.font_size);
+            let icon = row.cut_right(row.height());
+
+            name.text = pattern.name.clone().into();
+            name.update(row, context);
+            if name.state.clicked {
+                if let Some(level_editor) = &mut state.level_edit {
+                    level_editor.selection.clear();
+                    level_editor.state = State::PlacePattern {
+                        pattern: Rc::clone(pattern),
+                    };
+                }
+            }
+
+            delete.update(icon, context);
+            if delete.state.clicked {
+                state.context.local.delete_pattern(i);
+            }
+        }
+    }
+}
@@ -1,7 +1,8 @@
+mod replay;
 mod ui;
 
-pub use self::ui::GameUI;
 use self::ui::UiContext;
+pub use self::{replay::ReplayViewer, ui::GameUI};
 
 use crate::{
     leaderboard::Leaderboard,
@@ -41,6 +42,20 @@ pub struct PlayLevel {
     pub level: Rc<LevelFull>,
     pub config: LevelConfig,
     pub start_time: Time,
+    /// Set when playing in practice mode.
+    pub practice: Option<Practice>,
+}
+
+/// Settings of a practice run.
+/// Practice runs loop a section of the level and are never submitted to the leaderboard.
+#[derive(Debug, Clone)]
+pub struct Practice {
+    /// Beat at which the section starts.
+    pub from_beat: Time,
+    /// Beat at which the section ends and restarts.
+    pub to_beat: Time,
+    /// Playback rate of the music, from 0.5 to 1.0.
+    pub speed: R32,
 }
 
 impl Game {
@@ -124,6 +139,18 @@ impl geng::State for Game {
                 geng::Key::Escape => self.transition = Some(geng::state::Transition::Pop),
                 geng::Key::F11 => self.context.geng.window().toggle_fullscreen(),
                 geng::Key::F1 => self.debug_mode = !self.debug_mode,
+                geng::Key::P => {
+                    if let State::Lost { .. } | State::Finished = self.model.state {
+                        // Watch the replay of the last run
+                        let viewer = ReplayViewer::new(
+                            self.context.clone(),
+                            self.model.options.clone(),
+                            self.model.level.clone(),
+                            self.model.replay.clone(),
+                        );
+                        self.transition = Some(geng::state::Transition::Push(Box::new(viewer)));
+                    }
+                }
                 _ => {}
             },
             geng::Event::Wheel { delta } => {
@@ -168,8 +195,15 @@ impl geng::State for Game {
         if let Some(transition) = self.model.transition.take() {
             match transition {
                 Transition::LoadLeaderboard { submit_score } => {
+                    if !self.model.replay.frames.is_empty() {
+                        self.context.local.save_replay(&self.model.replay);
+                    }
+
                     let player_name = self.model.player.info.name.clone();
-                    let do_submit_score = submit_score && !player_name.trim().is_empty();
+                    // Practice runs never make it to the leaderboard
+                    let do_submit_score = submit_score
+                        && self.model.level.practice.is_none()
+                        && !player_name.trim().is_empty();
 
                     let score = &self.model.score;
                     let raw_score = score.calculated.combined;
@@ -186,6 +220,7 @@ impl geng::State for Game {
                             submit_score,
                             self.model.level.level.meta.id,
                             meta,
+                            Some(self.model.replay.clone()),
                         );
                     } else {
                         self.model.leaderboard.loaded.category = meta.category.clone();
@@ -0,0 +1,104 @@
+use super::*;
+
+/// Plays back a recorded run through the normal game renderer.
+pub struct ReplayViewer {
+    context: Context,
+    transition: Option<geng::state::Transition>,
+    render: GameRender,
+
+    model: Model,
+    replay: Replay,
+    /// Index of the next frame to play.
+    frame: usize,
+    /// Whether all the recorded frames have been played.
+    finished: bool,
+}
+
+impl ReplayViewer {
+    pub fn new(context: Context, options: Options, level: PlayLevel, replay: Replay) -> Self {
+        let level = PlayLevel {
+            config: replay.config.clone(),
+            start_time: replay.start_time,
+            practice: None,
+            ..level
+        };
+
+        let mut model = Model::empty(context.clone(), options, level);
+        model.init(replay.start_time);
+        // The recording starts right as the music starts
+        model.start(model.level.group.music.meta.offset + replay.start_time);
+
+        Self {
+            transition: None,
+            render: GameRender::new(context.clone()),
+            context,
+
+            model,
+            replay,
+            frame: 0,
+            finished: false,
+        }
+    }
+}
+
+impl geng::State for ReplayViewer {
+    fn transition(&mut self) -> Option<geng::state::Transition> {
+        self.transition.take()
+    }
+
+    fn draw(&mut self, framebuffer: &mut ugli::Framebuffer) {
+        ugli::clear(framebuffer, Some(self.model.options.theme.dark), None, None);
+
+        self.render.draw_world(&self.model, false, framebuffer);
+
+        let mut dither_buffer = self.render.dither.start();
+        self.render
+            .util
+            .draw_player(&self.model.player, &self.model.camera, &mut dither_buffer);
+        self.render.dither.finish(
+            self.model.real_time,
+            &self.model.options.theme.transparent(),
+        );
+        geng_utils::texture::DrawTexture::new(self.render.dither.get_buffer())
+            .fit_screen(vec2(0.5, 0.5), framebuffer)
+            .draw(&geng::PixelPerfectCamera, &self.context.geng, framebuffer);
+    }
+
+    fn handle_event(&mut self, event: geng::Event) {
+        if let geng::Event::KeyPress { key } = event {
+            match key {
+                geng::Key::Escape => {
+                    self.context.music.stop();
+                    self.transition = Some(geng::state::Transition::Pop);
+                }
+                geng::Key::F11 => self.context.geng.window().toggle_fullscreen(),
+                _ => {}
+            }
+        }
+    }
+
+    fn update(&mut self, _delta_time: f64) {
+        // Scores are never submitted from a replay
+        self.model.transition.take();
+    }
+
+    fn fixed_update(&mut self, _delta_time: f64) {
+        if self.finished {
+            return;
+        }
+
+        let Some(&target_pos) = self.replay.frames.get(self.frame) else {
+            self.finished = true;
+            self.context.music.stop();
+            return;
+        };
+        self.frame += 1;
+
+        self.model.update(target_pos, self.replay.delta_time);
+        if !matches!(self.model.state, State::Playing) {
+            // Nothing is recorded after the run ends
+            self.finished = true;
+            self.context.music.stop();
+        }
+    }
+}
@@ -1,13 +1,13 @@
 use crate::{
-    prelude::{HealthConfig, Id, LevelModifiers, Score},
+    prelude::{HealthConfig, Id, LevelModifiers, Replay, Score},
     task::Task,
 };
 
 use ctl_client::{
     core::{
         prelude::Uuid,
-        types::{UserInfo, UserLogin},
-        ScoreEntry, SubmitScore,
+        types::{UserInfo, UserLogin, UserProfile},
+        ScoreEntry, ScoresQuery, SubmitScore,
     },
     Nertboard,
 };
@@ -29,8 +29,11 @@ pub struct Leaderboard {
     geng: Geng,
     /// Logged in as user with a name.
     pub user: Option<UserLogin>,
+    /// Public profile of the logged in user.
+    pub profile: Option<UserProfile>,
     pub client: Option<Arc<Nertboard>>,
     log_task: Option<Task<ctl_client::Result<Result<UserLogin, String>>>>,
+    profile_task: Option<Task<ctl_client::Result<UserProfile>>>,
     task: Option<Task<ctl_client::Result<BoardUpdate>>>,
     pub status: LeaderboardStatus,
     pub loaded: LoadedBoard,
@@ -41,8 +44,10 @@ impl Clone for Leaderboard {
         Self {
             geng: self.geng.clone(),
             user: self.user.clone(),
+            profile: self.profile.clone(),
             client: self.client.clone(),
             log_task: None,
+            profile_task: None,
             task: None,
             status: LeaderboardStatus::None,
             loaded: LoadedBoard {
@@ -101,6 +106,11 @@ impl ScoreCategory {
             health,
         }
     }
+
+    /// The category as it is stored on the server.
+    pub fn server(&self) -> ctl_client::core::ScoreCategory {
+        ctl_client::core::ScoreCategory::new(&self.mods, &self.health)
+    }
 }
 
 impl ScoreMeta {
@@ -117,8 +127,10 @@ impl Leaderboard {
         Self {
             geng: geng.clone(),
             user: None,
+            profile: None,
             client: None,
             log_task: None,
+            profile_task: None,
             task: None,
             status: LeaderboardStatus::None,
             loaded: LoadedBoard::new(),
@@ -129,8 +141,10 @@ impl Leaderboard {
         let mut leaderboard = Self {
             geng: geng.clone(),
             user: None,
+            profile: None,
             client: client.cloned(),
             log_task: None,
+            profile_task: None,
             task: None,
             status: LeaderboardStatus::None,
             loaded: LoadedBoard::new(),
@@ -169,6 +183,7 @@ impl Leaderboard {
             };
             self.log_task = Some(Task::new(&self.geng, future));
             self.user = None;
+            self.profile = None;
         }
     }
 
@@ -188,6 +203,7 @@ impl Leaderboard {
             let future = async move { client.login_token(user.id, &user.token).await };
             self.log_task = Some(Task::new(&self.geng, future));
             self.user = None;
+            self.profile = None;
         }
     }
 
@@ -237,6 +253,7 @@ impl Leaderboard {
             };
             self.log_task = Some(Task::new(&self.geng, future));
             self.user = None;
+            self.profile = None;
         }
     }
 
@@ -252,6 +269,7 @@ impl Leaderboard {
                             preferences::save(crate::PLAYER_LOGIN_STORAGE, &user);
                             self.loaded.player = Some(user.id);
                             self.user = Some(user);
+                            self.fetch_profile();
                         }
                         Ok(Err(err)) => {
                             if err == "Logged out" {
@@ -270,6 +288,21 @@ impl Leaderboard {
             }
         }
 
+        if let Some(task) = self.profile_task.take() {
+            match task.poll() {
+                Err(task) => self.profile_task = Some(task),
+                Ok(res) => match res {
+                    Ok(profile) => {
+                        log::debug!("Loaded the profile of {}", profile.user.name);
+                        self.profile = Some(profile);
+                    }
+                    Err(err) => {
+                        log::error!("Failed to load the profile: {:?}", err);
+                    }
+                },
+            }
+        }
+
         if let Some(task) = self.task.take() {
             match task.poll() {
                 Err(task) => self.task = Some(task),
@@ -288,6 +321,20 @@ impl Leaderboard {
         }
     }
 
+    /// Fetch the public profile of the logged in user.
+    pub fn fetch_profile(&mut self) {
+        if self.profile_task.is_some() {
+            return;
+        }
+
+        if let (Some(client), Some(user)) = (&self.client, &self.user) {
+            let client = Arc::clone(client);
+            let user = user.id;
+            let future = async move { client.get_user_profile(user).await };
+            self.profile_task = Some(Task::new(&self.geng, future));
+        }
+    }
+
     fn load_scores(&mut self, mut scores: Vec<ScoreEntry>) {
         scores.sort_by_key(|entry| -entry.score);
         self.loaded.all_scores = scores;
@@ -300,6 +347,7 @@ impl Leaderboard {
             return; // Unchanged
         }
 
+        let refetch = self.loaded.category.server() != category.server();
         self.loaded.category = category;
         self.loaded.reload_local(None);
         match self.status {
@@ -308,7 +356,12 @@ impl Leaderboard {
             }
             LeaderboardStatus::Pending => {}
             LeaderboardStatus::Done => {
-                self.loaded.refresh();
+                // Categories are separated on the server
+                if refetch {
+                    self.refetch();
+                } else {
+                    self.loaded.refresh();
+                }
             }
         }
     }
@@ -323,10 +376,11 @@ impl Leaderboard {
         if let Some(client) = &self.client {
             let board = Arc::clone(client);
             let level = self.loaded.level;
+            let query = ScoresQuery::category(self.loaded.category.server());
             let future = async move {
                 log::debug!("Fetching scores for level {}...", level);
                 board
-                    .fetch_scores(level)
+                    .fetch_scores(level, &query)
                     .await
                     .map(|scores| BoardUpdate { scores })
             };
@@ -335,7 +389,13 @@ impl Leaderboard {
         }
     }
 
-    pub fn submit(&mut self, mut score: Option<i32>, level: Id, meta: ScoreMeta) {
+    pub fn submit(
+        &mut self,
+        mut score: Option<i32>,
+        level: Id,
+        meta: ScoreMeta,
+        replay: Option<Replay>,
+    ) {
         if self.user.is_none() {
             score = None;
         }
@@ -362,11 +422,16 @@ impl Leaderboard {
 
         if let Some(board) = &self.client {
             let board = Arc::clone(board);
+            let query = ScoresQuery::category(meta.category.server());
             let future = async move {
                 let meta_str = meta_str(&meta);
                 let score = score.map(|score| SubmitScore {
                     score: score.score,
                     extra_info: Some(meta_str),
+                    category: meta.category.server(),
+                    accuracy: meta.score.calculated.accuracy.as_f32(),
+                    precision: meta.score.calculated.precision.as_f32(),
+                    replay,
                 });
 
                 if let Some(score) = &score {
@@ -375,7 +440,7 @@ impl Leaderboard {
                 }
 
                 log::debug!("Fetching scores...");
-                let scores = board.fetch_scores(level).await?;
+                let scores = board.fetch_scores(level, &query).await?;
                 Ok(BoardUpdate { scores })
             };
             self.task = Some(Task::new(&self.geng, future));
@@ -2,11 +2,22 @@ use super::*;
 
 use crate::task::Task;
 
-use ctl_client::Nertboard;
+use ctl_client::{
+    core::{analysis::Waveform, audio::Audio, difficulty::rate_difficulty, types::MusicFormat},
+    Nertboard,
+};
 use generational_arena::Index;
 
 type TaskRes<T> = Option<Task<anyhow::Result<T>>>;
 
+/// Format to download the music in.
+/// Browsers do not agree on the formats they can decode, so the web keeps the original one.
+const MUSIC_FORMAT: Option<MusicFormat> = if cfg!(target_arch = "wasm32") {
+    None
+} else {
+    Some(MusicFormat::Ogg)
+};
+
 pub struct LevelCache {
     geng: Geng,
     pub inner: RefCell<LevelCacheImpl>,
@@ -20,9 +31,17 @@ pub struct LevelCacheImpl {
     pub music_list: CacheState<Vec<MusicInfo>>,
     /// List of downloadable level groups.
     pub group_list: CacheState<Vec<GroupInfo>>,
+    /// Cursor to the next page of the group list.
+    pub group_list_next: Option<String>,
+    /// Search, filters and sorting of the group list, kept for the next pages.
+    pub group_list_query: GroupsQuery,
 
     pub music: HashMap<Id, Rc<CachedMusic>>,
     pub groups: Arena<Rc<CachedGroup>>,
+    /// Editor patterns sorted by name.
+    pub patterns: Vec<Rc<Pattern>>,
+    /// Waveforms of the local music shown in the editor, analyzed on request.
+    pub waveforms: HashMap<Id, CacheState<Rc<Waveform>>>,
 
     pub notifications: Vec<String>,
 }
@@ -42,11 +61,14 @@ pub struct CacheTasks {
     // downloading_music: HashSet<Id>,
     download_music: VecDeque<(Id, Task<Result<CachedMusic>>)>,
 
-    fetch_groups: TaskRes<Vec<GroupInfo>>,
+    /// Fetches a page of groups, and whether to append it to the loaded list.
+    fetch_groups: TaskRes<(GroupsPage, bool)>,
     // downloading_groups: HashSet<Id>,
     download_group: VecDeque<(Id, Task<Result<CachedGroup>>)>,
     get_recommended: TaskRes<Vec<GroupInfo>>,
 
+    analyze_music: VecDeque<(Id, Task<Result<Waveform>>)>,
+
     notifications: Vec<String>,
 }
 
@@ -54,9 +76,16 @@ pub struct CacheTasks {
 enum CacheAction {
     MusicList(Vec<MusicInfo>),
     Music(CachedMusic),
-    GroupList(Vec<GroupInfo>),
+    GroupList {
+        page: GroupsPage,
+        append: bool,
+    },
     Group(CachedGroup),
     DownloadGroups(Vec<Id>),
+    Waveform {
+        music: Id,
+        waveform: Option<Waveform>,
+    },
 }
 
 impl CacheTasks {
@@ -75,6 +104,8 @@ impl CacheTasks {
             download_group: VecDeque::new(),
             get_recommended: None,
 
+            analyze_music: VecDeque::new(),
+
             notifications: Vec::new(),
         }
     }
@@ -105,11 +136,8 @@ impl CacheTasks {
         } else if let Some(task) = self.fetch_groups.take() {
             match task.poll() {
                 Err(task) => self.fetch_groups = Some(task),
-                Ok(result) => {
-                    if let Ok(groups) = result {
-                        return Some(CacheAction::GroupList(groups));
-                    }
-                }
+                Ok(Err(err)) => error!("Failed to fetch groups: {:?}", err),
+                Ok(Ok((page, append))) => return Some(CacheAction::GroupList { page, append }),
             }
         } else if let Some(task) = self.get_recommended.take() {
             match task.poll() {
@@ -142,6 +170,23 @@ impl CacheTasks {
                     return Some(CacheAction::Group(group));
                 }
             }
+        } else if let Some((music_id, task)) = self.analyze_music.pop_front() {
+            match task.poll() {
+                Err(task) => self.analyze_music.push_front((music_id, task)),
+                Ok(result) => {
+                    let waveform = match result {
+                        Ok(waveform) => Some(waveform),
+                        Err(err) => {
+                            error!("Failed to analyze music {}: {:?}", music_id, err);
+                            None
+                        }
+                    };
+                    return Some(CacheAction::Waveform {
+                        music: music_id,
+                        waveform,
+                    });
+                }
+            }
         }
 
         None
@@ -155,9 +200,13 @@ impl LevelCache {
 
             music_list: CacheState::Offline,
             group_list: CacheState::Offline,
+            group_list_next: None,
+            group_list_query: GroupsQuery::default(),
 
             music: HashMap::new(),
             groups: Arena::new(),
+            patterns: Vec::new(),
+            waveforms: HashMap::new(),
 
             notifications: Vec::new(),
         };
@@ -224,6 +273,14 @@ impl LevelCache {
             log::debug!("loaded groups: {}", inner.groups.len());
         }
 
+        {
+            let mut patterns = self.fs.load_patterns_all().await?;
+            patterns.sort_by(|a, b| a.name.cmp(&b.name));
+            let mut inner = self.inner.borrow_mut();
+            inner.patterns = patterns.into_iter().map(Rc::new).collect();
+            log::debug!("loaded patterns: {}", inner.patterns.len());
+        }
+
         Ok(())
     }
 
@@ -366,8 +423,108 @@ impl LevelCache {
         inner.tasks.fs.push_back(Task::new(&self.geng, future));
     }
 
+    pub fn save_replay(&self, replay: &Replay) {
+        let mut inner = self.inner.borrow_mut();
+        let future = {
+            let fs = self.fs.clone();
+            let replay = replay.clone();
+            async move {
+                fs.save_replay(&replay).await?;
+                Ok(())
+            }
+        };
+        inner.tasks.fs.push_back(Task::new(&self.geng, future));
+    }
+
+    fn save_music_info(&self, info: &MusicInfo) {
+        let mut inner = self.inner.borrow_mut();
+        let future = {
+            let fs = self.fs.clone();
+            let info = info.clone();
+            async move {
+                fs.save_music_info(&info).await?;
+                Ok(())
+            }
+        };
+        inner.tasks.fs.push_back(Task::new(&self.geng, future));
+    }
+
+    /// Add the pattern to the library, replacing the one stored under the same name.
+    pub fn save_pattern(&self, pattern: Pattern) -> Rc<Pattern> {
+        let mut inner = self.inner.borrow_mut();
+        let pattern = Rc::new(pattern);
+        let file_name = pattern.file_name();
+        inner
+            .patterns
+            .retain(|other| other.file_name() != file_name);
+        let i = inner
+            .patterns
+            .partition_point(|other| other.name < pattern.name);
+        inner.patterns.insert(i, Rc::clone(&pattern));
+
+        let future = {
+            let fs = self.fs.clone();
+            let pattern = Rc::clone(&pattern);
+            async move {
+                fs.save_pattern(&pattern).await?;
+                Ok(())
+            }
+        };
+        inner.tasks.fs.push_back(Task::new(&self.geng, future));
+
+        pattern
+    }
+
+    pub fn delete_pattern(&self, index: usize) {
+        let mut inner = self.inner.borrow_mut();
+        if index >= inner.patterns.len() {
+            return;
+        }
+        let pattern = inner.patterns.remove(index);
+
+        let future = {
+            let fs = self.fs.clone();
+            async move {
+                fs.remove_pattern(&pattern).await?;
+                Ok(())
+            }
+        };
+        inner.tasks.fs.push_back(Task::new(&self.geng, future));
+    }
+
+    /// Returns the waveform of the local music if it has been analyzed,
+    /// starting the analysis on the first request.
+    pub fn music_waveform(&self, music_id: Id) -> Option<Rc<Waveform>> {
+        let mut inner = self.inner.borrow_mut();
+        match inner.waveforms.get(&music_id) {
+            Some(CacheState::Loaded(waveform)) => return Some(Rc::clone(waveform)),
+            Some(_) => return None,
+            None => {}
+        }
+
+        log::debug!("Analyzing music {}", music_id);
+        inner.waveforms.insert(music_id, CacheState::Loading);
+        let future = {
+            let fs = self.fs.clone();
+            async move {
+                let bytes = fs.load_music_bytes(music_id).await?;
+                let format =
+                    MusicFormat::detect(&bytes).ok_or_else(|| anyhow!("unknown music format"))?;
+                let audio = Audio::decode(bytes, format)?;
+                Ok(Waveform::new(&audio.mono(), audio.sample_rate))
+            }
+        };
+        inner
+            .tasks
+            .analyze_music
+            .push_back((music_id, Task::new(&self.geng, future)));
+
+        None
+    }
+
     fn remove_music(&self, id: Id) {
         let mut inner = self.inner.borrow_mut();
+        inner.waveforms.remove(&id);
         let future = {
             let fs = self.fs.clone();
             async move {
@@ -426,18 +583,45 @@ impl LevelCache {
         self.inner.borrow_mut().groups.insert(group);
     }
 
-    pub fn fetch_groups(&self) {
+    /// Fetch the first page of the group list matching the query.
+    /// Replaces the request in progress, if any.
+    pub fn fetch_groups(&self, query: GroupsQuery) {
+        let mut inner = self.inner.borrow_mut();
+        let query = GroupsQuery {
+            cursor: None,
+            ..query
+        };
+        if let Some(client) = inner.tasks.client.clone() {
+            let first_page = query.clone();
+            let future = async move {
+                let page = client.get_group_list(&first_page).await?;
+                Ok((page, false))
+            };
+            inner.tasks.fetch_groups = Some(Task::new(&self.geng, future));
+            inner.group_list = CacheState::Loading;
+            inner.group_list_next = None;
+        }
+        inner.group_list_query = query;
+    }
+
+    /// Fetch the next page of the group list, if there is one.
+    pub fn fetch_groups_next(&self) {
         let mut inner = self.inner.borrow_mut();
         if inner.tasks.fetch_groups.is_none() {
+            // Kept until the page arrives, so that a failed request can be retried
+            let Some(cursor) = inner.group_list_next.clone() else {
+                return;
+            };
             if let Some(client) = inner.tasks.client.clone() {
+                let query = GroupsQuery {
+                    cursor: Some(cursor),
+                    ..inner.group_list_query.clone()
+                };
                 let future = async move {
-                    let groups = client
-                        .get_group_list(&GroupsQuery { recommended: false })
-                        .await?;
-                    Ok(groups)
+                    let page = client.get_group_list(&query).await?;
+                    Ok((page, true))
                 };
                 inner.tasks.fetch_groups = Some(Task::new(&self.geng, future));
-                inner.group_list = CacheState::Loading;
             }
         }
     }
@@ -447,10 +631,12 @@ impl LevelCache {
         if inner.tasks.get_recommended.is_none() {
             if let Some(client) = inner.tasks.client.clone() {
                 let future = async move {
-                    let list = client
-                        .get_group_list(&GroupsQuery { recommended: true })
-                        .await?;
-                    Ok(list)
+                    let query = GroupsQuery {
+                        recommended: true,
+                        ..default()
+                    };
+                    let page = client.get_group_list(&query).await?;
+                    Ok(page.groups)
                 };
                 inner.tasks.get_recommended = Some(Task::new(&self.geng, future));
             }
@@ -481,8 +667,8 @@ impl LevelCache {
                     // Download group
                     let info = client.get_group_info(group_id).await?;
                     let bytes = client.download_group(group_id).await?.to_vec();
-                    let hash = ctl_client::core::util::calculate_hash(&bytes);
-                    let data: LevelSet = bincode::deserialize(&bytes)?;
+                    let data = LevelSet::decode(&bytes)?;
+                    let hash = data.calculate_hash();
 
                     // Download music
                     let music = match music_list.get(&data.music) {
@@ -491,7 +677,10 @@ impl LevelCache {
                             log::debug!("Downloading music {}", data.music);
                             // Music is not local so we need to download it
                             let meta = client.get_music_info(data.music).await?;
-                            let bytes = client.download_music(data.music).await?.to_vec();
+                            let bytes = client
+                                .download_music(data.music, MUSIC_FORMAT)
+                                .await?
+                                .to_vec();
 
                             log::debug!("Decoding downloaded music bytes");
                             let music = geng.audio().decode(bytes.clone()).await?;
@@ -564,7 +753,10 @@ impl LevelCache {
             let fs = self.fs.clone();
             let future = async move {
                 let meta = client.get_music_info(music_id).await?;
-                let bytes = client.download_music(music_id).await?.to_vec();
+                let bytes = client
+                    .download_music(music_id, MUSIC_FORMAT)
+                    .await?
+                    .to_vec();
 
                 log::debug!("Decoding downloaded music bytes");
                 let music = geng.audio().decode(bytes.clone()).await?;
@@ -599,7 +791,13 @@ impl LevelCache {
 
                     inner.music.insert(music.meta.id, Rc::new(music));
                 }
-                CacheAction::GroupList(groups) => inner.group_list = CacheState::Loaded(groups),
+                CacheAction::GroupList { page, append } => {
+                    inner.group_list_next = page.next;
+                    match &mut inner.group_list {
+                        CacheState::Loaded(groups) if append => groups.extend(page.groups),
+                        _ => inner.group_list = CacheState::Loaded(page.groups),
+                    }
+                }
                 CacheAction::Group(mut group) => {
                     // Check music
                     if group.music.is_none() {
@@ -622,6 +820,13 @@ impl LevelCache {
                     }
                     inner.groups.insert(Rc::new(group));
                 }
+                CacheAction::Waveform { music, waveform } => {
+                    let state = match waveform {
+                        Some(waveform) => CacheState::Loaded(Rc::new(waveform)),
+                        None => CacheState::Offline,
+                    };
+                    inner.waveforms.insert(music, state);
+                }
                 CacheAction::DownloadGroups(ids) => {
                     drop(inner);
                     for group_id in ids {
@@ -700,11 +905,15 @@ impl LevelCache {
         name: String,
     ) -> Option<(Rc<CachedGroup>, Rc<LevelFull>)> {
         let inner = self.inner.borrow();
-        let mut new_group = inner.groups.get(group_index)?.data.clone();
+        let cached = inner.groups.get(group_index)?;
+        let mut new_group = cached.data.clone();
         let new_level = new_group.levels.get_mut(level_index)?;
 
         let mut meta = new_level.meta.clone();
         meta.name = name.into();
+        if let Some(music) = &cached.music {
+            meta.difficulty = Some(rate_difficulty(&level, &music.meta));
+        }
         *new_level = Rc::new(LevelFull { meta, data: level });
 
         drop(inner);
@@ -713,6 +922,32 @@ impl LevelCache {
         Some((group, level))
     }
 
+    /// Replace the metadata of the cached music, keeping the groups that use it in sync.
+    pub fn update_music(&self, meta: MusicInfo) -> Option<Rc<CachedMusic>> {
+        let mut inner = self.inner.borrow_mut();
+        let cached = inner.music.get_mut(&meta.id)?;
+
+        let music = Rc::new(CachedMusic {
+            meta,
+            music: Rc::clone(&cached.music),
+        });
+        *cached = Rc::clone(&music);
+
+        for (_, group) in inner.groups.iter_mut() {
+            if group.data.music == music.meta.id {
+                let mut new_group: CachedGroup = (**group).clone();
+                new_group.music = Some(Rc::clone(&music));
+                *group = Rc::new(new_group);
+            }
+        }
+
+        // Write to fs
+        drop(inner);
+        self.save_music_info(&music.meta);
+
+        Some(music)
+    }
+
     /// Delete the music and all associated groups.
     pub fn delete_music(&self, music_id: Id) {
         let mut inner = self.inner.borrow_mut();
@@ -76,6 +76,25 @@ impl Controller {
         }
     }
 
+    pub async fn load_patterns_all(&self) -> Result<Vec<Pattern>> {
+        log::debug!("Loading all local patterns");
+
+        #[cfg(target_arch = "wasm32")]
+        {
+            match web::load_patterns_all(&self.rexie).await {
+                Ok(items) => Ok(items),
+                Err(err) => {
+                    log::error!("failed to load patterns from web file system: {}", err);
+                    anyhow::bail!("check logs");
+                }
+            }
+        }
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            native::load_patterns_all().await
+        }
+    }
+
     pub async fn save_music(&self, music: &CachedMusic, data: &[u8]) -> Result<()> {
         let id = music.meta.id;
         let info = &music.meta;
@@ -96,6 +115,46 @@ impl Controller {
         Ok(())
     }
 
+    /// Update the metadata of the already saved music.
+    /// Load the raw bytes of the locally saved music file.
+    pub async fn load_music_bytes(&self, id: Id) -> Result<Vec<u8>> {
+        log::debug!("Loading music bytes: {}", id);
+
+        #[cfg(target_arch = "wasm32")]
+        {
+            match web::load_music_bytes(&self.rexie, id).await {
+                Ok(Some(data)) => Ok(data),
+                Ok(None) => anyhow::bail!("music {} is not saved locally", id),
+                Err(err) => {
+                    log::error!("failed to load music from web file system: {}", err);
+                    anyhow::bail!("check logs");
+                }
+            }
+        }
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            let data = file::load_bytes(music_path(id).join("music.mp3")).await?;
+            Ok(data)
+        }
+    }
+
+    pub async fn save_music_info(&self, info: &MusicInfo) -> Result<()> {
+        log::debug!("Saving music info: {}", info.id);
+
+        #[cfg(target_arch = "wasm32")]
+        {
+            if let Err(err) = web::save_music_info(&self.rexie, info).await {
+                log::error!("failed to save music info into web file system: {}", err);
+                anyhow::bail!("check logs");
+            }
+        }
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            native::save_music_info(info)?;
+        }
+        Ok(())
+    }
+
     pub async fn save_group(&self, group: &CachedGroup) -> Result<()> {
         log::debug!("Saving group: {}", group.data.id);
         #[cfg(target_arch = "wasm32")]
@@ -118,6 +177,58 @@ impl Controller {
         Ok(())
     }
 
+    /// Save the replay as the latest run of its level.
+    pub async fn save_replay(&self, replay: &Replay) -> Result<()> {
+        log::debug!("Saving replay for level: {}", replay.level_hash);
+        let data = replay.encode()?;
+
+        #[cfg(target_arch = "wasm32")]
+        {
+            if let Err(err) = web::save_replay(&self.rexie, &replay.level_hash, &data).await {
+                log::error!("failed to save replay into web file system: {}", err);
+                anyhow::bail!("check logs");
+            }
+        }
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            native::save_replay(&replay.level_hash, &data)?;
+        }
+        Ok(())
+    }
+
+    pub async fn save_pattern(&self, pattern: &Pattern) -> Result<()> {
+        log::debug!("Saving pattern: {:?}", pattern.name);
+        #[cfg(target_arch = "wasm32")]
+        {
+            if let Err(err) = web::save_pattern(&self.rexie, pattern).await {
+                log::error!("failed to save pattern into web file system: {}", err);
+                anyhow::bail!("check logs");
+            }
+        }
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            native::save_pattern(pattern)?;
+        }
+        Ok(())
+    }
+
+    pub async fn remove_pattern(&self, pattern: &Pattern) -> Result<()> {
+        log::debug!("Deleting pattern: {:?}", pattern.name);
+        #[cfg(target_arch = "wasm32")]
+        {
+            if let Err(err) = web::remove_pattern(&self.rexie, pattern).await {
+                log::error!("failed to remove pattern from the web file system: {}", err);
+                anyhow::bail!("check logs");
+            }
+            Ok(())
+        }
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            std::fs::remove_file(all_patterns_path().join(pattern.file_name()))?;
+            Ok(())
+        }
+    }
+
     pub async fn remove_music(&self, id: Id) -> Result<()> {
         log::debug!("Deleting music: {:?}", id);
         #[cfg(target_arch = "wasm32")]
@@ -188,6 +299,18 @@ pub fn all_groups_path() -> PathBuf {
     base_path().join("levels")
 }
 
+pub fn all_replays_path() -> PathBuf {
+    base_path().join("replays")
+}
+
+pub fn all_patterns_path() -> PathBuf {
+    base_path().join("patterns")
+}
+
+pub fn replay_path(level_hash: &str) -> PathBuf {
+    all_replays_path().join(format!("{}.replay", level_hash))
+}
+
 pub fn music_path(music: Id) -> PathBuf {
     all_music_path().join(format!("{}", music))
 }
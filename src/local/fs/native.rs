@@ -68,7 +68,7 @@ pub async fn load_groups_all() -> Result<Vec<(PathBuf, LevelSet)>> {
 
     let load_group = |path| async move {
         let bytes = file::load_bytes(&path).await?;
-        let group: LevelSet = bincode::deserialize(&bytes)?;
+        let group = LevelSet::decode(&bytes)?;
         anyhow::Ok((path, group))
     };
 
@@ -98,16 +98,60 @@ pub fn save_music(id: Id, data: &[u8], info: &MusicInfo) -> Result<()> {
     Ok(())
 }
 
+pub fn save_music_info(info: &MusicInfo) -> Result<()> {
+    let path = music_path(info.id);
+    std::fs::write(path.join("meta.toml"), toml::to_string_pretty(&info)?)?;
+    Ok(())
+}
+
 pub fn save_group(group: &CachedGroup) -> Result<()> {
     let path = &group.path;
     if let Some(parent) = path.parent() {
         std::fs::create_dir_all(parent)?;
     }
 
-    let writer = std::io::BufWriter::new(std::fs::File::create(path)?);
-    bincode::serialize_into(writer, &group.data)?;
+    std::fs::write(path, group.data.encode()?)?;
 
     log::debug!("Saved group ({}) successfully", group.data.id);
 
     Ok(())
 }
+
+pub async fn load_patterns_all() -> Result<Vec<Pattern>> {
+    let patterns_path = fs::all_patterns_path();
+    if !patterns_path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut res = Vec::new();
+    for entry in std::fs::read_dir(patterns_path)? {
+        let path = entry?.path();
+        let pattern = std::fs::read_to_string(&path)
+            .map_err(anyhow::Error::from)
+            .and_then(|data| Ok(Pattern::decode(&data)?));
+        match pattern {
+            Ok(pattern) => res.push(pattern),
+            Err(err) => log::error!("failed to load pattern at {:?}: {}", path, err),
+        }
+    }
+
+    Ok(res)
+}
+
+pub fn save_pattern(pattern: &Pattern) -> Result<()> {
+    let path = fs::all_patterns_path();
+    std::fs::create_dir_all(&path)?;
+    std::fs::write(path.join(pattern.file_name()), pattern.encode()?)?;
+    Ok(())
+}
+
+pub fn save_replay(level_hash: &str, data: &[u8]) -> Result<()> {
+    let path = fs::replay_path(level_hash);
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+
+    std::fs::write(path, data)?;
+
+    Ok(())
+}
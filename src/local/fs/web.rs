@@ -15,12 +15,24 @@ struct GroupItem {
     data: String,
 }
 
+#[derive(Serialize, Deserialize)]
+struct ReplayItem {
+    data: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct PatternItem {
+    data: String,
+}
+
 pub async fn build_database() -> Result<Rexie> {
     // Create a new database
     let rexie = Rexie::builder("close-to-light")
-        .version(1)
+        .version(3)
         .add_object_store(ObjectStore::new("music"))
         .add_object_store(ObjectStore::new("groups"))
+        .add_object_store(ObjectStore::new("replays"))
+        .add_object_store(ObjectStore::new("patterns"))
         .build()
         .await?;
 
@@ -64,7 +76,7 @@ pub async fn load_groups_all(rexie: &Rexie) -> Result<Vec<(PathBuf, LevelSet)>>
         let item: GroupItem = serde_wasm_bindgen::from_value(item).unwrap();
 
         let data = BASE64_STANDARD.decode(&item.data).unwrap(); // TODO dont panic
-        let group: LevelSet = bincode::deserialize(&data).unwrap();
+        let group = LevelSet::decode(&data).unwrap();
 
         items.push((path, group));
     }
@@ -72,6 +84,24 @@ pub async fn load_groups_all(rexie: &Rexie) -> Result<Vec<(PathBuf, LevelSet)>>
     Ok(items)
 }
 
+pub async fn load_patterns_all(rexie: &Rexie) -> Result<Vec<Pattern>> {
+    let transaction = rexie.transaction(&["patterns"], TransactionMode::ReadOnly)?;
+
+    let patterns = transaction.store("patterns")?;
+
+    let raw_items = patterns.get_all(None, None, None, None).await?;
+    let mut items = Vec::with_capacity(raw_items.len());
+    for (_key, item) in raw_items {
+        let item: PatternItem = serde_wasm_bindgen::from_value(item).unwrap();
+        match Pattern::decode(&item.data) {
+            Ok(pattern) => items.push(pattern),
+            Err(err) => log::error!("failed to decode pattern: {}", err),
+        }
+    }
+
+    Ok(items)
+}
+
 pub async fn save_music(rexie: &Rexie, id: Id, data: &[u8], info: &MusicInfo) -> Result<()> {
     log::debug!("Storing music {:?} into browser storage", id);
 
@@ -95,6 +125,55 @@ pub async fn save_music(rexie: &Rexie, id: Id, data: &[u8], info: &MusicInfo) ->
     Ok(())
 }
 
+pub async fn load_music_bytes(rexie: &Rexie, id: Id) -> Result<Option<Vec<u8>>> {
+    let transaction = rexie.transaction(&["music"], TransactionMode::ReadOnly)?;
+
+    let music = transaction.store("music")?;
+
+    let raw_items = music.get_all(None, None, None, None).await?;
+    for (key, item) in raw_items {
+        let key: Id = serde_wasm_bindgen::from_value(key).unwrap();
+        if key != id {
+            continue;
+        }
+
+        let item: MusicItem = serde_wasm_bindgen::from_value(item).unwrap();
+        let data = BASE64_STANDARD.decode(&item.data).unwrap(); // TODO dont panic
+        return Ok(Some(data));
+    }
+
+    Ok(None)
+}
+
+pub async fn save_music_info(rexie: &Rexie, info: &MusicInfo) -> Result<()> {
+    log::debug!("Updating music {:?} info in browser storage", info.id);
+
+    let transaction = rexie.transaction(&["music"], TransactionMode::ReadWrite)?;
+
+    let music = transaction.store("music")?;
+
+    let raw_items = music.get_all(None, None, None, None).await?;
+    for (key, item) in raw_items {
+        let key: Id = serde_wasm_bindgen::from_value(key).unwrap();
+        if key != info.id {
+            continue;
+        }
+
+        let mut item: MusicItem = serde_wasm_bindgen::from_value(item).unwrap();
+        item.info = serde_json::to_string(&info).unwrap();
+
+        let serializer = Serializer::json_compatible();
+        let item = item.serialize(&serializer).unwrap();
+        let id = key.serialize(&serializer).unwrap();
+
+        music.put(&item, Some(&id)).await?;
+    }
+
+    transaction.done().await?;
+
+    Ok(())
+}
+
 pub async fn save_group(rexie: &Rexie, group: &CachedGroup, id: &str) -> Result<()> {
     log::debug!("Storing group {:?} into browser storage", id);
 
@@ -102,7 +181,7 @@ pub async fn save_group(rexie: &Rexie, group: &CachedGroup, id: &str) -> Result<
 
     let store = transaction.store("groups")?;
 
-    let data = bincode::serialize(&group.data).unwrap();
+    let data = group.data.encode().unwrap();
     let data = BASE64_STANDARD.encode(&data);
     let item = GroupItem { data };
 
@@ -117,6 +196,65 @@ pub async fn save_group(rexie: &Rexie, group: &CachedGroup, id: &str) -> Result<
     Ok(())
 }
 
+pub async fn save_replay(rexie: &Rexie, level_hash: &str, data: &[u8]) -> Result<()> {
+    log::debug!("Storing replay {:?} into browser storage", level_hash);
+
+    let transaction = rexie.transaction(&["replays"], TransactionMode::ReadWrite)?;
+
+    let store = transaction.store("replays")?;
+
+    let data = BASE64_STANDARD.encode(data);
+    let item = ReplayItem { data };
+
+    let serializer = Serializer::json_compatible();
+    let item = item.serialize(&serializer).unwrap();
+    let id = level_hash.serialize(&serializer).unwrap();
+
+    store.put(&item, Some(&id)).await?;
+
+    transaction.done().await?;
+
+    Ok(())
+}
+
+pub async fn save_pattern(rexie: &Rexie, pattern: &Pattern) -> Result<()> {
+    log::debug!("Storing pattern {:?} into browser storage", pattern.name);
+
+    let transaction = rexie.transaction(&["patterns"], TransactionMode::ReadWrite)?;
+
+    let store = transaction.store("patterns")?;
+
+    let data = pattern.encode().unwrap();
+    let item = PatternItem { data };
+
+    let serializer = Serializer::json_compatible();
+    let item = item.serialize(&serializer).unwrap();
+    let id = pattern.file_name().serialize(&serializer).unwrap();
+
+    store.put(&item, Some(&id)).await?;
+
+    transaction.done().await?;
+
+    Ok(())
+}
+
+pub async fn remove_pattern(rexie: &Rexie, pattern: &Pattern) -> Result<()> {
+    log::debug!("Deleting pattern {:?} from browser storage", pattern.name);
+
+    let transaction = rexie.transaction(&["patterns"], TransactionMode::ReadWrite)?;
+
+    let store = transaction.store("patterns")?;
+
+    let serializer = Serializer::json_compatible();
+    let id = pattern.file_name().serialize(&serializer).unwrap();
+
+    store.delete(&id).await?;
+
+    transaction.done().await?;
+
+    Ok(())
+}
+
 pub async fn remove_music(rexie: &Rexie, id: Id) -> Result<()> {
     log::debug!("Deleting music {:?} from browser storage", id);
 
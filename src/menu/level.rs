@@ -5,7 +5,7 @@ pub use self::ui::*;
 use super::*;
 
 use crate::{
-    game::PlayGroup,
+    game::{PlayGroup, Practice},
     leaderboard::{Leaderboard, LeaderboardStatus, ScoreCategory, ScoreMeta},
     local::CachedMusic,
     render::{mask::MaskedRender, menu::MenuRender},
@@ -70,6 +70,8 @@ pub struct MenuState {
 
     /// Whether to open a (group, level) in the editor.
     pub edit_level: Option<(Index, Option<usize>)>,
+    /// Practice settings for the next run, `None` to play normally.
+    pub practice: Option<Practice>,
 
     /// List of notifications to be consumed and transferred to UI.
     pub notifications: Vec<String>,
@@ -234,6 +236,7 @@ impl LevelMenu {
                 switch_level: None,
 
                 edit_level: None,
+                practice: None,
 
                 notifications: Vec::new(),
             },
@@ -298,6 +301,10 @@ impl LevelMenu {
             let leaderboard = self.state.leaderboard.clone();
             let options = self.state.context.get_options();
             let config = self.state.config.clone();
+            let practice = self.state.practice.clone();
+            let start_time = practice.as_ref().map_or(Time::ZERO, |practice| {
+                group.music.meta.beat_to_time(practice.from_beat)
+            });
 
             async move {
                 let level = crate::game::PlayLevel {
@@ -305,7 +312,8 @@ impl LevelMenu {
                     level_index,
                     level: level.clone(),
                     config,
-                    start_time: Time::ZERO,
+                    start_time,
+                    practice,
                 };
                 crate::game::Game::new(context, options, level, leaderboard)
             }
@@ -444,7 +452,7 @@ impl LevelMenu {
                 score: Score::new(category.mods.multiplier()),
                 category,
             };
-            self.state.leaderboard.submit(None, level.meta.id, meta);
+            self.state.leaderboard.submit(None, level.meta.id, meta, None);
         }
     }
 
@@ -730,6 +738,7 @@ impl geng::State for LevelMenu {
                         level,
                         config: LevelConfig::default(),
                         start_time: Time::ZERO,
+                        practice: None,
                     };
                     crate::editor::EditorState::new_level(context, config, level)
                 } else {
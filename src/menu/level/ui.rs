@@ -1,8 +1,9 @@
 mod level;
 mod modifiers;
+mod practice;
 mod select;
 
-pub use self::{level::*, modifiers::*, select::*};
+pub use self::{level::*, modifiers::*, practice::*, select::*};
 
 use super::*;
 
@@ -6,6 +6,7 @@ pub struct PlayLevelWidget {
     pub music_original: TextWidget,
     pub difficulty: TextWidget,
     pub mappers: TextWidget,
+    pub practice: PracticeWidget,
 }
 
 impl PlayLevelWidget {
@@ -16,6 +17,7 @@ impl PlayLevelWidget {
             music_original: TextWidget::new("original"),
             difficulty: TextWidget::new(""),
             mappers: TextWidget::new("").aligned(vec2(1.0, 0.5)),
+            practice: PracticeWidget::new(),
         };
         widget.music_original.hide();
         widget
@@ -29,11 +31,13 @@ impl PlayLevelWidget {
         main.cut_top(context.layout_size * 1.0);
         let difficulty_pos = main.cut_top(context.font_size * 1.0);
         let mappers_pos = main.cut_top(context.font_size * 0.5);
+        main.cut_top(context.layout_size * 1.0);
+        let practice_pos = main;
 
         let font_factor = 1.3; // Scaling factor to fit better in the designated area
 
         // Sync data and dynamic layout
-        let local = &state.context.local;
+        let local = state.context.local.clone();
         if let Some(show) = &state.selected_music {
             if let Some(music) = local.get_music(show.data) {
                 self.music.text = music.meta.name.clone();
@@ -60,9 +64,15 @@ impl PlayLevelWidget {
         if let Some(group) = &state.selected_group {
             if let Some(show) = &state.selected_level {
                 if let Some(level) = local.get_level(group.data, show.data) {
-                    self.difficulty.text = level.meta.name.clone();
+                    self.difficulty.text = match level.meta.difficulty {
+                        Some(difficulty) => {
+                            format!("{} ({:.1})", level.meta.name, difficulty.as_f32()).into()
+                        }
+                        None => level.meta.name.clone(),
+                    };
                     self.mappers.text = level.meta.authors().into();
 
+                    let level_id = (group.data, show.data);
                     let t = crate::util::smoothstep(1.0 - show.time.get_ratio());
                     let slide = vec2(context.screen.max.x - difficulty_pos.min.x, 0.0) * t;
 
@@ -71,6 +81,14 @@ impl PlayLevelWidget {
                     self.difficulty.options.size = difficulty_pos.height() * font_factor;
                     self.mappers.update(mappers_pos.translate(slide), context);
                     self.mappers.options.size = mappers_pos.height() * font_factor;
+
+                    self.practice.update(
+                        practice_pos.translate(slide),
+                        state,
+                        context,
+                        level_id,
+                        level.data.last_beat(),
+                    );
                 }
             }
         }
@@ -0,0 +1,138 @@
+use super::*;
+
+use geng_utils::bounded::Bounded;
+
+/// Slowest playback rate allowed in practice.
+const MIN_SPEED: f32 = 0.5;
+
+pub struct PracticeWidget {
+    pub state: WidgetState,
+    pub toggle: ToggleWidget,
+    pub timeline: WidgetState,
+    /// The selected section of the timeline.
+    pub range: WidgetState,
+    pub range_text: TextWidget,
+    pub speed: SliderWidget,
+    /// Beat at which the current drag on the timeline started.
+    drag_from: Option<Time>,
+    /// The (group, level) that the section has been picked for.
+    level: Option<(Index, usize)>,
+}
+
+impl PracticeWidget {
+    pub fn new() -> Self {
+        Self {
+            state: WidgetState::new(),
+            toggle: ToggleWidget::new_deselectable("Practice"),
+            timeline: WidgetState::new(),
+            range: WidgetState::new(),
+            range_text: TextWidget::new("").aligned(vec2(1.0, 0.5)),
+            speed: SliderWidget::new("Speed"),
+            drag_from: None,
+            level: None,
+        }
+    }
+
+    pub fn update(
+        &mut self,
+        mut main: Aabb2<f32>,
+        state: &mut MenuState,
+        context: &mut UiContext,
+        level_id: (Index, usize),
+        last_beat: Time,
+    ) {
+        let whole_level = |speed| Practice {
+            from_beat: Time::ZERO,
+            to_beat: last_beat,
+            speed,
+        };
+
+        if self.level != Some(level_id) {
+            // Pick the whole level by default
+            self.level = Some(level_id);
+            self.drag_from = None;
+            if let Some(practice) = &mut state.practice {
+                *practice = whole_level(practice.speed);
+            }
+        }
+
+        self.state.update(main, context);
+
+        let mut toggle_row = main.cut_top(context.font_size * 0.6);
+        let toggle = toggle_row.cut_left(context.font_size * 3.0);
+        self.toggle.selected = state.practice.is_some();
+        self.toggle.update(toggle, context);
+        if self.toggle.selected != state.practice.is_some() {
+            state.practice = self.toggle.selected.then(|| whole_level(Time::ONE));
+        }
+        let range_text = toggle_row;
+
+        let Some(practice) = &mut state.practice else {
+            self.timeline.hide();
+            self.range.hide();
+            self.range_text.hide();
+            self.speed.hide();
+            return;
+        };
+        self.timeline.show();
+        self.range.show();
+        self.range_text.show();
+        self.speed.show();
+
+        main.cut_top(context.layout_size * 0.5);
+        let timeline = main.cut_top(context.font_size * 0.5);
+        self.timeline.update(timeline, context);
+
+        let beat_at = |x: f32| {
+            let t = ((x - timeline.min.x) / timeline.width()).clamp(0.0, 1.0);
+            // Snap to whole beats
+            r32((t * last_beat.as_f32()).round())
+        };
+        if self.timeline.pressed {
+            let beat = beat_at(context.cursor.position.x);
+            let from = *self.drag_from.get_or_insert(beat);
+            practice.from_beat = from.min(beat);
+            practice.to_beat = from.max(beat);
+        } else {
+            self.drag_from = None;
+            // Keep the section at least a beat long
+            if practice.to_beat - practice.from_beat < Time::ONE {
+                practice.to_beat = (practice.from_beat + Time::ONE).min(last_beat);
+                practice.from_beat = (practice.to_beat - Time::ONE).max(Time::ZERO);
+            }
+        }
+
+        let x_at = |beat: Time| {
+            let t = if last_beat > Time::ZERO {
+                (beat / last_beat).as_f32()
+            } else {
+                0.0
+            };
+            timeline.min.x + timeline.width() * t
+        };
+        let range = Aabb2::from_corners(
+            vec2(x_at(practice.from_beat), timeline.min.y),
+            vec2(x_at(practice.to_beat), timeline.max.y),
+        );
+        self.range.update(range, context);
+
+        self.range_text.text = format!(
+            "Beats {:.0}-{:.0}",
+            practice.from_beat.as_f32(),
+            practice.to_beat.as_f32()
+        )
+        .into();
+        self.range_text
+            .update(range_text, &mut context.scale_font(0.7));
+
+        main.cut_top(context.layout_size * 0.5);
+        let speed = main.cut_top(context.font_size * 0.6);
+        let mut value = Bounded::new(practice.speed.as_f32(), MIN_SPEED..=1.0);
+        self.speed
+            .update(speed, &mut context.scale_font(0.7), &mut value);
+        // Snap to 5%
+        practice.speed = r32((value.value() * 20.0).round() / 20.0);
+
+        context.update_focus(self.timeline.hovered || self.speed.state.hovered);
+    }
+}
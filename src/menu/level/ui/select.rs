@@ -354,16 +354,19 @@ impl LevelSelectUI {
     ) -> Option<LevelSelectAction> {
         let local = state.context.local.inner.borrow();
         let group_idx = state.switch_group;
+        // Easiest first, unrated last
         let levels: Vec<_> = group_idx
             .and_then(|group| local.groups.get(group))
             .map(|group| group.data.levels.clone())
             .into_iter()
             .flatten()
+            .enumerate()
+            .sorted_by_key(|(_, level)| (level.meta.difficulty.is_none(), level.meta.difficulty))
             .collect();
 
         // Synchronize vec length
         if self.grid_levels.len() != levels.len() {
-            if let Some(cached) = levels.first() {
+            if let Some((_, cached)) = levels.first() {
                 self.grid_levels = vec![
                     ItemLevelWidget::new(
                         &self.assets,
@@ -383,9 +386,8 @@ impl LevelSelectUI {
         let group = state.context.local.get_group(group_idx)?;
 
         // Synchronize data
-        for (widget, (level_id, cached)) in
-            self.grid_levels.iter_mut().zip(levels.iter().enumerate())
-        {
+        for (widget, (level_id, cached)) in self.grid_levels.iter_mut().zip(&levels) {
+            let level_id = *level_id;
             let origin_hash = group.origin.as_ref().and_then(|info| {
                 info.levels
                     .iter()
@@ -650,6 +652,7 @@ pub struct ItemLevelWidget {
     pub edited: IconWidget,
     pub local: IconWidget,
     pub text: TextWidget,
+    pub rating: TextWidget,
     pub group: Index,
     pub index: usize,
     pub level: Rc<LevelFull>,
@@ -671,6 +674,7 @@ impl ItemLevelWidget {
             edited: IconWidget::new(&assets.sprites.star),
             local: IconWidget::new(&assets.sprites.local),
             text: TextWidget::new(text).aligned(vec2(0.5, 0.5)),
+            rating: TextWidget::new("").aligned(vec2(1.0, 0.5)),
             group,
             index,
             level,
@@ -689,6 +693,10 @@ impl ItemLevelWidget {
         self.group = group_idx;
         self.level = cached.clone();
         self.text.text = cached.meta.name.clone();
+        self.rating.text = match cached.meta.difficulty {
+            Some(difficulty) => format!("{:.1}", difficulty.as_f32()).into(),
+            None => "?".into(),
+        };
         if cached.meta.id == 0 {
             self.local.show();
             self.edited.hide();
@@ -736,6 +744,12 @@ impl ItemLevelWidget {
             }
         }
 
+        let rating = position.cut_right(position.height() * 1.5);
+        self.rating.update(
+            rating.extend_right(-context.font_size * 0.3),
+            &mut context.scale_font(0.7),
+        );
+
         self.text.update(position, &mut context.scale_font(0.9));
 
         let mut action = None;
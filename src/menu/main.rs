@@ -269,7 +269,7 @@ impl MainUI {
             let _ = webbrowser::open(crate::DISCORD_SERVER_URL);
         }
 
-        let profile = vec2(6.0, 3.0) * font_size;
+        let profile = vec2(6.0, ProfileWidget::ROWS as f32) * font_size;
         let profile = screen
             .align_aabb(profile, vec2(1.0, 0.0))
             .translate(vec2(-1.0, 1.0) * layout_size);
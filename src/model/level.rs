@@ -1,6 +0,0 @@
-mod config;
-mod state;
-
-pub use self::{config::*, state::*};
-
-use super::*;
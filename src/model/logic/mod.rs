@@ -6,25 +6,33 @@ impl Model {
     /// Initialize the level by playing the events from the negative time.
     pub fn init(&mut self, target_time: Time) {
         log::info!("Starting at the requested time {:.2}...", target_time);
-        self.beat_time = target_time / self.level.group.music.meta.beat_time();
+        self.beat_time = self.level.group.music.meta.time_to_beat(target_time);
         self.player.health.set_ratio(Time::ONE);
         self.state = State::Starting {
             start_timer: r32(1.0),
-            music_start_time: target_time,
+            music_start_time: self.level.group.music.meta.offset + target_time,
         };
     }
 
     pub fn update(&mut self, player_target: vec2<Coord>, delta_time: Time) {
+        // The whole level is slowed down together with the music
+        let delta_time = delta_time * self.game_speed();
+
         self.context.music.set_volume(self.options.volume.music());
 
         self.update_rhythm(delta_time);
 
+        if let State::Playing = self.state {
+            self.replay.record(player_target, delta_time);
+        }
+
         // Move
         self.player.collider.position = player_target;
 
         if let State::Starting { .. } = self.state {
         } else {
-            self.beat_time += delta_time / self.level.group.music.meta.beat_time();
+            let music = &self.level.group.music.meta;
+            self.beat_time = music.time_to_beat(music.beat_to_time(self.beat_time) + delta_time);
         }
 
         self.real_time += delta_time;
@@ -57,43 +65,13 @@ impl Model {
 
         // Update player's light state
         // And check for missed rhythm
-        let get_light = |id: Option<usize>, pass: bool| {
-            id.and_then(|id| {
-                self.level_state
-                    .lights
-                    .iter()
-                    .find(|light| light.event_id == Some(id))
-                    .filter(|light| {
-                        // Can only miss after the waypoint, not before, hence no buffer time
-                        // (allows for unpunished early exit)
-                        //
-                        // A miss occurs when the player was inside a light that was leaving its waypoint
-                        // and has missed the coyote time
-                        //
-                        // `pass` used to extend the coyote time for `last_light`,
-                        // because otherwise we cannot detect a miss
-                        // as both will get set to `None` at the same frame
-                        // (allows for unpunished late entrance)
-                        let time = light.closest_waypoint.0.as_f32();
-                        time < 0.0 && (time > -COYOTE_TIME || pass && time > -COYOTE_TIME * 2.0)
-                    })
-                    .map(|light| (id, light.closest_waypoint.1))
-            })
-        };
-        let last_light = get_light(self.player.closest_light, true);
-
-        // Update light state
-        self.player.reset_distance();
-        for light in self.level_state.lights.iter() {
-            self.player.update_light_distance(light, self.last_rhythm);
-        }
-
-        // Check missed rhythm
-        let light = get_light(self.player.closest_light, false);
-        if last_light.is_some() && last_light != light && last_light != Some(self.last_rhythm) {
-            // Light has changed and no perfect rhythm
-            self.score.metrics.discrete.missed_rhythm();
-            self.handle_event(GameEvent::Rhythm { perfect: false });
+        if let Some(event) = simulation::update_lights(
+            &mut self.player,
+            &mut self.score,
+            &mut self.last_rhythm,
+            &self.level_state,
+        ) {
+            self.handle_event(event);
         }
 
         match &mut self.state {
@@ -115,26 +93,23 @@ impl Model {
                     //     self.telegraphs.push(telegraph);
                     // } else
                     self.finish();
+                } else if self
+                    .level
+                    .practice
+                    .as_ref()
+                    .is_some_and(|practice| self.beat_time >= practice.to_beat)
+                {
+                    // Loop the practiced section
+                    self.restart();
                 } else if !self.level.config.modifiers.clean_auto {
-                    // Player health
-                    if let Some(distance) = self.player.danger_distance {
-                        let multiplier = (r32(1.0) - distance + r32(0.5)).min(r32(1.0));
-                        self.player.health.change(
-                            -self.level.config.health.danger_decrease_rate
-                                * multiplier
-                                * delta_time,
-                        );
-                    } else if self.player.light_distance.is_some() {
-                        self.player
-                            .health
-                            .change(self.level.config.health.restore_rate * delta_time);
-                    } else {
-                        self.player
-                            .health
-                            .change(-self.level.config.health.dark_decrease_rate * delta_time);
-                    }
-
-                    let events = self.score.update(&self.player, delta_time);
+                    let events = simulation::update_score(
+                        &mut self.player,
+                        &mut self.score,
+                        &mut self.last_rhythm,
+                        &self.level_state,
+                        &self.level.config,
+                        delta_time,
+                    );
                     for event in events {
                         self.handle_event(event);
                     }
@@ -188,7 +163,18 @@ impl Model {
         self.rhythms.retain(|rhythm| !rhythm.time.is_max());
     }
 
+    /// Speed at which the level is played, slowed down in practice mode.
+    fn game_speed(&self) -> Time {
+        self.level
+            .practice
+            .as_ref()
+            .map_or(Time::ONE, |practice| practice.speed)
+    }
+
     pub fn save_highscore(&self) {
+        if self.level.practice.is_some() {
+            return;
+        }
         let high_score = self.high_score.max(self.score.calculated.combined);
         preferences::save("highscore", &high_score);
     }
@@ -206,14 +192,30 @@ impl Model {
 
     pub fn start(&mut self, music_start_time: Time) {
         self.state = State::Playing;
+        // Replays are recorded from here, so waiting for the player must not affect the run
+        simulation::reset_run(
+            &mut self.player,
+            &mut self.score,
+            &mut self.last_rhythm,
+            &self.level.config,
+        );
         self.context.music.play_from(
             &self.level.group.music,
-            time::Duration::from_secs_f64(music_start_time.as_f32() as f64),
+            time::Duration::from_secs_f64(music_start_time.max(Time::ZERO).as_f32() as f64),
         );
+        if let Some(practice) = &self.level.practice {
+            self.context.music.set_speed(practice.speed.as_f32());
+        }
     }
 
     pub fn finish(&mut self) {
+        if self.level.practice.is_some() {
+            self.restart();
+            return;
+        }
+
         self.save_highscore();
+        self.replay.score = self.score.clone();
         self.state = State::Finished;
         self.context.music.stop();
         self.switch_time = Time::ZERO;
@@ -221,7 +223,13 @@ impl Model {
     }
 
     pub fn lose(&mut self) {
+        if self.level.practice.is_some() {
+            self.restart();
+            return;
+        }
+
         self.save_highscore();
+        self.replay.score = self.score.clone();
         self.state = State::Lost {
             death_beat_time: self.beat_time,
         };
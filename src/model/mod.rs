@@ -1,22 +1,11 @@
-mod level;
 mod logic;
 mod options;
-mod player;
-mod score;
 
-pub use self::{level::*, options::*, player::*, score::*};
+pub use self::options::*;
 
 use crate::{game::PlayLevel, leaderboard::Leaderboard, prelude::*};
 
-const COYOTE_TIME: f32 = 0.1;
-const BUFFER_TIME: f32 = 0.1;
-
-pub type Lifetime = Bounded<Time>;
-
-#[derive(Debug, Clone)]
-pub enum GameEvent {
-    Rhythm { perfect: bool },
-}
+use ctl_client::core::simulation;
 
 #[derive(Debug, Clone)]
 pub struct HoverButton {
@@ -118,6 +107,8 @@ pub struct Model {
     pub last_rhythm: (usize, WaypointId),
     /// Waypoint rhythms.
     pub rhythms: Vec<Rhythm>,
+    /// Recording of the player input during the current run.
+    pub replay: Replay,
 
     pub real_time: Time,
     /// Time since the last state change.
@@ -183,8 +174,14 @@ impl Model {
             },
             score: Score::new(level.config.modifiers.multiplier()),
 
-            last_rhythm: (999, WaypointId::Frame(999)), // Should be never the first one
+            last_rhythm: simulation::NO_RHYTHM,
             rhythms: Vec::new(),
+            replay: Replay::new(
+                level.level.meta.id,
+                level.level.data.calculate_hash(),
+                level.config.clone(),
+                level.start_time,
+            ),
 
             beat_time: Time::ZERO,
             real_time: Time::ZERO,
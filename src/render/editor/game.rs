@@ -59,7 +59,6 @@ impl EditorRender {
         let hovered_event = level_editor.level_state.hovered_event();
 
         let select_color = editor.config.theme.select;
-        let selected_event = level_editor.selected_light.map(|i| i.event);
 
         let get_color =
             |event_id: Option<usize>| -> Color {
@@ -75,7 +74,9 @@ impl EditorRender {
                     } else {
                         light_color
                     };
-                    let mod_color = if !editor.show_only_selected && check(selected_event) {
+                    let mod_color = if !editor.show_only_selected
+                        && level_editor.selection.contains(LightId { event: event_id })
+                    {
                         select_color
                     } else if check(hovered_event) {
                         hover_color
@@ -97,6 +98,7 @@ impl EditorRender {
             };
 
         let static_alpha = if let State::Place { .. }
+        | State::PlacePattern { .. }
         | State::Movement { .. }
         | State::Waypoints { .. } = level_editor.state
         {
@@ -178,10 +180,13 @@ impl EditorRender {
 
                 let mut pixel_buffer = if editor.visualize_beat {
                     // Active movement
-                    let time = time
-                        + (level_editor.real_time
-                            / level_editor.static_level.group.music.meta.beat_time())
-                        .fract();
+                    let beat_time = level_editor
+                        .static_level
+                        .group
+                        .music
+                        .meta
+                        .beat_time_at(time);
+                    let time = time + (level_editor.real_time / beat_time).fract();
                     draw_active(time, &mut pixel_buffer);
                     draw_game!(0.75)
                 } else {
@@ -197,15 +202,15 @@ impl EditorRender {
 
             // Current action
             let shape = match level_editor.state {
-                State::Place { shape, danger } => Some((shape, danger)),
-                State::Movement { ref light, .. } => Some((light.light.shape, light.light.danger)),
+                State::Place { ref shape, danger } => Some((shape, danger)),
+                State::Movement { ref light, .. } => Some((&light.light.shape, light.light.danger)),
                 _ => None,
             };
             if let Some((shape, danger)) = shape {
                 let collider = Collider {
                     position: editor.cursor_world_pos_snapped,
                     rotation: level_editor.place_rotation,
-                    shape: shape.scaled(level_editor.place_scale),
+                    shape: shape.scaled(vec2::splat(level_editor.place_scale)),
                 };
                 let color = if danger { THEME.danger } else { THEME.light };
                 self.util.draw_outline(
@@ -216,6 +221,50 @@ impl EditorRender {
                     &mut pixel_buffer,
                 );
             }
+
+            if let State::PlacePattern { pattern } = &level_editor.state {
+                let placement = Placement {
+                    position: editor.cursor_world_pos_snapped,
+                    rotation: level_editor.place_rotation,
+                    scale: level_editor.place_scale,
+                };
+                for event in &pattern.events {
+                    if let Event::Light(event) = &event.event {
+                        let collider = Collider::new(vec2::ZERO, event.light.shape.clone())
+                            .transformed(placement.apply(&event.light.movement.initial));
+                        let color = if event.light.danger {
+                            THEME.danger
+                        } else {
+                            THEME.light
+                        };
+                        self.util.draw_outline(
+                            &collider,
+                            0.05,
+                            color,
+                            &level_editor.model.camera,
+                            &mut pixel_buffer,
+                        );
+                    }
+                }
+            }
+
+            if let Some(area) = level_editor.selection_area {
+                let collider = Collider {
+                    position: area.center(),
+                    rotation: Angle::ZERO,
+                    shape: Shape::Rectangle {
+                        width: area.width(),
+                        height: area.height(),
+                    },
+                };
+                self.util.draw_outline(
+                    &collider,
+                    0.05,
+                    select_color,
+                    &level_editor.model.camera,
+                    &mut pixel_buffer,
+                );
+            }
         }
         let mut pixel_buffer = draw_game!(1.0);
 
@@ -230,13 +279,39 @@ impl EditorRender {
                     };
 
                     // A dashed line moving through the waypoints to show general direction
-                    let mut positions: Vec<vec2<f32>> = level_editor
+                    let mut positions: Vec<vec2<f32>> = Vec::new();
+                    let points = level_editor
                         .level_state
                         .waypoints
                         .iter()
-                        .flat_map(|waypoints| &waypoints.points)
-                        .map(|point| point.collider.position.as_f32())
-                        .collect();
+                        .flat_map(|waypoints| &waypoints.points);
+                    for point in points {
+                        let curve = match point.original {
+                            Some(WaypointId::Frame(i)) => event
+                                .light
+                                .movement
+                                .key_frames
+                                .get(i)
+                                .map(|frame| frame.curve.as_slice()),
+                            _ => None,
+                        };
+                        let to = point.collider.position;
+                        match (positions.last(), curve) {
+                            (Some(&from), Some(curve)) if !curve.is_empty() => {
+                                // Sample the curve
+                                let controls: Vec<_> = std::iter::once(from.as_r32())
+                                    .chain(curve.iter().copied())
+                                    .chain(std::iter::once(to))
+                                    .collect();
+                                let resolution = 20;
+                                positions.extend((1..=resolution).map(|i| {
+                                    let t = r32(i as f32 / resolution as f32);
+                                    ctl_client::core::util::bezier(&controls, t).as_f32()
+                                }));
+                            }
+                            _ => positions.push(to.as_f32()),
+                        }
+                    }
                     positions.dedup();
                     let options = util::DashRenderOptions {
                         width: 0.15,
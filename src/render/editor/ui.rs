@@ -1,5 +1,7 @@
 use crate::ui::layout::AreaOps;
 
+use ctl_client::core::lint::Severity;
+
 use super::*;
 
 impl EditorRender {
@@ -18,6 +20,14 @@ impl EditorRender {
             self.draw_tab_config(editor, &ui.config);
         }
 
+        if ui.patterns.state.visible {
+            self.draw_tab_patterns(editor, &ui.patterns);
+        }
+
+        if ui.lint.state.visible {
+            self.draw_tab_lint(editor, &ui.lint);
+        }
+
         if ui.edit.state.visible {
             let framebuffer =
                 &mut geng_utils::texture::attach_texture(&mut self.ui_texture, self.geng.ugli());
@@ -52,6 +62,20 @@ impl EditorRender {
             theme,
             framebuffer,
         );
+        self.ui.draw_toggle_button(
+            &ui.tab_patterns.text,
+            ui.patterns.state.visible,
+            false,
+            theme,
+            framebuffer,
+        );
+        self.ui.draw_toggle_button(
+            &ui.tab_lint.text,
+            ui.lint.state.visible,
+            false,
+            theme,
+            framebuffer,
+        );
 
         self.ui.draw_button(&ui.exit, theme, framebuffer);
 
@@ -115,10 +139,63 @@ impl EditorRender {
 
         self.ui.draw_text(&ui.timeline, framebuffer);
         self.ui.draw_value(&ui.scroll_by, framebuffer);
-        self.ui.draw_value(&ui.shift_scroll, framebuffer);
+        self.ui.draw_button(&ui.beat_snap, theme, framebuffer);
         self.ui.draw_value(&ui.alt_scroll, framebuffer);
     }
 
+    fn draw_tab_patterns(&mut self, editor: &Editor, ui: &EditorPatternsWidget) {
+        if !ui.state.visible {
+            return;
+        }
+
+        let framebuffer =
+            &mut geng_utils::texture::attach_texture(&mut self.ui_texture, self.geng.ugli());
+        let theme = editor.context.get_options().theme;
+
+        self.ui.draw_text(&ui.save_title, framebuffer);
+        self.ui.draw_input(&ui.pattern_name, framebuffer);
+        self.ui.draw_button(&ui.save, theme, framebuffer);
+
+        self.ui.draw_text(&ui.library, framebuffer);
+        let placing =
+            editor
+                .level_edit
+                .as_ref()
+                .and_then(|level_editor| match &level_editor.state {
+                    State::PlacePattern { pattern } => Some(pattern.name.as_str()),
+                    _ => None,
+                });
+        for (delete, name) in &ui.all_pattern_names {
+            let selected = placing == Some(&*name.text);
+            self.ui.draw_icon(delete, theme, framebuffer);
+            self.ui
+                .draw_toggle_button(name, selected, false, theme, framebuffer);
+        }
+    }
+
+    fn draw_tab_lint(&mut self, editor: &Editor, ui: &EditorLintWidget) {
+        if !ui.state.visible {
+            return;
+        }
+
+        let framebuffer =
+            &mut geng_utils::texture::attach_texture(&mut self.ui_texture, self.geng.ugli());
+        let theme = editor.context.get_options().theme;
+
+        self.ui.draw_text(&ui.title, framebuffer);
+        self.ui.draw_text(&ui.no_problems, framebuffer);
+        for (problem, diagnostic) in ui.problems.iter().zip(&ui.diagnostics) {
+            let color = if problem.state.hovered {
+                theme.highlight
+            } else if diagnostic.severity == Severity::Error {
+                theme.danger
+            } else {
+                theme.light
+            };
+            self.ui.draw_text_colored(problem, color, framebuffer);
+        }
+    }
+
     fn draw_tab_edit(&mut self, editor: &Editor, ui: &EditorEditWidget) {
         if !ui.state.visible {
             return;
@@ -157,7 +234,19 @@ impl EditorRender {
         self.ui.draw_checkbox(&ui.snap_grid, theme, framebuffer);
         self.ui.draw_value(&ui.grid_size, framebuffer);
 
+        self.ui.draw_text(&ui.tempo, framebuffer);
+        self.ui.draw_value(&ui.tempo_bpm, framebuffer);
+        self.ui.draw_button(&ui.tempo_marker, theme, framebuffer);
+
         // Light
+        self.ui.draw_text(&ui.selection, framebuffer);
+        self.ui
+            .draw_button(&ui.selection_delete, theme, framebuffer);
+        self.ui
+            .draw_button(&ui.selection_flip_x, theme, framebuffer);
+        self.ui
+            .draw_button(&ui.selection_flip_y, theme, framebuffer);
+
         self.ui.draw_text(&ui.light, framebuffer);
         self.ui.draw_button(&ui.light_delete, theme, framebuffer);
         self.ui.draw_checkbox(&ui.light_danger, theme, framebuffer);
@@ -172,14 +261,32 @@ impl EditorRender {
             .draw_icon_button(&ui.next_waypoint, theme, framebuffer);
         self.ui.draw_text(&ui.current_waypoint, framebuffer);
         self.ui.draw_button(&ui.waypoint_delete, theme, framebuffer);
-        self.ui.draw_value(&ui.waypoint_scale, framebuffer);
+        self.ui.draw_value(&ui.waypoint_scale_x, framebuffer);
+        self.ui.draw_value(&ui.waypoint_scale_y, framebuffer);
         self.ui.draw_value(&ui.waypoint_angle, framebuffer);
+        self.ui.draw_button(&ui.waypoint_easing, theme, framebuffer);
+        self.ui.draw_value(&ui.waypoint_curve, framebuffer);
 
         {
             // Timeline
             self.ui.draw_text(&ui.current_beat, framebuffer);
 
             let mut quad = |aabb, color| self.geng.draw2d().quad(framebuffer, camera, aabb, color);
+
+            // Music
+            let waveform_color = crate::util::with_alpha(theme.light, 0.3);
+            for &bar in &ui.timeline.waveform {
+                quad(bar, waveform_color);
+            }
+            for &onset in &ui.timeline.onsets {
+                quad(onset, theme.highlight);
+            }
+
+            let grid_color = crate::util::with_alpha(theme.light, 0.5);
+            for &tick in &ui.timeline.ticks {
+                quad(tick, grid_color);
+            }
+
             let bar = ui.timeline.bar.position;
             quad(bar, theme.light);
 
@@ -243,7 +350,7 @@ impl EditorRender {
 
             // All lights
             for (id, light) in ui.timeline.lights.values().flatten() {
-                let color = if level_editor.selected_light == Some(*id) {
+                let color = if level_editor.selection.contains(*id) {
                     theme.highlight
                 } else if level_editor.level.events.get(id.event).map_or(
                     false,
@@ -262,6 +369,17 @@ impl EditorRender {
             for (_, waypoint) in &ui.timeline.waypoints {
                 diamond(waypoint.position, theme.highlight, framebuffer);
             }
+            // Tempo changes
+            for (_, marker) in &ui.timeline.tempo {
+                let color = if marker.hovered {
+                    theme.highlight
+                } else {
+                    theme.light
+                };
+                self.geng
+                    .draw2d()
+                    .quad(framebuffer, camera, marker.position, color);
+            }
 
             // // Selected light timespan
             // let event = if let State::Waypoints { event, .. } = level_editor.state {
@@ -105,11 +105,8 @@ impl GameRender {
             };
             let t = rhythm.time.get_ratio().as_f32();
 
-            let scale = r32(crate::util::smoothstep(1.0 - t));
-            let mut visual = model
-                .player
-                .collider
-                .transformed(Transform { scale, ..default() });
+            let scale = crate::util::smoothstep(1.0 - t);
+            let mut visual = model.player.collider.transformed(Transform::scale(scale));
             visual.position = rhythm.position;
             self.util
                 .draw_outline(&visual, 0.05, color, camera, &mut framebuffer);
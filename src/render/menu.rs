@@ -118,6 +118,7 @@ impl MenuRender {
                 self.ui.draw_icon(&sync.close.icon, theme, framebuffer);
                 self.ui.draw_text(&sync.title, framebuffer);
                 self.ui.draw_text(&sync.status, framebuffer);
+                self.ui.draw_text(&sync.changes, framebuffer);
 
                 self.ui
                     .draw_toggle(&sync.upload, self.font_size * 0.2, theme, framebuffer);
@@ -192,6 +193,8 @@ impl MenuRender {
                 );
                 let selected = state.switch_level == Some(level.index);
                 self.draw_item_widget(&level.text, selected, 1.0, theme, framebuffer);
+                self.ui
+                    .draw_text_colored(&level.rating, theme.highlight, framebuffer);
                 self.draw_item_menu(&level.menu, theme, framebuffer);
             }
         }
@@ -225,6 +228,21 @@ impl MenuRender {
             .draw_text_colored(&ui.difficulty, theme.highlight, framebuffer);
         self.ui
             .draw_text_colored(&ui.mappers, theme.highlight, framebuffer);
+
+        if state.selected_level.is_some() {
+            let practice = &ui.practice;
+            self.ui
+                .draw_toggle_widget(&practice.toggle, theme, framebuffer);
+            if practice.timeline.visible {
+                let width = self.font_size * 0.1;
+                self.ui
+                    .fill_quad(practice.range.position, theme.highlight, framebuffer);
+                self.ui
+                    .draw_outline(practice.timeline.position, width, theme.light, framebuffer);
+                self.ui.draw_text(&practice.range_text, framebuffer);
+                self.ui.draw_slider(&practice.speed, theme, framebuffer);
+            }
+        }
     }
 
     fn draw_modifiers(
@@ -416,6 +434,12 @@ impl MenuRender {
                         );
                     }
                 } else if ui.levels.state.visible {
+                    self.ui.draw_input(&ui.levels.search, framebuffer);
+                    self.ui
+                        .draw_toggle_button(&ui.levels.sort, false, false, theme, framebuffer);
+                    self.ui
+                        .draw_checkbox(&ui.levels.recommended, theme, framebuffer);
+
                     mask.mask_quad(ui.levels.items_state.position);
                     self.ui.draw_text(&ui.levels.status, &mut mask.color);
                     for item in &ui.levels.items {
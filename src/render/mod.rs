@@ -23,7 +23,7 @@ pub fn smooth_button(button: &HoverButton, time: Time) -> HoverButton {
 
     let mut button = button.clone();
     button.base_collider = button.base_collider.transformed(Transform {
-        scale: t,
+        scale: vec2::splat(t),
         ..default()
     });
     button
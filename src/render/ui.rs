@@ -675,6 +675,10 @@ impl UiRender {
         let logged = &ui.logged;
         if logged.state.visible {
             self.draw_text(&logged.username, framebuffer);
+            self.draw_text(&logged.stats, framebuffer);
+            for score in &logged.scores {
+                self.draw_text(score, framebuffer);
+            }
             self.draw_toggle_button(&logged.logout, false, false, theme, framebuffer);
         }
     }
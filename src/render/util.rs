@@ -248,10 +248,11 @@ impl UtilRender {
             return;
         }
 
-        let shape = match light.collider.shape {
+        let shape = match &light.collider.shape {
             Shape::Circle { .. } => Shape::circle(radius),
             Shape::Line { .. } => Shape::line(radius / r32(2.0)),
             Shape::Rectangle { .. } => Shape::rectangle(vec2::splat(radius)),
+            shape => shape.scaled(vec2::splat(radius / shape.radius().max(r32(0.01)))),
         };
         let waypoint = Collider {
             shape,
@@ -267,22 +268,47 @@ impl UtilRender {
         camera: &impl geng::AbstractCamera2d,
         framebuffer: &mut ugli::Framebuffer,
     ) {
-        let (texture, transform) = match collider.shape {
+        let (texture, transform) = match &collider.shape {
             Shape::Circle { radius } => (
                 &self.context.assets.sprites.radial_gradient,
                 mat3::scale_uniform(radius.as_f32()),
             ),
+            Shape::Ellipse { radius } => (
+                &self.context.assets.sprites.radial_gradient,
+                mat3::scale(radius.as_f32()),
+            ),
             Shape::Line { width } => {
-                let inf = 999.0;
+                let length = view_reach(camera, framebuffer.size().as_f32(), collider.position);
                 (
                     &self.context.assets.sprites.linear_gradient,
-                    mat3::scale(vec2(inf, width.as_f32()) / 2.0),
+                    mat3::scale(vec2(length, width.as_f32() / 2.0)),
                 )
             }
             Shape::Rectangle { width, height } => (
                 &self.context.assets.sprites.linear_gradient,
                 mat3::scale(vec2(width.as_f32(), height.as_f32()) / 2.0),
             ),
+            &Shape::Ring { radius, width } => {
+                let half_angle = Angle::from_radians(r32(f32::PI));
+                let sdf = LightSdf::ring(radius, width, half_angle);
+                self.draw_light_sdf(collider, sdf, color, camera, framebuffer);
+                return;
+            }
+            &Shape::Arc {
+                radius,
+                width,
+                angle,
+            } => {
+                let half_angle = Angle::from_radians(angle.as_radians() / r32(2.0));
+                let sdf = LightSdf::ring(radius, width, half_angle);
+                self.draw_light_sdf(collider, sdf, color, camera, framebuffer);
+                return;
+            }
+            Shape::Polygon { points } => {
+                let sdf = LightSdf::polygon(points);
+                self.draw_light_sdf(collider, sdf, color, camera, framebuffer);
+                return;
+            }
         };
         let transform = mat3::translate(collider.position.as_f32())
             * mat3::rotate(collider.rotation.map(Coord::as_f32))
@@ -309,6 +335,43 @@ impl UtilRender {
         );
     }
 
+    /// Draw a shape using a signed distance field, fading out towards its edges.
+    fn draw_light_sdf(
+        &self,
+        collider: &Collider,
+        sdf: Option<LightSdf>,
+        color: Color,
+        camera: &impl geng::AbstractCamera2d,
+        framebuffer: &mut ugli::Framebuffer,
+    ) {
+        let Some(sdf) = sdf else {
+            return;
+        };
+        let transform = mat3::translate(collider.position.as_f32())
+            * mat3::rotate(collider.rotation.map(Coord::as_f32))
+            * mat3::scale_uniform(sdf.scale);
+
+        let framebuffer_size = framebuffer.size();
+        ugli::draw(
+            framebuffer,
+            &self.context.assets.shaders.light_sdf,
+            ugli::DrawMode::TriangleFan,
+            &self.unit_quad,
+            (
+                ugli::uniforms! {
+                    u_model_matrix: transform,
+                    u_color: color,
+                },
+                &sdf,
+                camera.uniforms(framebuffer_size.as_f32()),
+            ),
+            ugli::DrawParameters {
+                blend_mode: Some(additive()),
+                ..default()
+            },
+        );
+    }
+
     pub fn draw_outline(
         &self,
         collider: &Collider,
@@ -317,7 +380,7 @@ impl UtilRender {
         camera: &impl geng::AbstractCamera2d,
         framebuffer: &mut ugli::Framebuffer,
     ) {
-        match collider.shape {
+        match &collider.shape {
             Shape::Circle { radius } => {
                 self.context.geng.draw2d().draw2d(
                     framebuffer,
@@ -331,14 +394,14 @@ impl UtilRender {
                 );
             }
             Shape::Line { width } => {
-                let inf = 1e3; // camera.fov;
+                let length = view_reach(camera, framebuffer.size().as_f32(), collider.position);
                 self.context.geng.draw2d().draw2d(
                     framebuffer,
                     camera,
                     &draw2d::Segment::new(
                         Segment(
-                            vec2(-inf * 2.0, (width.as_f32() - outline_width) / 2.0),
-                            vec2(inf * 2.0, (width.as_f32() - outline_width) / 2.0),
+                            vec2(-length, (width.as_f32() - outline_width) / 2.0),
+                            vec2(length, (width.as_f32() - outline_width) / 2.0),
                         ),
                         outline_width,
                         color,
@@ -351,8 +414,8 @@ impl UtilRender {
                     camera,
                     &draw2d::Segment::new(
                         Segment(
-                            vec2(-inf * 2.0, -(width.as_f32() - outline_width) / 2.0),
-                            vec2(inf * 2.0, -(width.as_f32() - outline_width) / 2.0),
+                            vec2(-length, -(width.as_f32() - outline_width) / 2.0),
+                            vec2(length, -(width.as_f32() - outline_width) / 2.0),
                         ),
                         outline_width,
                         color,
@@ -361,6 +424,83 @@ impl UtilRender {
                     .translate(collider.position.as_f32()),
                 );
             }
+            &Shape::Ring { radius, width } => {
+                for radius in [radius, radius - width + r32(outline_width)] {
+                    if radius <= Coord::ZERO {
+                        continue;
+                    }
+                    self.context.geng.draw2d().draw2d(
+                        framebuffer,
+                        camera,
+                        &draw2d::Ellipse::circle_with_cut(
+                            collider.position.as_f32(),
+                            radius.as_f32() - outline_width,
+                            radius.as_f32(),
+                            color,
+                        ),
+                    );
+                }
+            }
+            &Shape::Arc {
+                radius,
+                width,
+                angle,
+            } => {
+                let outer = radius.as_f32() - outline_width / 2.0;
+                let inner = (radius - width).as_f32().max(0.0) + outline_width / 2.0;
+                let half_angle = angle.as_radians().as_f32() / 2.0;
+                let resolution = 32;
+                let arc = |radius: f32| {
+                    (0..=resolution).map(move |i| {
+                        let t = i as f32 / resolution as f32;
+                        Angle::from_radians(-half_angle + half_angle * 2.0 * t).unit_vec() * radius
+                    })
+                };
+                let mut points: Vec<_> = arc(outer).collect();
+                points.extend(arc(inner).rev());
+                points.push(points[0]);
+                self.context.geng.draw2d().draw2d(
+                    framebuffer,
+                    camera,
+                    &draw2d::Chain::new(Chain::new(points), outline_width, color, 1)
+                        .rotate(collider.rotation.map(Coord::as_f32))
+                        .translate(collider.position.as_f32()),
+                );
+            }
+            &Shape::Ellipse { radius } => {
+                let radius = radius.as_f32() - vec2::splat(outline_width / 2.0);
+                let resolution = 32;
+                let points: Vec<_> = (0..=resolution)
+                    .map(|i| {
+                        let angle = i as f32 / resolution as f32 * f32::PI * 2.0;
+                        Angle::from_radians(angle).unit_vec() * radius
+                    })
+                    .collect();
+                self.context.geng.draw2d().draw2d(
+                    framebuffer,
+                    camera,
+                    &draw2d::Chain::new(Chain::new(points), outline_width, color, 1)
+                        .rotate(collider.rotation.map(Coord::as_f32))
+                        .translate(collider.position.as_f32()),
+                );
+            }
+            Shape::Polygon { points } => {
+                let mut points: Vec<_> = Shape::convex_hull(points)
+                    .into_iter()
+                    .map(|point| point.as_f32())
+                    .collect();
+                let Some(&first) = points.first() else {
+                    return;
+                };
+                points.push(first);
+                self.context.geng.draw2d().draw2d(
+                    framebuffer,
+                    camera,
+                    &draw2d::Chain::new(Chain::new(points), outline_width, color, 1)
+                        .rotate(collider.rotation.map(Coord::as_f32))
+                        .translate(collider.position.as_f32()),
+                );
+            }
             Shape::Rectangle { width, height } => {
                 let [a, b, c, d] = Aabb2::ZERO
                     .extend_symmetric(vec2(width.as_f32(), height.as_f32()) / 2.0)
@@ -617,6 +757,120 @@ impl UtilRender {
     }
 }
 
+/// Maximum number of polygon points the `light_sdf` shader accepts.
+const MAX_POLYGON_POINTS: usize = 32;
+
+/// Parameters of the `light_sdf` shader, relative to a quad of size `scale`.
+struct LightSdf {
+    scale: f32,
+    /// Distance from the edge at which the light is fully lit.
+    depth: f32,
+    inner: f32,
+    half_angle: f32,
+    points: Vec<vec2<f32>>,
+}
+
+impl LightSdf {
+    fn ring(radius: Coord, width: Coord, half_angle: Angle<Coord>) -> Option<Self> {
+        let radius = radius.as_f32();
+        if radius <= 0.0 {
+            return None;
+        }
+        let inner = ((radius - width.as_f32()) / radius).max(0.0);
+        Some(Self {
+            scale: radius,
+            // Brightest in the middle of the band, like the gradients
+            depth: (1.0 - inner) / 2.0,
+            inner,
+            half_angle: half_angle.as_radians().as_f32(),
+            points: Vec::new(),
+        })
+    }
+
+    fn polygon(points: &[vec2<Coord>]) -> Option<Self> {
+        // Same as the collider
+        let mut hull: Vec<vec2<f32>> = Shape::convex_hull(points)
+            .into_iter()
+            .map(|point| point.as_f32())
+            .collect();
+        if hull.len() < 3 {
+            return None;
+        }
+        if hull.len() > MAX_POLYGON_POINTS {
+            // Keep an evenly spread subset, which is still convex
+            hull = (0..MAX_POLYGON_POINTS)
+                .map(|i| hull[i * hull.len() / MAX_POLYGON_POINTS])
+                .collect();
+        }
+
+        let scale = hull.iter().map(|point| point.len()).fold(0.0, f32::max);
+        if scale <= 0.0 {
+            return None;
+        }
+        let points: Vec<_> = hull.iter().map(|&point| point / scale).collect();
+
+        // Fully lit at the center, same as the radial gradient
+        let center = points
+            .iter()
+            .copied()
+            .fold(vec2::ZERO, |sum, point| sum + point)
+            / points.len() as f32;
+        let depth = points
+            .iter()
+            .zip(points.iter().cycle().skip(1))
+            .map(|(&a, &b)| {
+                let normal = (b - a).rotate_90().normalize_or_zero();
+                vec2::dot(a - center, normal).abs()
+            })
+            .fold(f32::INFINITY, f32::min);
+        if depth <= 0.0 {
+            return None;
+        }
+
+        Some(Self {
+            scale,
+            depth,
+            inner: 0.0,
+            half_angle: 0.0,
+            points,
+        })
+    }
+}
+
+impl ugli::Uniforms for LightSdf {
+    fn walk_uniforms<C>(&self, visitor: &mut C)
+    where
+        C: ugli::UniformVisitor,
+    {
+        visitor.visit("u_depth", &self.depth);
+        visitor.visit("u_inner", &self.inner);
+        visitor.visit("u_half_angle", &self.half_angle);
+        visitor.visit("u_point_count", &(self.points.len() as i32));
+        if let Some(&first) = self.points.first() {
+            for i in 0..MAX_POLYGON_POINTS {
+                let point = self.points.get(i).copied().unwrap_or(first);
+                visitor.visit(&format!("u_points[{i}]"), &point);
+            }
+        }
+    }
+}
+
+/// Distance from `position` to the furthest visible point,
+/// so that lines drawn this far in both directions cross the whole view.
+fn view_reach(
+    camera: &impl geng::AbstractCamera2d,
+    framebuffer_size: vec2<f32>,
+    position: vec2<Coord>,
+) -> f32 {
+    let position = position.as_f32();
+    Aabb2::ZERO
+        .extend_positive(framebuffer_size)
+        .corners()
+        .into_iter()
+        .map(|corner| (camera.screen_to_world(framebuffer_size, corner) - position).len())
+        .fold(0.0, f32::max)
+}
+
 pub fn additive() -> ugli::BlendMode {
     ugli::BlendMode::combined(ugli::ChannelBlendMode {
         src_factor: ugli::BlendFactor::One,
@@ -1,4 +1,4 @@
-use ctl_client::core::types::{GroupInfo, Id, MusicInfo};
+use ctl_client::core::types::{GroupInfo, GroupsQuery, GroupsSort, Id, MusicInfo};
 
 use super::*;
 
@@ -42,9 +42,17 @@ pub struct ExploreWidget {
 pub struct ExploreLevelsWidget {
     assets: Rc<Assets>,
     pub state: WidgetState,
+    pub search: InputWidget,
+    pub sort: TextWidget,
+    pub recommended: CheckboxWidget,
+    /// Query of the shown list, changed by the controls above it.
+    query: GroupsQuery,
     pub status: TextWidget,
     pub scroll: f32,
     pub target_scroll: f32,
+    /// Number of items when the next page was requested,
+    /// so that it is requested once per page while scrolled to the bottom.
+    next_requested: Option<usize>,
     pub items_state: WidgetState,
     pub items: Vec<LevelItemWidget>,
 }
@@ -135,7 +143,7 @@ impl StatefulWidget for ExploreWidget {
 
         if std::mem::take(&mut self.refetch) {
             state.fetch_music();
-            state.fetch_groups();
+            state.fetch_groups(self.levels.query.clone());
         }
 
         self.state.update(position, context);
@@ -217,9 +225,14 @@ impl ExploreLevelsWidget {
         Self {
             assets: assets.clone(),
             state: WidgetState::new(),
+            search: InputWidget::new("Search", false),
+            sort: TextWidget::new(""),
+            recommended: CheckboxWidget::new("Recommended"),
+            query: GroupsQuery::default(),
             status: TextWidget::new("Offline"),
             scroll: 0.0,
             target_scroll: 0.0,
+            next_requested: None,
             items_state: WidgetState::new(),
             items: Vec::new(),
         }
@@ -285,7 +298,11 @@ impl StatefulWidget for ExploreLevelsWidget {
         }
         self.state.update(position, context);
 
-        let main = position;
+        let mut main = position;
+
+        let mut controls = main.cut_top(context.font_size * 1.2);
+        main.cut_top(context.layout_size);
+        self.update_controls(&mut controls, context, state);
 
         self.items_state.update(main, context);
         self.status.update(main, context);
@@ -323,7 +340,76 @@ impl StatefulWidget for ExploreLevelsWidget {
             };
             self.target_scroll -= overflow * (context.delta_time / 0.2).min(1.0);
             self.scroll += (self.target_scroll - self.scroll) * (context.delta_time / 0.1).min(1.0);
+
+            // Load the next page when scrolled to the bottom
+            if !self.items.is_empty() && self.target_scroll <= -max_scroll + row.height() {
+                if self.next_requested != Some(self.items.len()) {
+                    self.next_requested = Some(self.items.len());
+                    state.fetch_groups_next();
+                }
+            } else {
+                // Scrolling away from the bottom and back retries a failed request
+                self.next_requested = None;
+            }
+        }
+    }
+}
+
+impl ExploreLevelsWidget {
+    /// Update the search, filter and sort controls,
+    /// and fetch the list again when they change.
+    fn update_controls(
+        &mut self,
+        controls: &mut Aabb2<f32>,
+        context: &mut UiContext,
+        state: &LevelCache,
+    ) {
+        let width = controls.width();
+        let spacing = context.layout_size;
+
+        let search = controls.cut_left(width * 0.45);
+        controls.cut_left(spacing);
+        self.search.update(search, context);
+
+        let sort = controls.cut_left(width * 0.25);
+        controls.cut_left(spacing);
+        self.sort.update(sort, context);
+        if self.sort.state.clicked {
+            self.query.sort = match self.query.sort {
+                GroupsSort::Newest => GroupsSort::MostPlayed,
+                GroupsSort::MostPlayed => GroupsSort::Name,
+                GroupsSort::Name => GroupsSort::Newest,
+            };
+            self.refetch(state);
+        }
+        self.sort.text = match self.query.sort {
+            GroupsSort::Newest => "Newest",
+            GroupsSort::MostPlayed => "Most played",
+            GroupsSort::Name => "By name",
         }
+        .into();
+
+        self.recommended.update(*controls, context);
+        if self.recommended.state.clicked {
+            self.query.recommended = !self.query.recommended;
+            self.refetch(state);
+        }
+        self.recommended.checked = self.query.recommended;
+
+        // Search once the text is entered
+        let search = self.search.raw.trim();
+        let search = (!search.is_empty()).then(|| search.to_owned());
+        if !self.search.editing && search != self.query.search {
+            self.query.search = search;
+            self.refetch(state);
+        }
+    }
+
+    fn refetch(&mut self, state: &LevelCache) {
+        self.scroll = 0.0;
+        self.target_scroll = 0.0;
+        self.next_requested = None;
+        state.fetch_groups(self.query.clone());
     }
 }
 
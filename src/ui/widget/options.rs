@@ -105,7 +105,7 @@ impl StatefulWidget for OptionsWidget {
 
         let mut main = position.extend_symmetric(vec2(-1.0, -1.0) * context.layout_size);
 
-        let profile = main.cut_top(3.0 * context.font_size);
+        let profile = main.cut_top(ProfileWidget::ROWS as f32 * context.font_size);
         self.profile
             .update(profile, context, &mut state.leaderboard);
 
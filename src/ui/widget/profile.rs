@@ -23,10 +23,19 @@ pub struct RegisterWidget {
 pub struct LoggedWidget {
     pub state: WidgetState,
     pub username: TextWidget,
+    /// Play count and uploads from the public profile.
+    pub stats: TextWidget,
+    /// Best scores from the public profile.
+    pub scores: Vec<TextWidget>,
     pub logout: TextWidget,
 }
 
 impl ProfileWidget {
+    /// Number of best scores shown.
+    pub const BEST_SCORES: usize = 3;
+    /// Height of the widget in lines of text.
+    pub const ROWS: usize = 3 + Self::BEST_SCORES;
+
     pub fn new(assets: &Rc<Assets>) -> Self {
         Self {
             state: WidgetState::new(),
@@ -44,6 +53,10 @@ impl ProfileWidget {
             logged: LoggedWidget {
                 state: WidgetState::new(),
                 username: TextWidget::new("<username>"),
+                stats: TextWidget::new(""),
+                scores: (0..Self::BEST_SCORES)
+                    .map(|_| TextWidget::new(""))
+                    .collect(),
                 logout: TextWidget::new("Logout"),
             },
         }
@@ -155,11 +168,39 @@ impl StatefulWidget for LoggedWidget {
             self.username.text = user.name.clone();
         }
 
+        match &state.profile {
+            Some(profile) => {
+                let levels: usize = profile.groups.iter().map(|group| group.levels.len()).sum();
+                self.stats.show();
+                self.stats.text = format!("{} plays, {} levels", profile.plays, levels).into();
+            }
+            None => self.stats.hide(),
+        }
+
+        let mut best = state.profile.iter().flat_map(|profile| &profile.scores);
+        for widget in &mut self.scores {
+            match best.next() {
+                Some(score) => {
+                    widget.show();
+                    widget.text = format!(
+                        "{} - {} {}",
+                        score.music_name, score.level_name, score.score
+                    )
+                    .into();
+                }
+                None => widget.hide(),
+            }
+        }
+
         let main = position;
 
-        let rows = main.split_rows(2);
+        let rows = main.split_rows(ProfileWidget::ROWS);
         self.username.update(rows[0], context);
-        self.logout.update(rows[1], context);
+        self.stats.update(rows[1], context);
+        for (score, row) in self.scores.iter_mut().zip(&rows[2..]) {
+            score.update(*row, &mut context.scale_font(0.8));
+        }
+        self.logout.update(rows[ProfileWidget::ROWS - 1], context);
 
         if self.logout.state.clicked {
             state.logout();
@@ -9,7 +9,7 @@ use crate::{
 };
 
 use ctl_client::{
-    core::types::{GroupInfo, Id, LevelSet},
+    core::types::{GroupInfo, Id, LevelSet, Name, UserQuota},
     ClientError, Nertboard,
 };
 use generational_arena::Index;
@@ -32,11 +32,17 @@ pub struct SyncWidget {
     pub close: IconButtonWidget,
     pub title: TextWidget,
     pub status: TextWidget,
+    /// Summary of the local changes compared to the server version.
+    pub changes: TextWidget,
     pub upload: TextWidget,
     pub discard: TextWidget,
     pub response: TextWidget,
 
+    /// Difference between the local and the server versions, if known.
+    diff: Option<GroupDiff>,
+
     task_group_info: TaskRes<GroupInfo>,
+    task_quota: TaskRes<UserQuota>,
     /// Returns group and level index and the new group and level id.
     task_group_upload: TaskRes<(Index, GroupInfo)>,
     task_group_download: TaskRes<(LevelSet, GroupInfo)>,
@@ -64,11 +70,15 @@ impl SyncWidget {
             close: IconButtonWidget::new_close_button(&assets.sprites.button_close),
             title: TextWidget::new("Synchronizing level"),
             status: TextWidget::new("Offline"),
+            changes: TextWidget::new(""),
             upload: TextWidget::new("Upload to the server"),
             discard: TextWidget::new("Download new version"),
             response: TextWidget::new(""),
 
+            diff: None,
+
             task_group_info: None,
+            task_quota: None,
             task_group_upload: None,
             task_group_download: None,
         }
@@ -79,7 +89,7 @@ impl SyncWidget {
         let future = async move {
             let info = client.get_group_info(group_id).await?;
             let bytes = client.download_group(group_id).await?;
-            let group: LevelSet = bincode::deserialize(&bytes)?;
+            let group = LevelSet::decode(&bytes)?;
             Ok((group, info))
         };
         self.task_group_download = Some(Task::new(&self.geng, future));
@@ -89,9 +99,6 @@ impl SyncWidget {
         let group = (*self.cached_group).clone();
         let group_index = self.cached_group_index;
         let future = async move {
-            // TODO: it could happen that a level has a local non-zero id
-            // but is not present on the server.
-            // In that case, upload will fail with "Not found"
             let group = client.upload_group(&group.data).await?;
             Ok((group_index, group))
         };
@@ -112,11 +119,16 @@ impl StatefulWidget for SyncWidget {
         if std::mem::take(&mut self.reload) && self.task_group_info.is_none() {
             if let Some(client) = local.client() {
                 let group_id = self.cached_group.data.id;
+                self.diff = None;
                 if group_id == 0 {
                     self.status.text = "Level is local".into();
                     self.response.hide();
                     self.upload.show();
                     self.discard.hide();
+
+                    // Warn before the upload fails
+                    let future = async move { client.get_quota().await };
+                    self.task_quota = Some(Task::new(&self.geng, future));
                 } else {
                     let future = async move { client.get_group_info(group_id).await };
                     self.task_group_info = Some(Task::new(&self.geng, future));
@@ -148,8 +160,21 @@ impl StatefulWidget for SyncWidget {
                         self.status.text = "Outdated".into();
                         self.response.hide();
 
-                        if group.owner.id == self.cached_group.data.owner.id {
-                            // if current user is the author - upload new version ; discard changes
+                        let diff = GroupDiff::new(&self.cached_group, &group);
+                        if diff.unknown > 0 {
+                            // The server would reject the upload
+                            self.response.show();
+                            self.response.text =
+                                "Some difficulties are unknown to the server".into();
+                        }
+
+                        let can_edit = state
+                            .leaderboard
+                            .user
+                            .as_ref()
+                            .map_or(false, |user| group.can_edit(user.id));
+                        if can_edit && diff.unknown == 0 {
+                            // if current user is an author - upload new version ; discard changes
 
                             self.upload.show();
                         } else {
@@ -158,6 +183,7 @@ impl StatefulWidget for SyncWidget {
                         }
 
                         self.discard.show();
+                        self.diff = Some(diff);
                     } else {
                         // Everything's fine
                         self.status.text = "Up to date".into();
@@ -168,6 +194,41 @@ impl StatefulWidget for SyncWidget {
                 }
             }
         }
+        if let Some(task) = self.task_quota.take() {
+            match task.poll() {
+                Err(task) => self.task_quota = Some(task),
+                Ok(Err(err)) => {
+                    log::warn!("Failed to fetch the upload quota: {:?}", err);
+                }
+                Ok(Ok(quota)) => {
+                    let group = &self.cached_group.data;
+                    let size = group.encode().map_or(0, |data| data.len());
+                    let problem = if quota.remaining() == 0 {
+                        Some("You cannot upload more levels")
+                    } else if quota.remaining_for(group.music) == 0 {
+                        Some("You cannot upload more levels for that song")
+                    } else if size > quota.group_size {
+                        Some("The level is too large to upload")
+                    } else {
+                        None
+                    };
+                    match problem {
+                        Some(problem) => {
+                            self.response.show();
+                            self.response.text = problem.into();
+                            self.upload.hide();
+                        }
+                        None => {
+                            self.status.text = format!(
+                                "Level is local, {} uploads left",
+                                quota.remaining_for(group.music)
+                            )
+                            .into();
+                        }
+                    }
+                }
+            }
+        }
         if let Some(task) = self.task_group_upload.take() {
             match task.poll() {
                 Err(task) => self.task_group_upload = Some(task),
@@ -248,6 +309,16 @@ impl StatefulWidget for SyncWidget {
         let status = main.cut_top(context.font_size);
         self.status.update(status, context);
 
+        match &self.diff {
+            Some(diff) if self.upload.state.visible => {
+                self.changes.show();
+                self.changes.text = diff.summary().into();
+            }
+            _ => self.changes.hide(),
+        }
+        let changes = main.cut_top(context.font_size * 0.8);
+        self.changes.update(changes, &mut context.scale_font(0.8));
+
         main.cut_top(context.layout_size * 1.0);
 
         let button_size = vec2(main.width() * 0.75, context.font_size * 1.3);
@@ -263,10 +334,18 @@ impl StatefulWidget for SyncWidget {
                 if self.cached_group.data.id == 0 {
                     state.popup_confirm(ConfirmAction::SyncUpload, "You cannot undo this action");
                 } else {
-                    state.popup_confirm(
-                        ConfirmAction::SyncUpload,
-                        "Uploading a new version will reset leaderboards of all difficulties",
-                    );
+                    let removed = self.diff.as_ref().map_or(0, |diff| diff.removed.len());
+                    let message = if removed > 0 {
+                        format!(
+                            "Uploading a new version will delete {} difficulties with their scores \
+                             and reset leaderboards of the changed ones",
+                            removed
+                        )
+                    } else {
+                        "Uploading a new version will reset leaderboards of changed difficulties"
+                            .to_string()
+                    };
+                    state.popup_confirm(ConfirmAction::SyncUpload, message);
                 }
             } else {
                 state
@@ -299,3 +378,76 @@ impl StatefulWidget for SyncWidget {
         self.response.options.color = context.theme().danger;
     }
 }
+
+/// Changes to the levels of a group compared to its server version.
+struct GroupDiff {
+    added: usize,
+    changed: usize,
+    /// Names of the levels that will be deleted from the server.
+    removed: Vec<Name>,
+    /// Levels with an id that the server does not know about.
+    unknown: usize,
+}
+
+impl GroupDiff {
+    fn new(local: &CachedGroup, server: &GroupInfo) -> Self {
+        let mut diff = Self {
+            added: 0,
+            changed: 0,
+            removed: Vec::new(),
+            unknown: 0,
+        };
+
+        for (level, hash) in local.data.levels.iter().zip(&local.level_hashes) {
+            if level.meta.id == 0 {
+                diff.added += 1;
+                continue;
+            }
+            match server.levels.iter().find(|info| info.id == level.meta.id) {
+                None => diff.unknown += 1,
+                Some(info) => {
+                    if info.hash != *hash {
+                        diff.changed += 1;
+                    }
+                }
+            }
+        }
+
+        diff.removed = server
+            .levels
+            .iter()
+            .filter(|info| {
+                !local
+                    .data
+                    .levels
+                    .iter()
+                    .any(|level| level.meta.id == info.id)
+            })
+            .map(|info| info.name.clone())
+            .collect();
+
+        diff
+    }
+
+    fn summary(&self) -> String {
+        let mut parts = Vec::new();
+        if self.added > 0 {
+            parts.push(format!("{} new", self.added));
+        }
+        if self.changed > 0 {
+            parts.push(format!("{} changed", self.changed));
+        }
+        if !self.removed.is_empty() {
+            parts.push(format!(
+                "{} removed ({})",
+                self.removed.len(),
+                self.removed.join(", ")
+            ));
+        }
+        if parts.is_empty() {
+            "No difficulty changes".to_string()
+        } else {
+            parts.join(", ")
+        }
+    }
+}
@@ -1,10 +1,12 @@
 use super::*;
 
 use crate::{
-    editor::{LevelEditor, LightId},
+    editor::{beat_division, LevelEditor, LightId},
     prelude::*,
 };
 
+use ctl_client::core::analysis::Waveform;
+
 use std::collections::BTreeMap;
 
 pub struct TimelineWidget {
@@ -25,6 +27,16 @@ pub struct TimelineWidget {
     pub lights: BTreeMap<Time, Vec<(LightId, WidgetState)>>,
     pub selected: WidgetState,
     pub waypoints: Vec<(WaypointId, WidgetState)>,
+    /// Markers of the tempo changes.
+    pub tempo: Vec<(Time, WidgetState)>,
+    /// Grid lines at the beats and their subdivisions.
+    pub ticks: Vec<Aabb2<f32>>,
+    /// Number of subdivisions of a beat in the grid.
+    pub beat_divisor: u32,
+    /// Bars of the music amplitude.
+    pub waveform: Vec<Aabb2<f32>>,
+    /// Markers of the detected onsets in the music.
+    pub onsets: Vec<Aabb2<f32>>,
 
     /// Render scale in pixels per beat.
     scale: f32,
@@ -35,6 +47,10 @@ pub struct TimelineWidget {
     raw_right: Option<Time>,
     raw_replay: Option<Time>,
     level: Level, // TODO: reuse existing
+    music: MusicInfo,
+    /// Analysis of the music, once it is ready.
+    analysis: Option<Rc<Waveform>>,
+    /// The light whose movement is shown, if exactly one is selected.
     selected_light: Option<LightId>,
     selected_waypoint: Option<WaypointId>,
 }
@@ -67,6 +83,11 @@ impl TimelineWidget {
             lights: BTreeMap::new(),
             selected: WidgetState::new(),
             waypoints: Vec::new(),
+            tempo: Vec::new(),
+            ticks: Vec::new(),
+            beat_divisor: 4,
+            waveform: Vec::new(),
+            onsets: Vec::new(),
 
             scale: 15.0,
             scroll: Time::ZERO,
@@ -75,6 +96,8 @@ impl TimelineWidget {
             raw_right: None,
             raw_replay: None,
             level: Level::new(),
+            music: MusicInfo::default(),
+            analysis: None,
             selected_light: None,
             selected_waypoint: None,
         }
@@ -219,7 +242,11 @@ impl TimelineWidget {
                 state.update(position, &self.context);
                 if state.clicked {
                     if let Some(editor) = &mut editor {
-                        editor.selected_light = Some(light_id);
+                        if self.context.mods.shift {
+                            editor.selection.toggle(light_id);
+                        } else {
+                            editor.selection.select(light_id);
+                        }
                         editor.level_state.waypoints = None;
                     }
                 }
@@ -228,6 +255,98 @@ impl TimelineWidget {
             }
         }
 
+        self.tempo.clear();
+        let size = vec2(0.1, 0.6) * self.context.font_size;
+        for section in &self.music.tempo.sections {
+            let mut state = WidgetState::new();
+            let position = render_time(section.beat).center();
+            let position = Aabb2::point(position)
+                .extend_symmetric(vec2(size.x, 0.0) / 2.0)
+                .extend_up(size.y);
+            state.update(position, &self.context);
+            if state.clicked {
+                if let Some(editor) = &mut editor {
+                    editor.scroll_time(section.beat - editor.current_beat, self.beat_divisor);
+                }
+            }
+            self.tempo.push((section.beat, state));
+        }
+
+        self.ticks.clear();
+        {
+            let area = self.state.position;
+            let center = self.clickable.position.center().y;
+            let divisor = self.beat_divisor.max(1);
+            let division = beat_division(divisor);
+            // Hide the subdivisions when they get too dense
+            let subdivisions = self.scale * division.as_f32() >= self.context.font_size * 0.2;
+
+            let first = (-self.scroll).max(Time::ZERO);
+            let last = -self.scroll + self.visible_scroll();
+            let mut i = (first / division).as_f32().ceil() as u32;
+            loop {
+                let beat = division * r32(i as f32);
+                if beat > last {
+                    break;
+                }
+                let is_beat = i % divisor == 0;
+                if is_beat || subdivisions {
+                    let size = if is_beat {
+                        vec2(0.05, 0.5)
+                    } else {
+                        vec2(0.03, 0.25)
+                    } * self.context.font_size;
+                    let x = render_time(beat).center().x;
+                    if area.min.x <= x && x <= area.max.x {
+                        self.ticks
+                            .push(Aabb2::point(vec2(x, center)).extend_symmetric(size / 2.0));
+                    }
+                }
+                i += 1;
+            }
+        }
+
+        self.waveform.clear();
+        self.onsets.clear();
+        if let Some(analysis) = &self.analysis {
+            let area = self.state.position;
+            let center = self.clickable.position.center().y;
+            let max_height = self.context.font_size * 0.5;
+
+            // One bar per column, so that zooming does not change the bar width
+            let width = (self.context.font_size * 0.1).max(1.0);
+            let screen_to_beat = |x: f32| r32((x - area.min.x) / self.scale) - self.scroll;
+            let mut x = area.min.x;
+            while x < area.max.x {
+                let from = self.music.beat_to_music_time(screen_to_beat(x)).as_f32();
+                let to = self
+                    .music
+                    .beat_to_music_time(screen_to_beat(x + width))
+                    .as_f32();
+                if to > 0.0 {
+                    let height = analysis.peak(from, to) * max_height;
+                    self.waveform.push(
+                        Aabb2::point(vec2(x + width / 2.0, center))
+                            .extend_symmetric(vec2(width * 0.8, height * 2.0) / 2.0),
+                    );
+                }
+                x += width;
+            }
+
+            let size = vec2(0.05, 0.3) * self.context.font_size;
+            for &onset in &analysis.onsets {
+                let beat = self.music.time_to_beat(r32(onset) - self.music.offset);
+                let x = render_time(beat).center().x;
+                if area.min.x <= x && x <= area.max.x {
+                    self.onsets.push(
+                        Aabb2::point(vec2(x, center))
+                            .extend_symmetric(vec2(size.x, 0.0) / 2.0)
+                            .extend_up(size.y),
+                    );
+                }
+            }
+        }
+
         let render_option = |widget: &mut WidgetState, time: Option<Time>| match time {
             Some(time) => {
                 widget.show();
@@ -263,7 +382,9 @@ impl StatefulWidget for TimelineWidget {
 
         self.context = context.clone();
         self.level = state.level.clone();
-        self.selected_light = state.selected_light;
+        self.music = state.static_level.group.music.meta.clone();
+        self.analysis = context.context.local.music_waveform(self.music.id);
+        self.selected_light = state.selection.single();
         self.selected_waypoint = state
             .level_state
             .waypoints